@@ -0,0 +1,171 @@
+//! Prints a table of `agg_dec` latency against committee size.
+//!
+//! For each `n` this runs setup, encryption and aggregation with a minimal
+//! quorum: the dummy party plus `t = n / 2` others. Each phase is also traced
+//! with `start_timer!`.
+//!
+//! ```text
+//! bench_decrypt [--sizes 8,16,32] [--params-file ./params.bin]
+//! ```
+//!
+//! With `--params-file` the given `PowersOfTau` (e.g. from a ceremony) are
+//! used for every size and Lagrange powers are derived without tau, which is
+//! much slower than sampling tau locally.
+
+use ark_ec::pairing::Pairing;
+use ark_poly::univariate::DensePolynomial;
+use ark_std::{end_timer, start_timer, UniformRand, Zero};
+use silent_threshold_encryption::{
+    container,
+    decryption::agg_dec,
+    encryption::encrypt,
+    kzg::{PowersOfTau, KZG10},
+    rng::SecureRng,
+    setup::{required_degree, AggregateKey, LagrangePowers, SecretKey},
+    utils::parties_to_selector,
+};
+use std::error::Error;
+use std::time::Instant;
+
+type E = ark_bls12_381::Bls12_381;
+type Fr = <E as Pairing>::ScalarField;
+type G2 = <E as Pairing>::G2;
+type UniPoly381 = DensePolynomial<Fr>;
+
+const DEFAULT_SIZES: &[usize] = &[8, 16, 32, 64, 128];
+
+const USAGE: &str = "usage: bench_decrypt [--sizes 8,16,32] [--params-file PATH]";
+
+struct Args {
+    sizes: Vec<usize>,
+    params_file: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args {
+        sizes: DEFAULT_SIZES.to_vec(),
+        params_file: None,
+    };
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or(format!("{} needs a value\n{}", arg, USAGE))
+        };
+        match arg.as_str() {
+            "--sizes" => {
+                parsed.sizes = value()?
+                    .split(',')
+                    .map(|n| n.trim().parse().map_err(|_| format!("bad size {:?}", n)))
+                    .collect::<Result<_, _>>()?;
+            }
+            "--params-file" => parsed.params_file = Some(value()?),
+            _ => return Err(format!("unknown argument {:?}\n{}", arg, USAGE)),
+        }
+    }
+    Ok(parsed)
+}
+
+/// Where the KZG parameters and Lagrange powers for each size come from.
+enum Source {
+    Tau(Fr),
+    Params(PowersOfTau<E>),
+}
+
+impl Source {
+    fn setup(&self, n: usize) -> Result<(PowersOfTau<E>, LagrangePowers<E>), Box<dyn Error>> {
+        match self {
+            Source::Tau(tau) => Ok((
+                KZG10::<E, UniPoly381>::setup(n, *tau)?,
+                LagrangePowers::new(*tau, n)?,
+            )),
+            Source::Params(params) => {
+                if params.max_degree() < required_degree(n) {
+                    return Err(format!(
+                        "parameters have degree {}, n = {} needs {}",
+                        params.max_degree(),
+                        n,
+                        required_degree(n)
+                    )
+                    .into());
+                }
+                Ok((
+                    params.clone(),
+                    LagrangePowers::from_powers_of_tau(params, n)?,
+                ))
+            }
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = parse_args(std::env::args().skip(1))?;
+    let mut rng = SecureRng::new();
+
+    let source = match &args.params_file {
+        Some(path) => {
+            let params: PowersOfTau<E> = container::load_file(path)?;
+            params.validate_lengths()?;
+            Source::Params(params)
+        }
+        None => Source::Tau(Fr::rand(&mut rng)),
+    };
+
+    let mut rows = Vec::with_capacity(args.sizes.len());
+    for &n in &args.sizes {
+        let t = n / 2;
+
+        let timer = start_timer!(|| format!("Setup for n = {}", n));
+        let started = Instant::now();
+        let (params, lagrange_params) = source.setup(n)?;
+        let mut sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::new(&mut rng)).collect();
+        sk[0].nullify();
+        let pk = sk
+            .iter()
+            .enumerate()
+            .map(|(i, sk)| sk.lagrange_get_pk(i, &lagrange_params, n))
+            .collect::<Result<_, _>>()?;
+        let agg_key = AggregateKey::<E>::new(pk, &params)?;
+        let setup_time = started.elapsed();
+        end_timer!(timer);
+
+        let timer = start_timer!(|| format!("Encrypt for n = {}, t = {}", n, t));
+        let started = Instant::now();
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng)?;
+        let encrypt_time = started.elapsed();
+        end_timer!(timer);
+
+        let quorum: Vec<usize> = (0..=t).collect();
+        let selector = parties_to_selector(&quorum, n)?;
+        let mut partial_decryptions = vec![G2::zero(); n];
+        for &i in &quorum {
+            partial_decryptions[i] = sk[i].partial_decryption(&ct);
+        }
+
+        let timer = start_timer!(|| format!("agg_dec for n = {}, t = {}", n, t));
+        let started = Instant::now();
+        let dec_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params)?;
+        let agg_dec_time = started.elapsed();
+        end_timer!(timer);
+
+        if dec_key != ct.enc_key {
+            return Err(format!("decryption failed for n = {}", n).into());
+        }
+        rows.push((n, t, setup_time, encrypt_time, agg_dec_time));
+    }
+
+    println!(
+        "\n{:>6} {:>6} {:>14} {:>14} {:>14}",
+        "n", "t", "setup (ms)", "encrypt (ms)", "agg_dec (ms)"
+    );
+    for (n, t, setup_time, encrypt_time, agg_dec_time) in rows {
+        println!(
+            "{:>6} {:>6} {:>14.2} {:>14.2} {:>14.2}",
+            n,
+            t,
+            setup_time.as_secs_f64() * 1e3,
+            encrypt_time.as_secs_f64() * 1e3,
+            agg_dec_time.as_secs_f64() * 1e3
+        );
+    }
+    Ok(())
+}
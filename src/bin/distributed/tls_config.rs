@@ -100,8 +100,8 @@ pub fn save_cert_and_key(
     std::fs::write(cert_path, cert.cert.pem())?;
     std::fs::write(key_path, cert.key_pair.serialize_pem())?;
 
-    println!("✓ Certificate saved to: {}", cert_path);
-    println!("✓ Private key saved to: {}", key_path);
+    say!("✓ Certificate saved to: {}", cert_path);
+    say!("✓ Private key saved to: {}", key_path);
 
     Ok(())
 }
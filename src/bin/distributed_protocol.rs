@@ -34,7 +34,8 @@
 //!    - Ciphertext is broadcast to all parties
 //!
 //! 3. **Decryption Phase**:
-//!    - Coordinator selects t+1 parties for decryption
+//!    - Coordinator selects t+1 parties for decryption: party 0 plus the
+//!      lowest IDs, or random ones with `--selection random`
 //!    - Selected parties compute partial decryptions
 //!    - Parties send partial decryptions to coordinator
 //!    - Coordinator aggregates and recovers the message
@@ -65,25 +66,103 @@ mod distributed {
     use ark_poly::univariate::DensePolynomial;
     use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
     use ark_std::{rand::RngCore, UniformRand, Zero};
-    use bincode::{deserialize, serialize};
+    use bincode::{serialize, Options};
     use blake2::{Blake2b512, Digest};
-    use clap::{Parser, Subcommand};
-    use rand::{rngs::StdRng, SeedableRng};
+    use clap::{Parser, Subcommand, ValueEnum};
     use serde::{Deserialize, Serialize};
     use silent_threshold_encryption::{
-        decryption::agg_dec,
+        container,
+        decryption::{agg_dec, batch_verify_partials},
         encryption::{encrypt, Ciphertext},
+        error::SteError,
         kzg::{PowersOfTau, KZG10},
+        rng::SecureRng,
         security::SensitiveScalar,
-        setup::{AggregateKey, LagrangePowers, PublicKey, SecretKey},
+        setup::{
+            required_degree, AggregateKey, CommitteeSizeLimit, LagrangePowers, PublicKey, SecretKey,
+        },
+        utils::parties_to_selector,
     };
-    use std::collections::HashMap;
+    use std::collections::{HashMap, HashSet};
+    use std::sync::atomic::{AtomicBool, Ordering};
     use std::sync::Arc;
-    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use std::time::Instant;
+    use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt, WriteHalf};
     use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::{mpsc, Notify};
     use tokio_rustls::TlsAcceptor;
     use tokio_rustls::TlsConnector;
 
+    // ============================================================================
+    // Logging
+    // ============================================================================
+
+    /// How the binary reports progress.
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+    pub enum LogFormat {
+        /// Decorated lines for people watching a terminal
+        #[default]
+        Human,
+        /// One JSON object per line, with structured `phase` events
+        Json,
+    }
+
+    static JSON_LOGS: AtomicBool = AtomicBool::new(false);
+
+    /// Reports a progress line: printed as is, or in JSON mode logged as the
+    /// message of an event without its leading emoji.
+    macro_rules! say {
+        ($($arg:tt)*) => {
+            $crate::distributed::say(format_args!($($arg)*))
+        };
+    }
+
+    pub(crate) fn say(args: std::fmt::Arguments) {
+        if JSON_LOGS.load(Ordering::Relaxed) {
+            let text = args.to_string();
+            let text = text.trim_start_matches(|c: char| !c.is_ascii_alphanumeric());
+            tracing::info!("{}", text);
+        } else {
+            println!("{}", args);
+        }
+    }
+
+    /// Section header, shown only in the human format.
+    fn banner(title: &str) {
+        if !JSON_LOGS.load(Ordering::Relaxed) {
+            println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            println!("{}", title);
+            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+        }
+    }
+
+    /// Emits a structured event for a finished phase. Without a subscriber,
+    /// i.e. in the human format, this is a no-op.
+    fn phase_done(phase: &'static str, party_id: Option<usize>, started: Instant) {
+        let duration_ms = started.elapsed().as_secs_f64() * 1e3;
+        tracing::info!(phase, party_id, duration_ms, "phase complete");
+    }
+
+    /// A subscriber writing one flat JSON object per event to `writer`.
+    fn json_subscriber<W>(writer: W) -> impl tracing::Subscriber + Send + Sync
+    where
+        W: for<'a> tracing_subscriber::fmt::MakeWriter<'a> + Send + Sync + 'static,
+    {
+        tracing_subscriber::fmt()
+            .json()
+            .flatten_event(true)
+            .with_writer(writer)
+            .finish()
+    }
+
+    fn init_logging(format: LogFormat) -> Result<(), Box<dyn std::error::Error>> {
+        if format == LogFormat::Json {
+            JSON_LOGS.store(true, Ordering::Relaxed);
+            tracing::subscriber::set_global_default(json_subscriber(std::io::stdout))?;
+        }
+        Ok(())
+    }
+
     mod tls_config;
 
     type E = ark_bls12_381::Bls12_381;
@@ -95,11 +174,32 @@ mod distributed {
     // Protocol Messages
     // ============================================================================
 
+    /// Version of the wire format below, exchanged in the handshake: the
+    /// party's `Ready` and the coordinator's `RequestPublicKey`.
+    ///
+    /// Messages are bincode-encoded, which identifies variants by position
+    /// and has no field names, so new variants must only be appended and any
+    /// other change to a message (including adding a field) requires bumping
+    /// this version. `test_message_schema_snapshot` catches accidental changes.
+    pub const PROTOCOL_VERSION: u32 = 1;
+
+    /// Rejects a peer speaking a different wire format.
+    fn check_protocol_version(peer_version: u32) -> Result<(), String> {
+        if peer_version != PROTOCOL_VERSION {
+            return Err(format!(
+                "incompatible protocol version {} (this build speaks {})",
+                peer_version, PROTOCOL_VERSION
+            ));
+        }
+        Ok(())
+    }
+
     /// Messages sent from coordinator to parties
     #[derive(Serialize, Deserialize, Debug, Clone)]
     pub enum CoordinatorMessage {
         /// Request party to generate and send their public key
         RequestPublicKey {
+            protocol_version: u32,
             party_id: usize,
             lagrange_bytes: Vec<u8>, // Serialized Lagrange powers
             lagrange_hash: [u8; 32],
@@ -109,8 +209,14 @@ mod distributed {
         Ciphertext {
             ct_bytes: Vec<u8>, // Serialized ciphertext
         },
-        /// Request partial decryption from selected parties
-        RequestPartialDecryption { party_id: usize, ct_bytes: Vec<u8> },
+        /// Request partial decryption from selected parties. `request_id` is
+        /// bound to the ciphertext hash and the party (see
+        /// [`partial_decryption_request_id`]).
+        RequestPartialDecryption {
+            party_id: usize,
+            request_id: [u8; 32],
+            ct_bytes: Vec<u8>,
+        },
         /// Notify party of successful completion
         Success { message: String },
         /// Notify party of error
@@ -131,47 +237,149 @@ mod distributed {
             pd_bytes: Vec<u8>, // Serialized G2 element
         },
         /// Party ready and waiting for commands
-        Ready { party_id: usize },
+        Ready {
+            protocol_version: u32,
+            party_id: usize,
+        },
         /// Party encountered an error
         Error { party_id: usize, message: String },
     }
 
-    // ============================================================================
-    // Secure RNG (same as client demo)
-    // ============================================================================
-
-    struct SecureRng {
-        inner: StdRng,
+    /// Largest frame accepted from a peer. `RequestPublicKey` carries the
+    /// Lagrange powers, `O(n^2)` group elements: about 200 MiB for n = 2048.
+    const MAX_MESSAGE_LEN: u32 = 256 << 20;
+
+    /// Decodes a bincode message from untrusted bytes.
+    ///
+    /// Uses the same encoding as `bincode::serialize`, but rejects trailing
+    /// bytes and never allocates more than the input could describe, so it
+    /// returns an error rather than panicking or exhausting memory on any input.
+    fn decode_checked<T: serde::de::DeserializeOwned>(bytes: &[u8]) -> Result<T, SteError> {
+        bincode::DefaultOptions::new()
+            .with_fixint_encoding()
+            .with_limit(bytes.len() as u64)
+            .deserialize(bytes)
+            .map_err(|e| SteError::SerializationError(format!("malformed message: {}", e)))
     }
 
-    impl SecureRng {
-        fn new() -> Self {
-            use rand::RngCore;
-            let mut seed = [0u8; 32];
-            rand::rng().fill_bytes(&mut seed);
-            SecureRng {
-                inner: StdRng::from_seed(seed),
-            }
+    impl CoordinatorMessage {
+        /// Decodes a message received from the coordinator; see [`decode_checked`].
+        pub fn from_bytes_checked(bytes: &[u8]) -> Result<Self, SteError> {
+            decode_checked(bytes)
         }
     }
 
-    impl RngCore for SecureRng {
-        fn next_u32(&mut self) -> u32 {
-            <StdRng as rand::RngCore>::next_u32(&mut self.inner)
+    impl PartyMessage {
+        /// Decodes a message received from a party; see [`decode_checked`].
+        pub fn from_bytes_checked(bytes: &[u8]) -> Result<Self, SteError> {
+            decode_checked(bytes)
         }
+    }
 
-        fn next_u64(&mut self) -> u64 {
-            <StdRng as rand::RngCore>::next_u64(&mut self.inner)
+    /// Reads one length-prefixed frame, refusing lengths above `MAX_MESSAGE_LEN`
+    /// before allocating.
+    async fn read_frame<R: AsyncRead + Unpin>(reader: &mut R) -> std::io::Result<Vec<u8>> {
+        let len = reader.read_u32().await?;
+        if len > MAX_MESSAGE_LEN {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "frame of {} bytes exceeds limit of {}",
+                    len, MAX_MESSAGE_LEN
+                ),
+            ));
         }
+        let mut data = vec![0u8; len as usize];
+        reader.read_exact(&mut data).await?;
+        Ok(data)
+    }
+
+    /// Domain separator for partial decryption request ids
+    const REQUEST_ID_DST: &[u8] = b"STE-DISTRIBUTED-PD-REQUEST-v1";
+
+    /// Truncated Blake2b-512 digest used for all protocol hashes
+    fn digest32(bytes: &[u8]) -> [u8; 32] {
+        let digest_vec = Blake2b512::digest(bytes);
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(&digest_vec[..32]);
+        digest
+    }
+
+    /// Request id for asking `party_id` to decrypt the ciphertext with hash `ct_hash`
+    fn partial_decryption_request_id(ct_hash: &[u8; 32], party_id: usize) -> [u8; 32] {
+        let mut hasher = Blake2b512::new();
+        hasher.update(REQUEST_ID_DST);
+        hasher.update(ct_hash);
+        hasher.update((party_id as u64).to_le_bytes());
+        let mut request_id = [0u8; 32];
+        request_id.copy_from_slice(&hasher.finalize()[..32]);
+        request_id
+    }
+
+    // ============================================================================
+    // Randomness
+    // ============================================================================
 
-        fn fill_bytes(&mut self, dest: &mut [u8]) {
-            <StdRng as rand::RngCore>::fill_bytes(&mut self.inner, dest)
+    /// Deterministic RNG for `--seed` runs if a seed was given, otherwise OS
+    /// randomness. Seeded runs are INSECURE: anyone who knows the seed can
+    /// recompute tau and every secret key.
+    ///
+    /// `stream` keeps the coordinator's and each party's draws independent.
+    fn rng_for_stream(seed: Option<u64>, stream: u64) -> SecureRng {
+        match seed {
+            Some(seed) => {
+                let mut bytes = [0u8; 32];
+                bytes[..8].copy_from_slice(&seed.to_le_bytes());
+                bytes[8..16].copy_from_slice(&stream.to_le_bytes());
+                SecureRng::from_seed(bytes)
+            }
+            None => SecureRng::new(),
         }
+    }
 
-        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), ark_std::rand::Error> {
-            self.fill_bytes(dest);
-            Ok(())
+    /// RNG streams used with `--seed`; party `i` uses `PARTY_RNG_STREAM + i`
+    const SETUP_RNG_STREAM: u64 = 0;
+    const ENCRYPT_RNG_STREAM: u64 = 1;
+    const PARTY_RNG_STREAM: u64 = 2;
+    /// Kept clear of the party streams above it
+    const SELECTION_RNG_STREAM: u64 = u64::MAX;
+
+    // ============================================================================
+    // Party Selection
+    // ============================================================================
+
+    /// How the coordinator picks the `t + 1` parties asked to decrypt
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+    pub enum SelectionStrategy {
+        /// Party 0 and parties `1..=t`
+        #[default]
+        LowestId,
+        /// Party 0 and `t` parties drawn uniformly from the rest, spreading
+        /// the load across sessions
+        Random,
+    }
+
+    /// The parties to request partial decryptions from, in ascending order.
+    /// Party 0 (the dummy party) is always included.
+    fn select_parties_for_decryption<R: RngCore>(
+        n: usize,
+        t: usize,
+        strategy: SelectionStrategy,
+        rng: &mut R,
+    ) -> Vec<usize> {
+        let k = t.min(n - 1);
+        let mut others: Vec<usize> = (1..n).collect();
+        if strategy == SelectionStrategy::Random {
+            // Partial Fisher-Yates: the first k entries become a uniform sample
+            for i in 0..k {
+                let j = i + (rng.next_u64() % (others.len() - i) as u64) as usize;
+                others.swap(i, j);
+            }
         }
+        let mut selected = vec![0];
+        selected.extend_from_slice(&others[..k]);
+        selected.sort_unstable();
+        selected
     }
 
     // ============================================================================
@@ -182,14 +390,186 @@ mod distributed {
         n: usize,
         t: usize,
         port: u16,
-        kzg_params: PowersOfTau<E>,
+        kzg_params: Arc<PowersOfTau<E>>,
         lagrange_bytes: Vec<u8>,
         lagrange_hash: [u8; 32],
         public_keys: HashMap<usize, PublicKey<E>>,
         partial_decryptions: HashMap<usize, G2>,
-        party_connections: HashMap<usize, tokio_rustls::server::TlsStream<TcpStream>>,
+        /// Write halves of the party connections; the read halves are owned
+        /// by the reader tasks feeding `inbox`
+        party_connections: HashMap<usize, WriteHalf<tokio_rustls::server::TlsStream<TcpStream>>>,
+        inbox_tx: mpsc::UnboundedSender<InboxItem>,
+        inbox: mpsc::UnboundedReceiver<InboxItem>,
         cert_path: Option<String>,
         key_path: Option<String>,
+        seed: Option<u64>,
+        selection: SelectionStrategy,
+        shutdown: Arc<Notify>,
+    }
+
+    /// A message (or read error) from one party connection
+    type InboxItem = (usize, Result<PartyMessage, String>);
+
+    /// Read length-prefixed `PartyMessage`s from `reader` on its own task and
+    /// forward them to `inbox`, tagged with `party_id`.
+    ///
+    /// The task stops after the first read or decode error, which is
+    /// forwarded as well, or once the receiving side is dropped.
+    fn spawn_party_reader<R>(
+        party_id: usize,
+        mut reader: R,
+        inbox: mpsc::UnboundedSender<InboxItem>,
+    ) where
+        R: AsyncRead + Unpin + Send + 'static,
+    {
+        tokio::spawn(async move {
+            loop {
+                let result = async {
+                    let data = read_frame(&mut reader).await.map_err(|e| e.to_string())?;
+                    PartyMessage::from_bytes_checked(&data).map_err(|e| e.to_string())
+                }
+                .await;
+                let failed = result.is_err();
+                if inbox.send((party_id, result)).is_err() || failed {
+                    return;
+                }
+            }
+        });
+    }
+
+    /// Wait for the next message from any party.
+    async fn receive_from_inbox(
+        inbox: &mut mpsc::UnboundedReceiver<InboxItem>,
+    ) -> Result<(usize, PartyMessage), Box<dyn std::error::Error>> {
+        match inbox.recv().await {
+            Some((party_id, Ok(msg))) => Ok((party_id, msg)),
+            Some((party_id, Err(e))) => {
+                Err(format!("Failed to read from party {}: {}", party_id, e).into())
+            }
+            None => Err("All party connections closed".into()),
+        }
+    }
+
+    /// Records a partial decryption received on `sender`'s connection.
+    ///
+    /// The share must come from a party that was asked for one, under its own
+    /// id. Returns `Ok(false)` for a resent copy of a share already recorded.
+    /// A second, different share from the same party is equivocation and is
+    /// rejected.
+    fn accept_partial_decryption(
+        shares: &mut HashMap<usize, G2>,
+        requested: &[usize],
+        sender: usize,
+        claimed_id: usize,
+        pd: G2,
+    ) -> Result<bool, String> {
+        if claimed_id != sender {
+            return Err(format!(
+                "Party {} sent a partial decryption as party {}",
+                sender, claimed_id
+            ));
+        }
+        if !requested.contains(&sender) {
+            return Err(format!(
+                "Party {} sent a partial decryption it was not asked for",
+                sender
+            ));
+        }
+        match shares.get(&sender) {
+            None => {
+                shares.insert(sender, pd);
+                Ok(true)
+            }
+            Some(existing) if *existing == pd => Ok(false),
+            Some(_) => Err(format!(
+                "Party {} equivocated: sent two different partial decryptions",
+                sender
+            )),
+        }
+    }
+
+    /// Rejects the run if any party's partial decryption fails the BLS check.
+    fn check_partial_decryptions(
+        shares: &HashMap<usize, G2>,
+        agg_key: &AggregateKey<E>,
+        ct: &Ciphertext<E>,
+    ) -> Result<(), String> {
+        let invalid = batch_verify_partials(shares, agg_key, ct);
+        if !invalid.is_empty() {
+            return Err(format!(
+                "Invalid partial decryptions from parties {:?}",
+                invalid
+            ));
+        }
+        Ok(())
+    }
+
+    /// Run `encrypt` on the blocking thread pool so large committees don't
+    /// stall the executor.
+    ///
+    /// Returns `Ok(None)` if `cancel` is notified before encryption finishes;
+    /// the caller must then not send the ciphertext anywhere. The ciphertext
+    /// is identical to calling `encrypt` directly with the same RNG.
+    async fn encrypt_cancellable<R: RngCore + Send + 'static>(
+        agg_key: Arc<AggregateKey<E>>,
+        t: usize,
+        params: Arc<PowersOfTau<E>>,
+        mut rng: R,
+        cancel: &Notify,
+    ) -> Result<Option<Ciphertext<E>>, Box<dyn std::error::Error>> {
+        let task =
+            tokio::task::spawn_blocking(move || encrypt::<E, _>(&*agg_key, t, &params, &mut rng));
+
+        tokio::select! {
+            biased;
+            _ = cancel.notified() => Ok(None),
+            res = task => Ok(Some(res??)),
+        }
+    }
+
+    /// The IDs in `0..n` that are not keys of `present`, in ascending order
+    fn missing_party_ids<V>(n: usize, present: &HashMap<usize, V>) -> Vec<usize> {
+        (0..n).filter(|id| !present.contains_key(id)).collect()
+    }
+
+    /// How long an interrupted coordinator waits for the session to finish
+    const SHUTDOWN_GRACE: std::time::Duration = std::time::Duration::from_secs(30);
+
+    /// How a session ended under [`run_with_graceful_shutdown`]
+    #[derive(Debug, PartialEq, Eq)]
+    enum SessionOutcome {
+        /// The session ran to completion (possibly after a shutdown request)
+        Completed,
+        /// The grace period expired before the session finished
+        Incomplete,
+    }
+
+    /// Drive `session` to completion, honouring a shutdown `signal`.
+    ///
+    /// On the signal, `shutdown` is notified so no new ciphertext is sent
+    /// (see [`encrypt_cancellable`]), and a decryption already in flight gets
+    /// up to `grace` to finish before the session is reported incomplete.
+    async fn run_with_graceful_shutdown<S, F>(
+        session: S,
+        shutdown: &Notify,
+        signal: F,
+        grace: std::time::Duration,
+    ) -> Result<SessionOutcome, Box<dyn std::error::Error>>
+    where
+        S: std::future::Future<Output = Result<(), Box<dyn std::error::Error>>>,
+        F: std::future::Future<Output = ()>,
+    {
+        tokio::pin!(session);
+        tokio::select! {
+            res = &mut session => return res.map(|_| SessionOutcome::Completed),
+            _ = signal => {}
+        }
+
+        shutdown.notify_one();
+        match tokio::time::timeout(grace, session).await {
+            Ok(res) => res.map(|_| SessionOutcome::Completed),
+            Err(_) => Ok(SessionOutcome::Incomplete),
+        }
     }
 
     impl Coordinator {
@@ -199,51 +579,99 @@ mod distributed {
             t: usize,
             cert_path: Option<String>,
             key_path: Option<String>,
+            seed: Option<u64>,
+            params_file: Option<String>,
         ) -> Result<Self, Box<dyn std::error::Error>> {
-            println!("🔧 Coordinator: Initializing with n={}, t={}", n, t);
+            let started = Instant::now();
+            say!("🔧 Coordinator: Initializing with n={}, t={}", n, t);
+            if seed.is_some() {
+                say!("⚠️ Coordinator: WARNING - deterministic --seed mode is INSECURE, use for tests and demos only");
+            }
+            CommitteeSizeLimit::default().check(n)?;
+
+            let (kzg_params, lagrange_params) = match params_file {
+                Some(path) => {
+                    say!("🔧 Coordinator: Loading KZG parameters from {}", path);
+                    let kzg_params: PowersOfTau<E> = container::load_file(&path)?;
+                    kzg_params.validate_lengths()?;
+                    if kzg_params.max_degree() < required_degree(n) {
+                        return Err(format!(
+                            "Parameters in {} have degree {}, need at least {}",
+                            path,
+                            kzg_params.max_degree(),
+                            required_degree(n)
+                        )
+                        .into());
+                    }
 
-            let mut rng = SecureRng::new();
-            let tau_raw = Fr::rand(&mut rng);
-            let tau = SensitiveScalar::new(tau_raw);
+                    say!("🔧 Coordinator: Deriving Lagrange powers from parameters...");
+                    let lagrange_params = LagrangePowers::<E>::from_powers_of_tau(&kzg_params, n)?;
+                    (kzg_params, lagrange_params)
+                }
+                None => {
+                    let mut rng = rng_for_stream(seed, SETUP_RNG_STREAM);
+                    let tau_raw = Fr::rand(&mut rng);
+                    let tau = SensitiveScalar::new(tau_raw);
 
-            println!("🔧 Coordinator: Setting up KZG parameters...");
-            let kzg_params = KZG10::<E, UniPoly381>::setup(n, *tau.expose_secret())?;
+                    say!("🔧 Coordinator: Setting up KZG parameters...");
+                    let kzg_params = KZG10::<E, UniPoly381>::setup(n, *tau.expose_secret())?;
 
-            println!("🔧 Coordinator: Preprocessing Lagrange powers...");
-            let lagrange_params = LagrangePowers::<E>::new(*tau.expose_secret(), n)?;
+                    say!("🔧 Coordinator: Preprocessing Lagrange powers...");
+                    let lagrange_params = LagrangePowers::<E>::new(*tau.expose_secret(), n)?;
+                    (kzg_params, lagrange_params)
+                }
+            };
             let mut lagrange_bytes = Vec::new();
             lagrange_params.serialize_compressed(&mut lagrange_bytes)?;
-            let lagrange_hash_vec = Blake2b512::digest(&lagrange_bytes);
-            let mut lagrange_hash = [0u8; 32];
-            lagrange_hash.copy_from_slice(&lagrange_hash_vec[..32]);
+            let lagrange_hash = digest32(&lagrange_bytes);
 
-            println!("✓ Coordinator: Setup complete");
+            say!("✓ Coordinator: Setup complete");
+            phase_done("setup", None, started);
 
+            let (inbox_tx, inbox) = mpsc::unbounded_channel();
             Ok(Self {
                 n,
                 t,
                 port,
-                kzg_params,
+                kzg_params: Arc::new(kzg_params),
                 lagrange_bytes,
                 lagrange_hash,
                 public_keys: HashMap::new(),
                 partial_decryptions: HashMap::new(),
                 party_connections: HashMap::new(),
+                inbox_tx,
+                inbox,
                 cert_path,
                 key_path,
+                seed,
+                selection: SelectionStrategy::default(),
+                shutdown: Arc::new(Notify::new()),
             })
         }
 
+        /// Use `strategy` to choose the decrypting parties
+        pub fn with_selection(mut self, strategy: SelectionStrategy) -> Self {
+            self.selection = strategy;
+            self
+        }
+
+        /// Handle that tears down the session; an encryption still in flight
+        /// (or not yet started) is abandoned and its ciphertext never sent to
+        /// the parties.
+        pub fn shutdown_handle(&self) -> Arc<Notify> {
+            self.shutdown.clone()
+        }
+
         pub async fn run(&mut self) -> Result<(), Box<dyn std::error::Error>> {
             // Load or generate certificate for TLS
-            println!("🔐 Coordinator: Preparing TLS certificate...");
+            say!("🔐 Coordinator: Preparing TLS certificate...");
             let (certs, key) = match (&self.cert_path, &self.key_path) {
                 (Some(cert_path), Some(key_path)) => {
-                    println!("🔐 Coordinator: Loading certificate from {}", cert_path);
+                    say!("🔐 Coordinator: Loading certificate from {}", cert_path);
                     tls_config::load_cert_and_key(cert_path, key_path)?
                 }
                 (None, None) => {
-                    println!("⚠️ Coordinator: No certificate/key provided. Generating self-signed certificate (share its PEM with parties for pinning).");
+                    say!("⚠️ Coordinator: No certificate/key provided. Generating self-signed certificate (share its PEM with parties for pinning).");
                     tls_config::generate_self_signed_cert()?
                 }
                 _ => {
@@ -252,12 +680,12 @@ mod distributed {
             };
             let tls_config = tls_config::create_server_config(certs, key)?;
             let acceptor = TlsAcceptor::from(tls_config);
-            println!("✓ Coordinator: TLS certificate ready");
+            say!("✓ Coordinator: TLS certificate ready");
 
             let addr = format!("127.0.0.1:{}", self.port);
             let listener = TcpListener::bind(&addr).await?;
-            println!("🌐 Coordinator: Listening on {} (TLS 1.3)", addr);
-            println!(
+            say!("🌐 Coordinator: Listening on {} (TLS 1.3)", addr);
+            say!(
                 "⏳ Coordinator: Waiting for {} parties to connect...",
                 self.n
             );
@@ -265,59 +693,77 @@ mod distributed {
             // Accept connections from all n parties
             for i in 0..self.n {
                 let (tcp_stream, peer_addr) = listener.accept().await?;
-                println!(
+                say!(
                     "🔌 Coordinator: TCP connection from {} (party {})",
-                    peer_addr, i
+                    peer_addr,
+                    i
                 );
 
                 // Perform TLS handshake
                 let tls_stream = acceptor.accept(tcp_stream).await?;
-                println!(
+                say!(
                     "✓ Coordinator: Party {} connected with TLS from {}",
-                    i, peer_addr
+                    i,
+                    peer_addr
                 );
-                self.party_connections.insert(i, tls_stream);
+                let (reader, writer) = tokio::io::split(tls_stream);
+                spawn_party_reader(i, reader, self.inbox_tx.clone());
+                self.party_connections.insert(i, writer);
             }
 
-            println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            println!("Phase 1: Key Generation");
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            banner("Phase 1: Key Generation");
+            let started = Instant::now();
 
             // Request public keys from all parties
             self.request_public_keys().await?;
 
             // Compute aggregate key
-            println!("\n🔧 Coordinator: Computing aggregate key...");
+            say!("\n🔧 Coordinator: Computing aggregate key...");
             let pk_vec: Vec<PublicKey<E>> =
                 (0..self.n).map(|i| self.public_keys[&i].clone()).collect();
-            let agg_key = AggregateKey::<E>::new(pk_vec, &self.kzg_params)?;
-            println!("✓ Coordinator: Aggregate key computed");
+            let agg_key = Arc::new(AggregateKey::<E>::new(pk_vec, &self.kzg_params)?);
+            say!("✓ Coordinator: Aggregate key computed");
+            phase_done("keygen", None, started);
 
-            println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            println!("Phase 2: Encryption");
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            banner("Phase 2: Encryption");
+            let started = Instant::now();
 
             // Encrypt a message
-            let mut rng = SecureRng::new();
-            println!(
+            say!(
                 "🔐 Coordinator: Encrypting message with threshold t={}...",
                 self.t
             );
-            let ct = encrypt::<E, _>(&agg_key, self.t, &self.kzg_params, &mut rng)?;
-            println!("✓ Coordinator: Ciphertext generated");
-            println!("  Encrypted key: {:?}", ct.enc_key);
+            let ct = match encrypt_cancellable(
+                agg_key.clone(),
+                self.t,
+                self.kzg_params.clone(),
+                rng_for_stream(self.seed, ENCRYPT_RNG_STREAM),
+                &self.shutdown,
+            )
+            .await?
+            {
+                Some(ct) => ct,
+                None => {
+                    say!("⚠️ Coordinator: Encryption cancelled, nothing sent");
+                    return Ok(());
+                }
+            };
+            say!("✓ Coordinator: Ciphertext generated");
+            phase_done("encryption", None, started);
+            say!("  Encrypted key: {:?}", ct.enc_key);
 
-            println!("\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-            println!("Phase 3: Decryption");
-            println!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+            banner("Phase 3: Decryption");
+            let started = Instant::now();
 
             // Select t+1 parties for decryption (always include party 0)
-            let mut selected_parties: Vec<usize> = vec![0];
-            for i in 1..=self.t.min(self.n - 1) {
-                selected_parties.push(i);
-            }
+            let selected_parties = select_parties_for_decryption(
+                self.n,
+                self.t,
+                self.selection,
+                &mut rng_for_stream(self.seed, SELECTION_RNG_STREAM),
+            );
 
-            println!(
+            say!(
                 "🎯 Coordinator: Selected {} parties for decryption: {:?}",
                 selected_parties.len(),
                 selected_parties
@@ -326,13 +772,12 @@ mod distributed {
             // Request partial decryptions
             self.request_partial_decryptions(&ct, &selected_parties)
                 .await?;
+            check_partial_decryptions(&self.partial_decryptions, &agg_key, &ct)?;
+            say!("✓ Coordinator: All partial decryptions verified");
 
             // Aggregate and decrypt
-            println!("\n🔓 Coordinator: Aggregating partial decryptions...");
-            let mut selector = vec![false; self.n];
-            for &party_id in &selected_parties {
-                selector[party_id] = true;
-            }
+            say!("\n🔓 Coordinator: Aggregating partial decryptions...");
+            let selector = parties_to_selector(&selected_parties, self.n)?;
 
             let mut pd_vec = vec![G2::zero(); self.n];
             for (party_id, pd) in &self.partial_decryptions {
@@ -341,14 +786,15 @@ mod distributed {
 
             let dec_key = agg_dec(&pd_vec, &ct, &selector, &agg_key, &self.kzg_params)?;
 
-            println!("✓ Coordinator: Decryption complete");
-            println!("  Decrypted key: {:?}", dec_key);
+            say!("✓ Coordinator: Decryption complete");
+            phase_done("decryption", None, started);
+            say!("  Decrypted key: {:?}", dec_key);
 
             // Verify correctness
             if dec_key == ct.enc_key {
-                println!("\n✅ SUCCESS: Decryption successful! Keys match.");
+                say!("\n✅ SUCCESS: Decryption successful! Keys match.");
             } else {
-                println!("\n❌ ERROR: Decryption failed! Keys do not match.");
+                say!("\n❌ ERROR: Decryption failed! Keys do not match.");
             }
 
             // Notify all parties of success
@@ -357,15 +803,22 @@ mod distributed {
             Ok(())
         }
 
+        /// Key request for `party_id`. Parties get the precomputed Lagrange
+        /// powers, never tau itself.
+        fn public_key_request(&self, party_id: usize) -> CoordinatorMessage {
+            CoordinatorMessage::RequestPublicKey {
+                protocol_version: PROTOCOL_VERSION,
+                party_id,
+                lagrange_bytes: self.lagrange_bytes.clone(),
+                lagrange_hash: self.lagrange_hash,
+                n: self.n,
+            }
+        }
+
         async fn request_public_keys(&mut self) -> Result<(), Box<dyn std::error::Error>> {
             // Send requests to all parties
             for party_id in 0..self.n {
-                let msg = CoordinatorMessage::RequestPublicKey {
-                    party_id,
-                    lagrange_bytes: self.lagrange_bytes.clone(),
-                    lagrange_hash: self.lagrange_hash,
-                    n: self.n,
-                };
+                let msg = self.public_key_request(party_id);
                 self.send_to_party(party_id, &msg).await?;
             }
 
@@ -376,14 +829,26 @@ mod distributed {
 
                 match msg {
                     PartyMessage::PublicKey { party_id, pk_bytes } => {
-                        let pk = PublicKey::<E>::deserialize_compressed(&pk_bytes[..])?;
+                        let pk = PublicKey::<E>::deserialize_validated(&pk_bytes)?;
                         self.public_keys.insert(party_id, pk);
-                        println!("✓ Coordinator: Received public key from party {}", party_id);
+                        say!("✓ Coordinator: Received public key from party {}", party_id);
                         received += 1;
+
+                        let missing = self.missing_parties();
+                        if !missing.is_empty() {
+                            say!("  Waiting for public keys from parties {:?}", missing);
+                        }
                     }
-                    PartyMessage::Ready { party_id } => {
-                        // Ignore ready messages during key collection
-                        println!("  Party {} ready", party_id);
+                    PartyMessage::Ready {
+                        protocol_version,
+                        party_id,
+                    } => {
+                        if let Err(e) = check_protocol_version(protocol_version) {
+                            let msg = CoordinatorMessage::Error { message: e.clone() };
+                            self.send_to_party(party_id, &msg).await?;
+                            return Err(format!("Party {}: {}", party_id, e).into());
+                        }
+                        say!("  Party {} ready", party_id);
                     }
                     _ => {
                         return Err(format!(
@@ -398,6 +863,11 @@ mod distributed {
             Ok(())
         }
 
+        /// Party IDs that have not yet sent a public key, in ascending order
+        pub fn missing_parties(&self) -> Vec<usize> {
+            missing_party_ids(self.n, &self.public_keys)
+        }
+
         async fn request_partial_decryptions(
             &mut self,
             ct: &Ciphertext<E>,
@@ -406,30 +876,56 @@ mod distributed {
             // Serialize ciphertext
             let mut ct_bytes = Vec::new();
             ct.serialize_compressed(&mut ct_bytes)?;
+            let ct_hash = digest32(&ct_bytes);
+
+            // Broadcast the ciphertext so parties only answer requests for it
+            for party_id in 0..self.n {
+                let msg = CoordinatorMessage::Ciphertext {
+                    ct_bytes: ct_bytes.clone(),
+                };
+                self.send_to_party(party_id, &msg).await?;
+            }
 
             // Send requests to selected parties
             for &party_id in selected_parties {
                 let msg = CoordinatorMessage::RequestPartialDecryption {
                     party_id,
+                    request_id: partial_decryption_request_id(&ct_hash, party_id),
                     ct_bytes: ct_bytes.clone(),
                 };
                 self.send_to_party(party_id, &msg).await?;
             }
 
             // Receive partial decryptions
-            for _ in 0..selected_parties.len() {
-                let (party_id, msg) = self.receive_from_any_party().await?;
+            self.partial_decryptions.clear();
+            while self.partial_decryptions.len() < selected_parties.len() {
+                let (sender, msg) = self.receive_from_any_party().await?;
 
                 if let PartyMessage::PartialDecryption { party_id, pd_bytes } = msg {
                     let pd = G2::deserialize_compressed(&pd_bytes[..])?;
-                    self.partial_decryptions.insert(party_id, pd);
-                    println!(
-                        "✓ Coordinator: Received partial decryption from party {}",
-                        party_id
-                    );
+                    match accept_partial_decryption(
+                        &mut self.partial_decryptions,
+                        selected_parties,
+                        sender,
+                        party_id,
+                        pd,
+                    ) {
+                        Ok(true) => say!(
+                            "✓ Coordinator: Received partial decryption from party {}",
+                            sender
+                        ),
+                        Ok(false) => say!(
+                            "⚠️ Coordinator: Ignoring duplicate partial decryption from party {}",
+                            sender
+                        ),
+                        Err(e) => {
+                            say!("❌ Coordinator: {}", e);
+                            return Err(e.into());
+                        }
+                    }
                 } else {
                     return Err(
-                        format!("Unexpected message from party {}: {:?}", party_id, msg).into(),
+                        format!("Unexpected message from party {}: {:?}", sender, msg).into(),
                     );
                 }
             }
@@ -460,29 +956,7 @@ mod distributed {
         async fn receive_from_any_party(
             &mut self,
         ) -> Result<(usize, PartyMessage), Box<dyn std::error::Error>> {
-            // Simple round-robin polling (in production, use select! or similar)
-            loop {
-                for party_id in 0..self.n {
-                    if let Some(stream) = self.party_connections.get_mut(&party_id) {
-                        // Try to read with a small timeout
-                        match tokio::time::timeout(
-                            std::time::Duration::from_millis(10),
-                            stream.read_u32(),
-                        )
-                        .await
-                        {
-                            Ok(Ok(len)) => {
-                                let mut data = vec![0u8; len as usize];
-                                stream.read_exact(&mut data).await?;
-                                let msg: PartyMessage = deserialize(&data)?;
-                                return Ok((party_id, msg));
-                            }
-                            Ok(Err(e)) => return Err(e.into()),
-                            Err(_) => continue, // Timeout, try next party
-                        }
-                    }
-                }
-            }
+            receive_from_inbox(&mut self.inbox).await
         }
 
         async fn notify_all_parties(&mut self) -> Result<(), Box<dyn std::error::Error>> {
@@ -510,6 +984,9 @@ mod distributed {
         lagrange_cache: Option<([u8; 32], Arc<LagrangePowers<E>>)>,
         bad_lagrange_digest: Option<[u8; 32]>,
         secret_key: Option<SecretKey<E>>,
+        seed: Option<u64>,
+        known_ciphertexts: HashSet<[u8; 32]>,
+        answered_requests: HashSet<[u8; 32]>,
     }
 
     impl Party {
@@ -518,8 +995,15 @@ mod distributed {
             coordinator_addr: String,
             server_cert_path: Option<String>,
             allow_insecure: bool,
+            seed: Option<u64>,
         ) -> Self {
-            println!("🎭 Party {}: Initializing", id);
+            say!("🎭 Party {}: Initializing", id);
+            if seed.is_some() {
+                say!(
+                    "⚠️ Party {}: WARNING - deterministic --seed mode is INSECURE, use for tests and demos only",
+                    id
+                );
+            }
             Self {
                 id,
                 coordinator_addr,
@@ -528,20 +1012,25 @@ mod distributed {
                 lagrange_cache: None,
                 bad_lagrange_digest: None,
                 secret_key: None,
+                seed,
+                known_ciphertexts: HashSet::new(),
+                answered_requests: HashSet::new(),
             }
         }
 
         pub async fn run(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-            println!(
+            say!(
                 "🌐 Party {}: Connecting to coordinator at {}",
-                self.id, self.coordinator_addr
+                self.id,
+                self.coordinator_addr
             );
 
             // Create TLS client configuration with optional certificate pinning
             let tls_config = if let Some(cert_path) = &self.server_cert_path {
-                println!(
+                say!(
                     "🔐 Party {}: Using pinned server certificate {}",
-                    self.id, cert_path
+                    self.id,
+                    cert_path
                 );
                 let certs = tls_config::load_certs(cert_path)?;
                 tls_config::create_client_config_with_roots(certs).map_err(|e| {
@@ -555,7 +1044,7 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
                 if !self.allow_insecure {
                     return Err("Server certificate path missing. Provide --server-cert or use --allow-insecure for development".into());
                 }
-                println!(
+                say!(
                     "⚠️ Party {}: WARNING - running without server certificate verification",
                     self.id
                 );
@@ -565,16 +1054,19 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
 
             // Connect via TCP
             let tcp_stream = TcpStream::connect(&self.coordinator_addr).await?;
-            println!("🔌 Party {}: TCP connected to coordinator", self.id);
+            say!("🔌 Party {}: TCP connected to coordinator", self.id);
 
             // Perform TLS handshake
             let server_name = rustls::pki_types::ServerName::try_from("localhost")
                 .map_err(|_| "Invalid DNS name")?;
             let mut stream = connector.connect(server_name, tcp_stream).await?;
-            println!("✓ Party {}: TLS connection established", self.id);
+            say!("✓ Party {}: TLS connection established", self.id);
 
             // Send ready message
-            let ready_msg = PartyMessage::Ready { party_id: self.id };
+            let ready_msg = PartyMessage::Ready {
+                protocol_version: PROTOCOL_VERSION,
+                party_id: self.id,
+            };
             self.send_message(&mut stream, &ready_msg).await?;
 
             // Main message loop
@@ -583,6 +1075,7 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
 
                 match msg {
                     CoordinatorMessage::RequestPublicKey {
+                        protocol_version,
                         party_id,
                         lagrange_bytes,
                         lagrange_hash,
@@ -591,7 +1084,15 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
                         if party_id != self.id {
                             continue;
                         }
-                        println!("\n📨 Party {}: Received request for public key", self.id);
+                        if let Err(e) = check_protocol_version(protocol_version) {
+                            let msg = PartyMessage::Error {
+                                party_id: self.id,
+                                message: e.clone(),
+                            };
+                            self.send_message(&mut stream, &msg).await?;
+                            return Err(format!("Coordinator: {}", e).into());
+                        }
+                        say!("\n📨 Party {}: Received request for public key", self.id);
                         self.handle_public_key_request(
                             &mut stream,
                             &lagrange_bytes,
@@ -600,26 +1101,36 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
                         )
                         .await?;
                     }
-                    CoordinatorMessage::RequestPartialDecryption { party_id, ct_bytes } => {
+                    CoordinatorMessage::Ciphertext { ct_bytes } => {
+                        self.known_ciphertexts.insert(digest32(&ct_bytes));
+                    }
+                    CoordinatorMessage::RequestPartialDecryption {
+                        party_id,
+                        request_id,
+                        ct_bytes,
+                    } => {
                         if party_id != self.id {
                             continue;
                         }
-                        println!(
+                        say!(
                             "\n📨 Party {}: Received request for partial decryption",
                             self.id
                         );
+                        if let Err(reason) = self.accept_decryption_request(request_id, &ct_bytes) {
+                            say!("⚠️ Party {}: Ignoring request - {}", self.id, reason);
+                            continue;
+                        }
                         self.handle_partial_decryption_request(&mut stream, &ct_bytes)
                             .await?;
                     }
                     CoordinatorMessage::Success { message } => {
-                        println!("\n✅ Party {}: {}", self.id, message);
+                        say!("\n✅ Party {}: {}", self.id, message);
                         break;
                     }
                     CoordinatorMessage::Error { message } => {
-                        println!("\n❌ Party {}: Error - {}", self.id, message);
+                        say!("\n❌ Party {}: Error - {}", self.id, message);
                         break;
                     }
-                    _ => {}
                 }
             }
 
@@ -633,6 +1144,7 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
             lagrange_hash: [u8; 32],
             n: usize,
         ) -> Result<(), Box<dyn std::error::Error>> {
+            let started = Instant::now();
             // Obtain Lagrange parameters from cache or deserialize once
             let lagrange_params = if let Some((cached_hash, params)) = &self.lagrange_cache {
                 if cached_hash == &lagrange_hash {
@@ -644,39 +1156,72 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
                 self.load_lagrange_params(lagrange_bytes, lagrange_hash)?
             };
 
-            // Generate secret key
-            let mut rng = SecureRng::new();
+            let pk = self.generate_keys(lagrange_params.as_ref(), n)?;
+
+            // Serialize and send public key
+            let mut pk_bytes = Vec::new();
+            pk.serialize_compressed(&mut pk_bytes)?;
+
+            let response = PartyMessage::PublicKey {
+                party_id: self.id,
+                pk_bytes,
+            };
+
+            self.send_message(stream, &response).await?;
+            say!("✓ Party {}: Sent public key to coordinator", self.id);
+            phase_done("keygen", Some(self.id), started);
+
+            Ok(())
+        }
+
+        /// Generate and store this party's secret key and return its public key.
+        fn generate_keys(
+            &mut self,
+            lagrange_params: &LagrangePowers<E>,
+            n: usize,
+        ) -> Result<PublicKey<E>, Box<dyn std::error::Error>> {
+            let mut rng = rng_for_stream(self.seed, PARTY_RNG_STREAM + self.id as u64);
             let mut sk = SecretKey::<E>::new(&mut rng);
 
             // Party 0 is the dummy party
             if self.id == 0 {
                 sk.nullify();
-                println!(
+                say!(
                     "🔑 Party {}: Generated nullified secret key (dummy party)",
                     self.id
                 );
             } else {
-                println!("🔑 Party {}: Generated secret key", self.id);
+                say!("🔑 Party {}: Generated secret key", self.id);
             }
 
             // Compute public key using provided Lagrange parameters
-            let pk = sk.lagrange_get_pk(self.id, lagrange_params.as_ref(), n)?;
+            let pk = sk.lagrange_get_pk(self.id, lagrange_params, n)?;
 
             // Store secret key for later
             self.secret_key = Some(sk);
+            Ok(pk)
+        }
 
-            // Serialize and send public key
-            let mut pk_bytes = Vec::new();
-            pk.serialize_compressed(&mut pk_bytes)?;
-
-            let response = PartyMessage::PublicKey {
-                party_id: self.id,
-                pk_bytes,
-            };
-
-            self.send_message(stream, &response).await?;
-            println!("✓ Party {}: Sent public key to coordinator", self.id);
-
+        /// Check that a partial decryption request is fresh and for a
+        /// ciphertext the coordinator broadcast, then mark it answered.
+        ///
+        /// Without this a replayed request would make the party hand out a
+        /// partial decryption for a ciphertext outside the current session.
+        fn accept_decryption_request(
+            &mut self,
+            request_id: [u8; 32],
+            ct_bytes: &[u8],
+        ) -> Result<(), &'static str> {
+            let ct_hash = digest32(ct_bytes);
+            if !self.known_ciphertexts.contains(&ct_hash) {
+                return Err("ciphertext was not broadcast in this session");
+            }
+            if request_id != partial_decryption_request_id(&ct_hash, self.id) {
+                return Err("request id does not match ciphertext");
+            }
+            if !self.answered_requests.insert(request_id) {
+                return Err("request already answered (replay)");
+            }
             Ok(())
         }
 
@@ -685,8 +1230,9 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
             stream: &mut tokio_rustls::client::TlsStream<TcpStream>,
             ct_bytes: &[u8],
         ) -> Result<(), Box<dyn std::error::Error>> {
+            let started = Instant::now();
             // Deserialize ciphertext
-            let ct = Ciphertext::<E>::deserialize_compressed(ct_bytes)?;
+            let ct = Ciphertext::<E>::deserialize_validated(ct_bytes)?;
 
             // Compute partial decryption
             let sk = self
@@ -705,11 +1251,13 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
             };
 
             self.send_message(stream, &response).await?;
-            println!(
+            say!(
                 "✓ Party {}: Sent partial decryption to coordinator",
                 self.id
             );
 
+            phase_done("partial_decryption", Some(self.id), started);
+
             Ok(())
         }
 
@@ -721,14 +1269,8 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
             if bytes.is_empty() {
                 return Err("Missing Lagrange parameters payload".into());
             }
-            let digest_vec = Blake2b512::digest(bytes);
-            let mut digest = [0u8; 32];
-            digest.copy_from_slice(&digest_vec[..32]);
-            if self
-                .bad_lagrange_digest
-                .as_ref()
-                .map_or(false, |bad| bad == &digest)
-            {
+            let digest = digest32(bytes);
+            if self.bad_lagrange_digest.as_ref() == Some(&digest) {
                 return Err("Lagrange parameters previously rejected".into());
             }
             if digest != expected_hash {
@@ -736,10 +1278,7 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
                 return Err("Lagrange parameters hash mismatch".into());
             }
             let params = LagrangePowers::<E>::deserialize_compressed(bytes)
-                .map_err(|e| {
-                    self.bad_lagrange_digest = Some(digest);
-                    e
-                })?;
+                .inspect_err(|_| self.bad_lagrange_digest = Some(digest))?;
             let arc = Arc::new(params);
             self.lagrange_cache = Some((expected_hash, arc.clone()));
             self.bad_lagrange_digest = None;
@@ -765,11 +1304,8 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
             &self,
             stream: &mut tokio_rustls::client::TlsStream<TcpStream>,
         ) -> Result<CoordinatorMessage, Box<dyn std::error::Error>> {
-            let len = stream.read_u32().await?;
-            let mut data = vec![0u8; len as usize];
-            stream.read_exact(&mut data).await?;
-            let msg: CoordinatorMessage = deserialize(&data)?;
-            Ok(msg)
+            let data = read_frame(stream).await?;
+            Ok(CoordinatorMessage::from_bytes_checked(&data)?)
         }
     }
 
@@ -783,6 +1319,9 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
     struct Cli {
         #[command(subcommand)]
         command: Commands,
+        /// Output format for progress logs
+        #[arg(long, value_enum, global = true, default_value_t = LogFormat::Human)]
+        log_format: LogFormat,
     }
 
     #[derive(Subcommand)]
@@ -804,6 +1343,15 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
             /// Path to PEM-encoded private key
             #[arg(long)]
             key: Option<String>,
+            /// INSECURE, test-only: derive tau and encryption randomness from this seed
+            #[arg(long)]
+            seed: Option<u64>,
+            /// Load KZG parameters (a PowersOfTau container, e.g. from a ceremony) instead of sampling tau
+            #[arg(long)]
+            params_file: Option<String>,
+            /// How to choose the parties asked to decrypt
+            #[arg(long, value_enum, default_value_t = SelectionStrategy::LowestId)]
+            selection: SelectionStrategy,
         },
         /// Run as party client
         Party {
@@ -819,11 +1367,15 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
             /// Allow running without certificate verification (development only)
             #[arg(long, default_value_t = false)]
             allow_insecure: bool,
+            /// INSECURE, test-only: derive this party's secret key from this seed
+            #[arg(long)]
+            seed: Option<u64>,
         },
     }
 
     pub async fn main_async() -> Result<(), Box<dyn std::error::Error>> {
         let cli = Cli::parse();
+        init_logging(cli.log_format)?;
 
         match cli.command {
             Commands::Coordinator {
@@ -832,23 +1384,593 @@ Use the CA certificate that signed the coordinator's TLS certificate.",
                 threshold,
                 cert,
                 key,
+                seed,
+                params_file,
+                selection,
             } => {
-                let mut coordinator = Coordinator::new(port, parties, threshold, cert, key)?;
-                coordinator.run().await?;
+                let mut coordinator =
+                    Coordinator::new(port, parties, threshold, cert, key, seed, params_file)?
+                        .with_selection(selection);
+                let shutdown = coordinator.shutdown_handle();
+                let signal = async {
+                    let _ = tokio::signal::ctrl_c().await;
+                    say!(
+                        "\n⚠️ Coordinator: Interrupted, waiting up to {}s for the session to finish",
+                        SHUTDOWN_GRACE.as_secs()
+                    );
+                };
+                let outcome = run_with_graceful_shutdown(
+                    coordinator.run(),
+                    &shutdown,
+                    signal,
+                    SHUTDOWN_GRACE,
+                )
+                .await?;
+                if outcome == SessionOutcome::Incomplete {
+                    return Err(
+                        "Shutdown grace period expired; decryption session incomplete".into(),
+                    );
+                }
             }
             Commands::Party {
                 id,
                 coordinator,
                 server_cert,
                 allow_insecure,
+                seed,
             } => {
-                let mut party = Party::new(id, coordinator, server_cert, allow_insecure);
+                let mut party = Party::new(id, coordinator, server_cert, allow_insecure, seed);
                 party.run().await?;
             }
         }
 
         Ok(())
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ark_std::rand::{rngs::StdRng, SeedableRng};
+
+        type Committee = (Vec<SecretKey<E>>, Arc<AggregateKey<E>>, Arc<PowersOfTau<E>>);
+
+        fn setup(n: usize) -> Committee {
+            let mut rng = StdRng::seed_from_u64(7);
+            let tau = Fr::rand(&mut rng);
+            let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+            let mut sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::new(&mut rng)).collect();
+            sk[0].nullify();
+            let pk = sk
+                .iter()
+                .enumerate()
+                .map(|(i, sk)| sk.get_pk(i, &params, n).unwrap())
+                .collect();
+            let agg_key = AggregateKey::new(pk, &params).unwrap();
+            (sk, Arc::new(agg_key), Arc::new(params))
+        }
+
+        #[tokio::test]
+        async fn test_receive_from_concurrent_parties() {
+            let n = 4;
+            let (inbox_tx, mut inbox) = mpsc::unbounded_channel();
+            let mut senders = Vec::new();
+            for party_id in 0..n {
+                let (mut client, server) = tokio::io::duplex(1024);
+                spawn_party_reader(party_id, server, inbox_tx.clone());
+                senders.push(tokio::spawn(async move {
+                    for msg in [
+                        PartyMessage::Ready {
+                            protocol_version: PROTOCOL_VERSION,
+                            party_id,
+                        },
+                        PartyMessage::PublicKey {
+                            party_id,
+                            pk_bytes: vec![party_id as u8; 4096],
+                        },
+                    ] {
+                        let data = serialize(&msg).unwrap();
+                        client.write_u32(data.len() as u32).await.unwrap();
+                        client.write_all(&data).await.unwrap();
+                    }
+                    client
+                }));
+            }
+            let mut clients = Vec::new();
+            for sender in senders {
+                clients.push(sender.await.unwrap());
+            }
+
+            let mut ready = HashSet::new();
+            let mut keys = HashSet::new();
+            for _ in 0..2 * n {
+                match receive_from_inbox(&mut inbox).await.unwrap() {
+                    (from, PartyMessage::Ready { party_id, .. }) => {
+                        assert_eq!(from, party_id);
+                        ready.insert(from);
+                    }
+                    (from, PartyMessage::PublicKey { party_id, pk_bytes }) => {
+                        assert_eq!(from, party_id);
+                        assert!(pk_bytes.iter().all(|&b| b == from as u8));
+                        keys.insert(from);
+                    }
+                    other => panic!("unexpected message {:?}", other),
+                }
+            }
+            assert_eq!(ready, (0..n).collect());
+            assert_eq!(keys, (0..n).collect());
+
+            // A closed connection surfaces as an error tagged with the party
+            clients.remove(2);
+            let err = receive_from_inbox(&mut inbox).await.unwrap_err();
+            assert!(err.to_string().contains("party 2"));
+        }
+
+        /// One instance of every message, with small fixed contents.
+        fn schema_samples() -> Vec<(&'static str, Vec<u8>)> {
+            let coordinator = [
+                CoordinatorMessage::RequestPublicKey {
+                    protocol_version: PROTOCOL_VERSION,
+                    party_id: 1,
+                    lagrange_bytes: vec![0xaa],
+                    lagrange_hash: [0x11; 32],
+                    n: 4,
+                },
+                CoordinatorMessage::Ciphertext {
+                    ct_bytes: vec![0xbb],
+                },
+                CoordinatorMessage::RequestPartialDecryption {
+                    party_id: 2,
+                    request_id: [0x22; 32],
+                    ct_bytes: vec![0xcc],
+                },
+                CoordinatorMessage::Success {
+                    message: "ok".to_string(),
+                },
+                CoordinatorMessage::Error {
+                    message: "no".to_string(),
+                },
+            ];
+            let party = [
+                PartyMessage::PublicKey {
+                    party_id: 3,
+                    pk_bytes: vec![0xdd],
+                },
+                PartyMessage::PartialDecryption {
+                    party_id: 3,
+                    pd_bytes: vec![0xee],
+                },
+                PartyMessage::Ready {
+                    protocol_version: PROTOCOL_VERSION,
+                    party_id: 3,
+                },
+                PartyMessage::Error {
+                    party_id: 3,
+                    message: "no".to_string(),
+                },
+            ];
+            let names = [
+                "RequestPublicKey",
+                "Ciphertext",
+                "RequestPartialDecryption",
+                "Success",
+                "Error",
+                "PublicKey",
+                "PartialDecryption",
+                "Ready",
+                "Error",
+            ];
+            let bytes = coordinator
+                .iter()
+                .map(|msg| serialize(msg).unwrap())
+                .chain(party.iter().map(|msg| serialize(msg).unwrap()));
+            names.into_iter().zip(bytes).collect()
+        }
+
+        /// bincode encoding of `schema_samples()`. A mismatch means the wire
+        /// format changed: bump `PROTOCOL_VERSION` and update this table.
+        const SCHEMA_SNAPSHOT: [&str; 9] = [
+            "000000000100000001000000000000000100000000000000aa\
+             1111111111111111111111111111111111111111111111111111111111111111\
+             0400000000000000",
+            "010000000100000000000000bb",
+            "020000000200000000000000\
+             2222222222222222222222222222222222222222222222222222222222222222\
+             0100000000000000cc",
+            "0300000002000000000000006f6b",
+            "0400000002000000000000006e6f",
+            "0000000003000000000000000100000000000000dd",
+            "0100000003000000000000000100000000000000ee",
+            "02000000010000000300000000000000",
+            "03000000030000000000000002000000000000006e6f",
+        ];
+
+        #[test]
+        fn test_random_selection_varies_and_keeps_party_0() {
+            let (n, t) = (16, 5);
+            let mut rng = ark_std::test_rng();
+            assert_eq!(
+                select_parties_for_decryption(n, t, SelectionStrategy::LowestId, &mut rng),
+                (0..=t).collect::<Vec<_>>()
+            );
+
+            let mut seen = HashSet::new();
+            for _ in 0..50 {
+                let selected =
+                    select_parties_for_decryption(n, t, SelectionStrategy::Random, &mut rng);
+                assert_eq!(selected.len(), t + 1);
+                assert_eq!(selected[0], 0);
+                assert!(selected.windows(2).all(|w| w[0] < w[1]));
+                assert!(selected.iter().all(|&id| id < n));
+                seen.insert(selected);
+            }
+            assert!(seen.len() > 1, "random selection never changed");
+        }
+
+        #[test]
+        fn test_from_bytes_checked_never_panics() {
+            let mut rng = ark_std::test_rng();
+            let samples: Vec<Vec<u8>> = schema_samples().into_iter().map(|(_, b)| b).collect();
+            // gamma_g2 and sa2 (7 G2), sa1 (2 G1), enc_key (GT) and t
+            let ct_len = 7 * 96 + 2 * 48 + 576 + 8;
+
+            for i in 0..2000 {
+                // Alternate between pure noise and corrupted or truncated valid messages
+                let mut bytes = if i % 2 == 0 {
+                    let mut bytes = vec![0u8; (rng.next_u32() % 512) as usize];
+                    rng.fill_bytes(&mut bytes);
+                    bytes
+                } else {
+                    let mut bytes = samples[i % samples.len()].clone();
+                    let at = rng.next_u32() as usize % bytes.len();
+                    bytes[at] = rng.next_u32() as u8;
+                    bytes
+                };
+                let _ = CoordinatorMessage::from_bytes_checked(&bytes);
+                let _ = PartyMessage::from_bytes_checked(&bytes);
+                bytes.truncate(rng.next_u32() as usize % (bytes.len() + 1));
+                let _ = CoordinatorMessage::from_bytes_checked(&bytes);
+                let _ = PartyMessage::from_bytes_checked(&bytes);
+
+                let mut ct_bytes = vec![0u8; ct_len];
+                rng.fill_bytes(&mut ct_bytes);
+                assert!(Ciphertext::<E>::deserialize_validated(&ct_bytes).is_err());
+            }
+
+            // A huge length prefix for a Vec field fails without allocating it
+            let mut bytes = samples[1].clone();
+            bytes[4..12].copy_from_slice(&u64::MAX.to_le_bytes());
+            assert!(CoordinatorMessage::from_bytes_checked(&bytes).is_err());
+
+            for (name, bytes) in schema_samples() {
+                let decodes = CoordinatorMessage::from_bytes_checked(&bytes).is_ok()
+                    || PartyMessage::from_bytes_checked(&bytes).is_ok();
+                assert!(decodes, "{} does not decode", name);
+            }
+        }
+
+        #[test]
+        fn test_message_schema_snapshot() {
+            assert_eq!(
+                PROTOCOL_VERSION, 1,
+                "update SCHEMA_SNAPSHOT for the new version"
+            );
+            for ((name, bytes), expected) in schema_samples().into_iter().zip(SCHEMA_SNAPSHOT) {
+                assert_eq!(
+                    hex::encode(&bytes),
+                    expected,
+                    "wire format of {} changed",
+                    name
+                );
+            }
+        }
+
+        #[test]
+        fn test_protocol_version_mismatch_is_rejected() {
+            assert!(check_protocol_version(PROTOCOL_VERSION).is_ok());
+            let err = check_protocol_version(PROTOCOL_VERSION + 1).unwrap_err();
+            assert!(err.contains("incompatible protocol version"));
+        }
+
+        #[test]
+        fn test_coordinator_rejects_bad_partial_decryption() {
+            let n = 4;
+            let (sk, agg_key, params) = setup(n);
+            let mut rng = StdRng::seed_from_u64(5);
+            let ct = encrypt::<E, _>(&*agg_key, 2, &params, &mut rng).unwrap();
+
+            let mut shares: HashMap<usize, G2> =
+                (0..n).map(|i| (i, sk[i].partial_decryption(&ct))).collect();
+            assert!(check_partial_decryptions(&shares, &agg_key, &ct).is_ok());
+
+            shares.insert(1, G2::rand(&mut rng));
+            let err = check_partial_decryptions(&shares, &agg_key, &ct).unwrap_err();
+            assert_eq!(err, "Invalid partial decryptions from parties [1]");
+        }
+
+        #[test]
+        fn test_conflicting_partial_decryptions_are_equivocation() {
+            let mut rng = StdRng::seed_from_u64(6);
+            let requested = [0, 2, 3];
+            let pd = G2::rand(&mut rng);
+            let mut shares = HashMap::new();
+
+            assert_eq!(
+                accept_partial_decryption(&mut shares, &requested, 2, 2, pd),
+                Ok(true)
+            );
+            // A resent copy is harmless
+            assert_eq!(
+                accept_partial_decryption(&mut shares, &requested, 2, 2, pd),
+                Ok(false)
+            );
+
+            let err = accept_partial_decryption(&mut shares, &requested, 2, 2, G2::rand(&mut rng))
+                .unwrap_err();
+            assert!(err.contains("equivocated"), "{err}");
+            assert_eq!(shares[&2], pd);
+
+            // Unrequested parties and spoofed ids are rejected too
+            assert!(accept_partial_decryption(&mut shares, &requested, 1, 1, pd).is_err());
+            assert!(accept_partial_decryption(&mut shares, &requested, 3, 2, pd).is_err());
+            assert_eq!(shares.len(), 1);
+        }
+
+        #[derive(Clone, Default)]
+        struct SharedBuf(Arc<std::sync::Mutex<Vec<u8>>>);
+
+        impl std::io::Write for SharedBuf {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                std::io::Write::write(&mut *self.0.lock().unwrap(), buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        #[test]
+        fn test_coordinator_rejects_oversized_committee() {
+            let err = Coordinator::new(0, 1 << 20, 1, None, None, Some(3), None)
+                .err()
+                .expect("expected an oversized committee to be rejected");
+            assert!(err.to_string().contains("exceeds the maximum"), "{err}");
+        }
+
+        #[test]
+        fn test_json_logs_report_setup_phase() {
+            let buf = SharedBuf::default();
+            let writer = buf.clone();
+            tracing::subscriber::with_default(json_subscriber(move || writer.clone()), || {
+                Coordinator::new(0, 4, 1, None, None, Some(3), None).unwrap();
+            });
+
+            let output = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+            let events: Vec<serde_json::Value> = output
+                .lines()
+                .map(|line| serde_json::from_str(line).expect("every line is JSON"))
+                .collect();
+            let setup = events
+                .iter()
+                .find(|event| event["phase"] == "setup")
+                .expect("a setup phase event");
+            assert!(setup["duration_ms"].as_f64().unwrap() >= 0.0);
+            assert!(setup.get("party_id").is_none());
+        }
+
+        #[test]
+        fn test_missing_party_ids() {
+            let present: HashMap<usize, ()> = [0, 1, 3].into_iter().map(|id| (id, ())).collect();
+            assert_eq!(missing_party_ids(4, &present), vec![2]);
+            assert_eq!(missing_party_ids(2, &present), Vec::<usize>::new());
+            assert_eq!(
+                missing_party_ids(4, &HashMap::<usize, ()>::new()),
+                vec![0, 1, 2, 3]
+            );
+        }
+
+        #[test]
+        fn test_replayed_decryption_request_is_rejected() {
+            let mut party = Party::new(2, "localhost:0".to_string(), None, true, None);
+            let ct_bytes = b"ciphertext".to_vec();
+            let ct_hash = digest32(&ct_bytes);
+            let request_id = partial_decryption_request_id(&ct_hash, 2);
+
+            // Not broadcast yet
+            assert!(party
+                .accept_decryption_request(request_id, &ct_bytes)
+                .is_err());
+
+            party.known_ciphertexts.insert(ct_hash);
+            assert!(party
+                .accept_decryption_request(partial_decryption_request_id(&ct_hash, 3), &ct_bytes)
+                .is_err());
+            assert!(party
+                .accept_decryption_request(request_id, &ct_bytes)
+                .is_ok());
+            assert_eq!(
+                party.accept_decryption_request(request_id, &ct_bytes),
+                Err("request already answered (replay)")
+            );
+        }
+
+        /// Runs setup and key generation in-process, as a `--seed` deployment would.
+        fn seeded_aggregate_key(seed: u64, n: usize) -> Vec<u8> {
+            let coordinator = Coordinator::new(0, n, 1, None, None, Some(seed), None).unwrap();
+            let lagrange_params =
+                LagrangePowers::<E>::deserialize_compressed(&coordinator.lagrange_bytes[..])
+                    .unwrap();
+            let pk = (0..n)
+                .map(|id| {
+                    Party::new(id, String::new(), None, true, Some(seed))
+                        .generate_keys(&lagrange_params, n)
+                        .unwrap()
+                })
+                .collect();
+            let agg_key = AggregateKey::new(pk, &coordinator.kzg_params).unwrap();
+
+            let mut bytes = Vec::new();
+            agg_key.serialize_compressed(&mut bytes).unwrap();
+            bytes
+        }
+
+        #[test]
+        fn test_seeded_runs_produce_identical_aggregate_keys() {
+            assert_eq!(seeded_aggregate_key(7, 4), seeded_aggregate_key(7, 4));
+            assert_ne!(seeded_aggregate_key(7, 4), seeded_aggregate_key(8, 4));
+        }
+
+        #[test]
+        fn test_public_key_request_carries_no_tau() {
+            let (n, t, seed) = (4, 1, 11);
+            let coordinator = Coordinator::new(0, n, t, None, None, Some(seed), None).unwrap();
+            let tau = Fr::rand(&mut rng_for_stream(Some(seed), SETUP_RNG_STREAM));
+            let mut tau_le = Vec::new();
+            tau.serialize_compressed(&mut tau_le).unwrap();
+            let tau_be: Vec<u8> = tau_le.iter().rev().copied().collect();
+
+            let mut pk = Vec::new();
+            let mut parties = Vec::new();
+            for id in 0..n {
+                let msg = coordinator.public_key_request(id);
+                let wire = serialize(&msg).unwrap();
+                for tau_bytes in [&tau_le, &tau_be] {
+                    assert!(!wire.windows(tau_bytes.len()).any(|w| w == &tau_bytes[..]));
+                }
+
+                let CoordinatorMessage::RequestPublicKey {
+                    protocol_version,
+                    party_id,
+                    lagrange_bytes,
+                    lagrange_hash,
+                    n,
+                } = CoordinatorMessage::from_bytes_checked(&wire).unwrap()
+                else {
+                    panic!("expected a public key request");
+                };
+                assert_eq!(protocol_version, PROTOCOL_VERSION);
+                let mut party = Party::new(party_id, String::new(), None, true, Some(seed));
+                let lagrange_params = party
+                    .load_lagrange_params(&lagrange_bytes, lagrange_hash)
+                    .unwrap();
+                pk.push(party.generate_keys(&lagrange_params, n).unwrap());
+                parties.push(party);
+            }
+
+            // Keys built from the Lagrange powers alone still decrypt
+            let agg_key = AggregateKey::new(pk, &coordinator.kzg_params).unwrap();
+            let mut rng = StdRng::seed_from_u64(seed);
+            let ct = encrypt::<E, _>(&agg_key, t, &coordinator.kzg_params, &mut rng).unwrap();
+            let mut selector = vec![false; n];
+            let mut partial_decryptions = vec![G2::zero(); n];
+            for (i, party) in parties.iter().enumerate().take(t + 1) {
+                selector[i] = true;
+                partial_decryptions[i] = party.secret_key.as_ref().unwrap().partial_decryption(&ct);
+            }
+            let dec_key = agg_dec(
+                &partial_decryptions,
+                &ct,
+                &selector,
+                &agg_key,
+                &coordinator.kzg_params,
+            )
+            .unwrap();
+            assert_eq!(dec_key, ct.enc_key);
+        }
+
+        #[test]
+        fn test_coordinator_loads_params_file() {
+            let mut rng = StdRng::seed_from_u64(3);
+            let tau = Fr::rand(&mut rng);
+            let params = KZG10::<E, UniPoly381>::setup(4, tau).unwrap();
+            let path = std::env::temp_dir().join(format!("ste-params-{}.bin", std::process::id()));
+            container::save_file(&params, &path).unwrap();
+            let load = |n| {
+                Coordinator::new(
+                    0,
+                    n,
+                    1,
+                    None,
+                    None,
+                    None,
+                    Some(path.to_string_lossy().into_owned()),
+                )
+            };
+            let coordinator = load(4);
+            // Parameters of degree 4 are too short for 8 parties
+            let too_short = load(8);
+            std::fs::remove_file(&path).unwrap();
+
+            let mut expected = Vec::new();
+            LagrangePowers::<E>::new(tau, 4)
+                .unwrap()
+                .serialize_compressed(&mut expected)
+                .unwrap();
+            assert_eq!(coordinator.unwrap().lagrange_bytes, expected);
+            assert!(too_short.is_err());
+        }
+
+        #[tokio::test]
+        async fn test_graceful_shutdown_reports_session_outcome() {
+            let shutdown = Notify::new();
+            let grace = std::time::Duration::from_millis(200);
+
+            // Shutdown arrives while the session is mid-flight but it finishes in time
+            let session = async {
+                tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+                Ok(())
+            };
+            let outcome = run_with_graceful_shutdown(session, &shutdown, async {}, grace)
+                .await
+                .unwrap();
+            assert_eq!(outcome, SessionOutcome::Completed);
+
+            // The session outlives the grace period
+            let session = async {
+                tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+                Ok(())
+            };
+            let outcome = run_with_graceful_shutdown(session, &shutdown, async {}, grace)
+                .await
+                .unwrap();
+            assert_eq!(outcome, SessionOutcome::Incomplete);
+        }
+
+        #[tokio::test]
+        async fn test_encrypt_cancellable_matches_encrypt() {
+            let (_, agg_key, params) = setup(4);
+            let cancel = Notify::new();
+
+            let ct = encrypt_cancellable(
+                agg_key.clone(),
+                2,
+                params.clone(),
+                StdRng::seed_from_u64(42),
+                &cancel,
+            )
+            .await
+            .unwrap()
+            .expect("not cancelled");
+            let expected =
+                encrypt::<E, _>(&*agg_key, 2, &params, &mut StdRng::seed_from_u64(42)).unwrap();
+
+            let (mut a, mut b) = (Vec::new(), Vec::new());
+            ct.serialize_compressed(&mut a).unwrap();
+            expected.serialize_compressed(&mut b).unwrap();
+            assert_eq!(a, b);
+        }
+
+        #[tokio::test]
+        async fn test_cancelled_encryption_yields_no_ciphertext() {
+            let (_, agg_key, params) = setup(4);
+            let cancel = Notify::new();
+            cancel.notify_one();
+
+            let ct = encrypt_cancellable(agg_key, 2, params, StdRng::seed_from_u64(42), &cancel)
+                .await
+                .unwrap();
+            assert!(ct.is_none());
+        }
+    }
 }
 
 #[cfg(feature = "distributed")]
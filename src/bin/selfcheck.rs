@@ -0,0 +1,78 @@
+//! Checks a committee's setup before it goes into production.
+//!
+//! ```text
+//! selfcheck --params-file ./params.bin --n 64 [--lagrange-file ./lagrange.bin]
+//! ```
+//!
+//! Both files are containers written with `container::save_file`. Without
+//! `--lagrange-file` the Lagrange powers are derived from the parameters, as
+//! parties without tau would do. Exits non-zero if `setup::selfcheck` fails.
+
+use silent_threshold_encryption::{
+    container,
+    kzg::PowersOfTau,
+    setup::{selfcheck, LagrangePowers},
+};
+use std::error::Error;
+
+type E = ark_bls12_381::Bls12_381;
+
+const USAGE: &str = "usage: selfcheck --params-file PATH --n N [--lagrange-file PATH]";
+
+struct Args {
+    params_file: String,
+    n: usize,
+    lagrange_file: Option<String>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let mut params_file = None;
+    let mut n = None;
+    let mut lagrange_file = None;
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or(format!("{} needs a value\n{}", arg, USAGE))
+        };
+        match arg.as_str() {
+            "--params-file" => params_file = Some(value()?),
+            "--n" => {
+                let v = value()?;
+                n = Some(v.parse().map_err(|_| format!("bad n {:?}", v))?);
+            }
+            "--lagrange-file" => lagrange_file = Some(value()?),
+            _ => return Err(format!("unknown argument {:?}\n{}", arg, USAGE)),
+        }
+    }
+    Ok(Args {
+        params_file: params_file.ok_or(format!("--params-file is required\n{}", USAGE))?,
+        n: n.ok_or(format!("--n is required\n{}", USAGE))?,
+        lagrange_file,
+    })
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = parse_args(std::env::args().skip(1))?;
+
+    let params: PowersOfTau<E> = container::load_file(&args.params_file)?;
+    println!(
+        "params: degree {} from {}",
+        params.max_degree(),
+        args.params_file
+    );
+
+    let lagrange = match &args.lagrange_file {
+        Some(path) => {
+            println!("lagrange: loaded from {}", path);
+            container::load_file(path)?
+        }
+        None => {
+            println!("lagrange: derived from params");
+            LagrangePowers::<E>::from_powers_of_tau(&params, args.n)?
+        }
+    };
+
+    selfcheck(&params, &lagrange, args.n)?;
+    println!("selfcheck passed for n = {}", args.n);
+    Ok(())
+}
@@ -0,0 +1,216 @@
+//! Self-describing file format for serialized artifacts.
+//!
+//! Raw arkworks bytes carry no indication of what they encode, so a file of
+//! the wrong kind can deserialize into garbage. Every artifact written through
+//! this module is wrapped as
+//!
+//! ```text
+//! magic "STE1" (4) || version u16 LE (2) || type tag (1) || payload length u64 LE (8) || payload
+//! ```
+//!
+//! where the payload is the compressed arkworks encoding.
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use std::path::Path;
+
+use crate::{error::SteError, kzg::PowersOfTau, setup::LagrangePowers, trusted_setup::Ceremony};
+
+/// Marks a file as a container
+pub const CONTAINER_MAGIC: [u8; 4] = *b"STE1";
+
+/// Version of the container layout
+pub const CONTAINER_VERSION: u16 = 1;
+
+const HEADER_SIZE: usize = 4 + 2 + 1 + 8;
+
+/// What a container holds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ArtifactType {
+    PowersOfTau = 1,
+    LagrangePowers = 2,
+    Ceremony = 3,
+}
+
+impl ArtifactType {
+    fn from_tag(tag: u8) -> Result<Self, SteError> {
+        match tag {
+            1 => Ok(Self::PowersOfTau),
+            2 => Ok(Self::LagrangePowers),
+            3 => Ok(Self::Ceremony),
+            _ => Err(SteError::SerializationError(format!(
+                "unknown container type tag {}",
+                tag
+            ))),
+        }
+    }
+}
+
+/// A type that can be stored in a container.
+pub trait Artifact: CanonicalSerialize + CanonicalDeserialize {
+    const TYPE: ArtifactType;
+}
+
+impl<E: Pairing> Artifact for PowersOfTau<E> {
+    const TYPE: ArtifactType = ArtifactType::PowersOfTau;
+}
+
+impl<E: Pairing> Artifact for LagrangePowers<E> {
+    const TYPE: ArtifactType = ArtifactType::LagrangePowers;
+}
+
+impl<E: Pairing> Artifact for Ceremony<E> {
+    const TYPE: ArtifactType = ArtifactType::Ceremony;
+}
+
+/// Serializes `value` into a container.
+///
+/// # Errors
+/// Returns `SteError::SerializationError` if serialization fails
+pub fn save<T: Artifact>(value: &T) -> Result<Vec<u8>, SteError> {
+    let payload_len = value.compressed_size();
+    let mut bytes = Vec::with_capacity(HEADER_SIZE + payload_len);
+    bytes.extend_from_slice(&CONTAINER_MAGIC);
+    bytes.extend_from_slice(&CONTAINER_VERSION.to_le_bytes());
+    bytes.push(T::TYPE as u8);
+    bytes.extend_from_slice(&(payload_len as u64).to_le_bytes());
+    value.serialize_compressed(&mut bytes).map_err(|e| {
+        SteError::SerializationError(format!("Failed to serialize {:?}: {}", T::TYPE, e))
+    })?;
+    Ok(bytes)
+}
+
+/// Reads a `T` from a container, checking the header first.
+///
+/// # Errors
+/// Returns `SteError::SerializationError` if the magic, version, type or
+/// length do not match, or the payload is not a valid `T`
+pub fn load<T: Artifact>(bytes: &[u8]) -> Result<T, SteError> {
+    if bytes.len() < HEADER_SIZE {
+        return Err(SteError::SerializationError(format!(
+            "container is {} bytes, shorter than its {}-byte header",
+            bytes.len(),
+            HEADER_SIZE
+        )));
+    }
+    let (header, payload) = bytes.split_at(HEADER_SIZE);
+
+    if header[..4] != CONTAINER_MAGIC {
+        return Err(SteError::SerializationError(
+            "not a container: bad magic number".to_string(),
+        ));
+    }
+    let version = u16::from_le_bytes([header[4], header[5]]);
+    if version != CONTAINER_VERSION {
+        return Err(SteError::SerializationError(format!(
+            "unsupported container version {} (expected {})",
+            version, CONTAINER_VERSION
+        )));
+    }
+    let artifact = ArtifactType::from_tag(header[6])?;
+    if artifact != T::TYPE {
+        return Err(SteError::SerializationError(format!(
+            "container holds {:?}, expected {:?}",
+            artifact,
+            T::TYPE
+        )));
+    }
+    let mut len = [0u8; 8];
+    len.copy_from_slice(&header[7..]);
+    if u64::from_le_bytes(len) != payload.len() as u64 {
+        return Err(SteError::SerializationError(format!(
+            "container payload is {} bytes, header says {}",
+            payload.len(),
+            u64::from_le_bytes(len)
+        )));
+    }
+
+    T::deserialize_compressed(payload)
+        .map_err(|e| SteError::SerializationError(format!("Invalid {:?} payload: {}", T::TYPE, e)))
+}
+
+/// Writes `value` to `path` as a container.
+///
+/// # Errors
+/// Returns an error if serialization or the write fails
+pub fn save_file<T: Artifact>(value: &T, path: impl AsRef<Path>) -> Result<(), SteError> {
+    let path = path.as_ref();
+    std::fs::write(path, save(value)?).map_err(|e| {
+        SteError::SerializationError(format!("Failed to write {}: {}", path.display(), e))
+    })
+}
+
+/// Reads a `T` from the container at `path`.
+///
+/// # Errors
+/// Returns an error if the file cannot be read or [`load`] rejects it
+pub fn load_file<T: Artifact>(path: impl AsRef<Path>) -> Result<T, SteError> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path).map_err(|e| {
+        SteError::SerializationError(format!("Failed to read {}: {}", path.display(), e))
+    })?;
+    load(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kzg::KZG10;
+    use ark_poly::univariate::DensePolynomial;
+    use ark_std::UniformRand;
+
+    type E = ark_bls12_381::Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+    type UniPoly381 = DensePolynomial<Fr>;
+
+    fn expect_error<T: Artifact>(bytes: &[u8], needle: &str) {
+        match load::<T>(bytes) {
+            Err(SteError::SerializationError(msg)) => {
+                assert!(msg.contains(needle), "{msg:?} does not mention {needle:?}")
+            }
+            Err(e) => panic!("unexpected error {e:?}"),
+            Ok(_) => panic!("load accepted a bad container"),
+        }
+    }
+
+    #[test]
+    fn test_container_round_trip_and_mismatches() {
+        let mut rng = ark_std::test_rng();
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(4, tau).unwrap();
+        let bytes = save(&params).unwrap();
+
+        let loaded = load::<PowersOfTau<E>>(&bytes).unwrap();
+        assert_eq!(loaded.powers_of_g, params.powers_of_g);
+        assert_eq!(loaded.powers_of_h, params.powers_of_h);
+
+        let mut bad_magic = bytes.clone();
+        bad_magic[0] ^= 0xff;
+        expect_error::<PowersOfTau<E>>(&bad_magic, "magic");
+
+        let mut bad_version = bytes.clone();
+        bad_version[4] = 2;
+        expect_error::<PowersOfTau<E>>(&bad_version, "version");
+
+        expect_error::<LagrangePowers<E>>(&bytes, "holds PowersOfTau");
+        expect_error::<PowersOfTau<E>>(&bytes[..bytes.len() - 1], "payload");
+
+        // Raw arkworks bytes are rejected rather than misread
+        let mut raw = Vec::new();
+        params.serialize_compressed(&mut raw).unwrap();
+        expect_error::<PowersOfTau<E>>(&raw, "magic");
+    }
+
+    #[test]
+    fn test_container_file_round_trip() {
+        let mut rng = ark_std::test_rng();
+        let lagrange_params = LagrangePowers::<E>::new(Fr::rand(&mut rng), 4).unwrap();
+        let path = std::env::temp_dir().join(format!("ste-container-{}.bin", std::process::id()));
+
+        save_file(&lagrange_params, &path).unwrap();
+        let loaded = load_file::<LagrangePowers<E>>(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap().li, lagrange_params.li);
+    }
+}
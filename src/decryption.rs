@@ -1,20 +1,22 @@
 use ark_ec::{
     pairing::{Pairing, PairingOutput},
-    VariableBaseMSM,
-};
-use ark_poly::{
-    univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial,
-    Radix2EvaluationDomain,
+    PrimeGroup, VariableBaseMSM,
 };
+use ark_ff::{FftField, PrimeField};
+use ark_poly::{univariate::DensePolynomial, DenseUVPolynomial, EvaluationDomain, Polynomial};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{One, Zero};
+use blake2::{Blake2b512, Digest};
+use std::collections::HashMap;
 use std::ops::Div;
 
 use crate::error::SteError;
 use crate::{
-    encryption::Ciphertext,
+    encryption::{Ciphertext, CiphertextBody, Encapsulation, SA2_SIZE},
     kzg::{PowersOfTau, KZG10},
-    setup::AggregateKey,
-    utils::interp_mostly_zero,
+    security::{selectors_eq_ct, verify_bls_signature_ct, RecoveredKey},
+    setup::{validate_committee_size, AggregateKey, DecryptOnlyKey, DecryptionHints, DummyConfig},
+    utils::{interp_mostly_zero, make_domain, selector_to_parties},
 };
 
 /// Helper function to compute MSM over G1 group elements.
@@ -45,6 +47,10 @@ fn compute_msm_g2<E: Pairing>(
 
 /// Aggregates partial decryptions and recovers the encrypted key.
 ///
+/// Any quorum of at least `t + 1` selected parties (including the dummy party 0)
+/// is accepted. For the maximal threshold `t = n - 1` this means every party
+/// must be selected.
+///
 /// # Arguments
 /// * `partial_decryptions` - Partial decryptions from each party (use zero if party didn't respond)
 /// * `ct` - The ciphertext to decrypt
@@ -54,6 +60,10 @@ fn compute_msm_g2<E: Pairing>(
 ///
 /// # Errors
 /// Returns an error if inputs are invalid, lengths don't match, or operations fail
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(n = agg_key.pk.len(), t = ct.t))
+)]
 pub fn agg_dec<E: Pairing>(
     partial_decryptions: &[E::G2],
     ct: &Ciphertext<E>,
@@ -61,8 +71,509 @@ pub fn agg_dec<E: Pairing>(
     agg_key: &AggregateKey<E>,
     params: &PowersOfTau<E>,
 ) -> Result<PairingOutput<E>, SteError> {
+    agg_dec_with_dummies(
+        partial_decryptions,
+        ct,
+        selector,
+        agg_key,
+        params,
+        &DummyConfig::default(),
+    )
+}
+
+/// Like [`agg_dec`], for a ciphertext made by
+/// [`encrypt_with_dummies`](crate::encryption::encrypt_with_dummies) with the
+/// same `dummies`. Every dummy must be selected, with partial decryption
+/// `ct.gamma_g2`.
+///
+/// # Errors
+/// Returns the errors of [`agg_dec`], with the dummies of `dummies` in place
+/// of party 0
+pub fn agg_dec_with_dummies<E: Pairing>(
+    partial_decryptions: &[E::G2],
+    ct: &Ciphertext<E>,
+    selector: &[bool],
+    agg_key: &AggregateKey<E>,
+    params: &PowersOfTau<E>,
+    dummies: &DummyConfig,
+) -> Result<PairingOutput<E>, SteError> {
+    let enc_key = aggregate(
+        partial_decryptions,
+        ct,
+        selector,
+        agg_key,
+        params,
+        dummies,
+        true,
+    )?;
+
+    if enc_key != ct.enc_key {
+        return Err(key_mismatch());
+    }
+
+    Ok(enc_key)
+}
+
+/// Like [`agg_dec`], but with the [`DecryptOnlyKey`] projection of the
+/// aggregate key instead of the full key.
+///
+/// # Errors
+/// Returns an error if `key` is inconsistent (see [`DecryptOnlyKey::validate`])
+/// or for any of the reasons [`agg_dec`] fails
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(n = key.bls_pk.len(), t = ct.t))
+)]
+pub fn agg_dec_with_decrypt_key<E: Pairing>(
+    partial_decryptions: &[E::G2],
+    ct: &Ciphertext<E>,
+    selector: &[bool],
+    key: &DecryptOnlyKey<E>,
+    params: &PowersOfTau<E>,
+) -> Result<PairingOutput<E>, SteError> {
+    key.validate()?;
+    let enc_key = aggregate(
+        partial_decryptions,
+        ct,
+        selector,
+        key,
+        params,
+        &DummyConfig::default(),
+        true,
+    )?;
+
+    if enc_key != ct.enc_key {
+        return Err(key_mismatch());
+    }
+
+    Ok(enc_key)
+}
+
+/// An aggregate key reduced to what decrypting with one fixed selector needs.
+///
+/// Built by [`AggregateKey::fix_selector`]. Everything in [`agg_dec`] that
+/// depends only on the selector (the selector polynomial commitments and the
+/// hint MSMs) is computed once, so [`agg_dec_with`] only has to combine the
+/// partial decryptions and evaluate the pairing product for each ciphertext.
+#[derive(Clone, Debug)]
+pub struct FixedSelectorKey<E: Pairing> {
+    selector: Vec<bool>,
+    pre: DecryptionPrecompute<E>,
+}
+
+impl<E: Pairing> FixedSelectorKey<E> {
+    pub(crate) fn new(
+        agg_key: &AggregateKey<E>,
+        selector: &[bool],
+        params: &PowersOfTau<E>,
+    ) -> Result<Self, SteError> {
+        let dummies = DummyConfig::default();
+        check_selector(selector, agg_key, &dummies)?;
+        Ok(Self {
+            selector: selector.to_vec(),
+            pre: DecryptionPrecompute::compute(selector, agg_key, params, &dummies)?,
+        })
+    }
+
+    /// The parties whose partial decryptions this key expects
+    pub fn selector(&self) -> &[bool] {
+        &self.selector
+    }
+
+    /// Whether this key was built for `selector`, e.g. to look up a cached
+    /// key. The comparison is constant-time, so it does not reveal where two
+    /// quorums differ.
+    pub fn matches(&self, selector: &[bool]) -> bool {
+        selectors_eq_ct(&self.selector, selector)
+    }
+
+    fn check_inputs(
+        &self,
+        partial_decryptions: &[E::G2],
+        ct: &Ciphertext<E>,
+    ) -> Result<(), SteError> {
+        let n = self.selector.len();
+        check_ciphertext_threshold(ct, n)?;
+        if partial_decryptions.len() != n {
+            return Err(SteError::ValidationError(format!(
+                "partial_decryptions length ({}) must equal n ({})",
+                partial_decryptions.len(),
+                n
+            )));
+        }
+        check_quorum(self.pre.parties.len(), ct.t)
+    }
+}
+
+/// Like [`agg_dec`], with the selector-dependent work already done by `key`.
+///
+/// # Errors
+/// Returns an error if the selected parties are too few for `ct.t`, if
+/// `partial_decryptions` has the wrong length, or for any other reason
+/// [`agg_dec`] fails
+pub fn agg_dec_with<E: Pairing>(
+    partial_decryptions: &[E::G2],
+    ct: &Ciphertext<E>,
+    key: &FixedSelectorKey<E>,
+    params: &PowersOfTau<E>,
+) -> Result<PairingOutput<E>, SteError> {
+    key.check_inputs(partial_decryptions, ct)?;
+
+    let enc_key = key.pre.finish(partial_decryptions, ct, params, true)?;
+    if enc_key != ct.enc_key {
+        return Err(key_mismatch());
+    }
+
+    Ok(enc_key)
+}
+
+/// Domain separation tag for the batch decryption coefficients.
+const BATCH_DEC_DST: &[u8] = b"silent-threshold-encryption/agg-dec-batch/v1";
+
+/// Like [`agg_dec_with`] for many ciphertexts under the same committee and
+/// selector, verified with one combined pairing check.
+///
+/// The pairing products of all items are combined with coefficients derived
+/// by hashing the inputs. Every pairing of a selector-derived commitment with
+/// a ciphertext element then merges into one across the batch, so `k`
+/// ciphertexts cost `2k + 6` pairings and a single final exponentiation
+/// instead of `8k` pairings and `k` final exponentiations. If the combined
+/// check fails, each item is checked on its own to find the bad ones.
+///
+/// Returns one result per ciphertext, in order; `partials_per_ct[i]` holds
+/// the partial decryptions of `ciphertexts[i]`.
+pub fn agg_dec_batch<E: Pairing>(
+    key: &FixedSelectorKey<E>,
+    ciphertexts: &[Ciphertext<E>],
+    partials_per_ct: &[Vec<E::G2>],
+    params: &PowersOfTau<E>,
+) -> Vec<Result<PairingOutput<E>, SteError>> {
+    if partials_per_ct.len() != ciphertexts.len() {
+        let err = SteError::ValidationError(format!(
+            "got partial decryptions for {} ciphertexts, expected {}",
+            partials_per_ct.len(),
+            ciphertexts.len()
+        ));
+        return vec![Err(err); ciphertexts.len()];
+    }
+
+    let terms: Vec<Result<CiphertextTerms<E>, SteError>> = ciphertexts
+        .iter()
+        .zip(partials_per_ct)
+        .map(|(ct, partials)| {
+            key.check_inputs(partials, ct)?;
+            key.pre.ciphertext_terms(partials, ct, params, true)
+        })
+        .collect();
+
+    let ready: Vec<usize> = (0..terms.len()).filter(|&i| terms[i].is_ok()).collect();
+    let batch_ok = !ready.is_empty() && batch_check(&key.pre, ciphertexts, &terms, &ready);
+
+    terms
+        .into_iter()
+        .zip(ciphertexts)
+        .map(|(terms, ct)| {
+            let (bhat_g1, sigma) = terms?;
+            if batch_ok || key.pre.pairing_product(ct, bhat_g1, sigma) == ct.enc_key {
+                Ok(ct.enc_key)
+            } else {
+                Err(key_mismatch())
+            }
+        })
+        .collect()
+}
+
+/// Checks `sum_i r_i * product_i = sum_i r_i * enc_key_i` over the items in
+/// `ready`, merging the pairings against the selector-derived commitments.
+fn batch_check<E: Pairing>(
+    pre: &DecryptionPrecompute<E>,
+    ciphertexts: &[Ciphertext<E>],
+    terms: &[Result<CiphertextTerms<E>, SteError>],
+    ready: &[usize],
+) -> bool {
+    // Position of the bhat commitment in the pairing product, the only one of
+    // the six sa2 pairings that is not against a shared commitment
+    const BHAT: usize = 4;
+
+    let items: Vec<(&Ciphertext<E>, E::G1, E::G2)> = ready
+        .iter()
+        .filter_map(|&i| {
+            let (bhat_g1, sigma) = *terms[i].as_ref().ok()?;
+            Some((&ciphertexts[i], bhat_g1, sigma))
+        })
+        .collect();
+    let coeffs = batch_dec_coefficients(&items);
+
+    let mut sa2_acc = [E::G2::zero(); SA2_SIZE];
+    let mut sa1_acc = E::G1::zero();
+    let mut expected = PairingOutput::<E>::zero();
+    let mut lhs = Vec::with_capacity(2 * items.len() + 6);
+    let mut rhs = Vec::with_capacity(2 * items.len() + 6);
+    for (&(ct, bhat_g1, sigma), r) in items.iter().zip(coeffs) {
+        for (acc, sa2) in sa2_acc.iter_mut().zip(&ct.sa2) {
+            *acc += *sa2 * r;
+        }
+        sa1_acc += ct.sa1[0] * r;
+        lhs.push(-(bhat_g1 * r));
+        rhs.push(ct.sa2[BHAT]);
+        lhs.push(ct.sa1[1] * r);
+        rhs.push(sigma);
+        expected += ct.enc_key * r;
+    }
+    lhs.extend([-pre.apk, -pre.qz, -pre.qx, pre.qhatx, -pre.q0_g1, sa1_acc]);
+    rhs.extend([
+        sa2_acc[0], sa2_acc[1], sa2_acc[2], sa2_acc[3], sa2_acc[5], pre.b_g2,
+    ]);
+
+    E::multi_pairing(lhs, rhs) == expected
+}
+
+/// Derives one batching coefficient per item from a hash of the ciphertexts
+/// and their sigmas, which carry the partial decryptions.
+fn batch_dec_coefficients<E: Pairing>(
+    items: &[(&Ciphertext<E>, E::G1, E::G2)],
+) -> Vec<E::ScalarField> {
+    let mut bytes = Vec::new();
+    for (ct, _, sigma) in items {
+        ct.serialize_compressed(&mut bytes)
+            .expect("serializing into a Vec cannot fail");
+        sigma
+            .serialize_compressed(&mut bytes)
+            .expect("serializing into a Vec cannot fail");
+    }
+    let seed = Blake2b512::new()
+        .chain_update(BATCH_DEC_DST)
+        .chain_update(&bytes)
+        .finalize();
+
+    (0..items.len())
+        .map(|i| {
+            let mut hasher = Blake2b512::new();
+            hasher.update(seed);
+            hasher.update((i as u64).to_le_bytes());
+            E::ScalarField::from_le_bytes_mod_order(&hasher.finalize())
+        })
+        .collect()
+}
+
+fn key_mismatch() -> SteError {
+    SteError::ValidationError(
+        "Decrypted key does not match encrypted key. Decryption verification failed.".to_string(),
+    )
+}
+
+/// Pairs in the multi-pairing of [`agg_dec`]: the six G1 terms against
+/// `ct.sa2`, and `ct.sa1` against `B(tau)` and sigma.
+pub const AGG_DEC_PAIRINGS: usize = 8;
+
+/// How many pairings [`agg_dec`] evaluates for a committee of size `n`,
+/// threshold `t` and `selector`, without running it.
+///
+/// All of them share one final exponentiation. A quorum short of the full
+/// committee needs [`AGG_DEC_PAIRINGS`]. When every party is selected the
+/// selector polynomial is constant, so `q0` and `qz` are the identity and
+/// their pairings are skipped, leaving 6.
+///
+/// The count is 0 when `agg_dec` would reject the inputs before pairing: an
+/// invalid `n` or `t`, a selector of the wrong length, without party 0, or
+/// with fewer than `t + 1` parties. Padding slots are not known without the
+/// key and are not checked.
+pub fn agg_dec_pairing_count(n: usize, t: usize, selector: &[bool]) -> usize {
+    let num_selected = selector.iter().filter(|&&s| s).count();
+    let valid = validate_committee_size(n).is_ok()
+        && t >= 1
+        && t < n
+        && selector.len() == n
+        && DummyConfig::default().check_selector(selector).is_ok()
+        && check_quorum(num_selected, t).is_ok();
+    if !valid {
+        0
+    } else if num_selected == n {
+        AGG_DEC_PAIRINGS - 2
+    } else {
+        AGG_DEC_PAIRINGS
+    }
+}
+
+/// Checks a published decryption result from public data only.
+///
+/// Re-runs the aggregation of [`agg_dec`] over the published partial
+/// decryptions and accepts `result` only if it is what they produce and it
+/// equals the key encapsulated in `ct`. No secret key is needed, so anyone
+/// who did not take part in decryption can check the transcript.
+///
+/// # Returns
+/// `true` if the transcript reproduces `result`, `false` otherwise
+/// (including for malformed inputs or a quorum below the threshold)
+pub fn verify_decryption<E: Pairing>(
+    result: &PairingOutput<E>,
+    partial_decryptions: &[E::G2],
+    ct: &Ciphertext<E>,
+    selector: &[bool],
+    agg_key: &AggregateKey<E>,
+    params: &PowersOfTau<E>,
+) -> bool {
+    match aggregate(
+        partial_decryptions,
+        ct,
+        selector,
+        agg_key,
+        params,
+        &DummyConfig::default(),
+        true,
+    ) {
+        Ok(recomputed) => recomputed == *result && recomputed == ct.enc_key,
+        Err(_) => false,
+    }
+}
+
+/// Domain separation tag for the partial decryption batch coefficients.
+const BATCH_VERIFY_DST: &[u8] = b"silent-threshold-encryption/batch-verify-partials/v1";
+
+/// Checks many partial decryptions of `ct` at once and returns the ids of the
+/// parties whose shares are invalid, in ascending order.
+///
+/// Share `pd_i` is valid if `e(g, pd_i) = e(bls_pk_i, gamma_g2)`. The equations
+/// are combined with coefficients derived by hashing the inputs, so when every
+/// share is valid the whole batch costs a single two-pairing check. On failure
+/// the batch is bisected to locate the bad shares. Ids outside the committee
+/// are reported as invalid.
+pub fn batch_verify_partials<E: Pairing>(
+    shares: &HashMap<usize, E::G2>,
+    agg_key: &AggregateKey<E>,
+    ct: &Ciphertext<E>,
+) -> Vec<usize> {
     let n = agg_key.pk.len();
-    let t = ct.t;
+    let mut ids: Vec<usize> = shares.keys().copied().collect();
+    ids.sort_unstable();
+    let (known, mut invalid): (Vec<usize>, Vec<usize>) = ids.into_iter().partition(|&id| id < n);
+
+    let coeffs = partial_batch_coefficients(shares, &known, ct);
+    let entries: Vec<(usize, E::G2, E::ScalarField)> = known
+        .iter()
+        .zip(coeffs)
+        .map(|(&id, r)| (id, shares[&id], r))
+        .collect();
+    verify_partials_subset(&entries, agg_key, ct, &mut invalid);
+
+    invalid.sort_unstable();
+    invalid
+}
+
+/// Derives one batching coefficient per share from a hash of the inputs.
+fn partial_batch_coefficients<E: Pairing>(
+    shares: &HashMap<usize, E::G2>,
+    ids: &[usize],
+    ct: &Ciphertext<E>,
+) -> Vec<E::ScalarField> {
+    let mut bytes = Vec::new();
+    ct.gamma_g2
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a Vec cannot fail");
+    for id in ids {
+        bytes.extend_from_slice(&(*id as u64).to_le_bytes());
+        shares[id]
+            .serialize_compressed(&mut bytes)
+            .expect("serializing into a Vec cannot fail");
+    }
+    let seed = Blake2b512::new()
+        .chain_update(BATCH_VERIFY_DST)
+        .chain_update(&bytes)
+        .finalize();
+
+    (0..ids.len())
+        .map(|i| {
+            let mut hasher = Blake2b512::new();
+            hasher.update(seed);
+            hasher.update((i as u64).to_le_bytes());
+            E::ScalarField::from_le_bytes_mod_order(&hasher.finalize())
+        })
+        .collect()
+}
+
+/// Adds the ids of invalid shares in `entries` to `invalid`, bisecting on failure.
+fn verify_partials_subset<E: Pairing>(
+    entries: &[(usize, E::G2, E::ScalarField)],
+    agg_key: &AggregateKey<E>,
+    ct: &Ciphertext<E>,
+    invalid: &mut Vec<usize>,
+) {
+    if entries.is_empty() {
+        return;
+    }
+
+    // e(g, sum r_i pd_i) = e(sum r_i pk_i, gamma_g2)
+    let mut acc_pd = E::G2::zero();
+    let mut acc_pk = E::G1::zero();
+    for &(id, pd, r) in entries {
+        acc_pd += pd * r;
+        acc_pk += agg_key.pk[id].bls_pk * r;
+    }
+    let combined = E::multi_pairing([E::G1::generator(), -acc_pk], [acc_pd, ct.gamma_g2]);
+    if combined.is_zero() {
+        return;
+    }
+    if entries.len() == 1 {
+        invalid.push(entries[0].0);
+        return;
+    }
+
+    let (left, right) = entries.split_at(entries.len() / 2);
+    verify_partials_subset(left, agg_key, ct, invalid);
+    verify_partials_subset(right, agg_key, ct, invalid);
+}
+
+/// Runs the aggregation of [`agg_dec`] with fewer than `t + 1` parties and
+/// returns whatever GT element comes out, without comparing it to `ct.enc_key`.
+///
+/// This exists to demonstrate the threshold boundary: the result is not the
+/// encrypted key. With `k <= t` selected parties the polynomial
+/// `x^{t+1} * B(x)` has degree above `n`, beyond what the degree-`n` KZG
+/// parameters can commit to, so the term that cancels `s3` in the ciphertext
+/// is missing and is left out of the pairing product.
+///
+/// # Errors
+/// Returns an error if the inputs are malformed, party 0 is not selected, or
+/// `t + 1` or more parties are selected (use [`agg_dec`] then)
+pub fn attempt_dec_below_threshold<E: Pairing>(
+    partial_decryptions: &[E::G2],
+    ct: &Ciphertext<E>,
+    selector: &[bool],
+    agg_key: &AggregateKey<E>,
+    params: &PowersOfTau<E>,
+) -> Result<PairingOutput<E>, SteError> {
+    let num_selected = selector.iter().filter(|&&selected| selected).count();
+    if num_selected > ct.t {
+        return Err(SteError::InvalidThreshold(format!(
+            "{} parties selected, which meets the threshold t={}; use agg_dec",
+            num_selected, ct.t
+        )));
+    }
+    aggregate(
+        partial_decryptions,
+        ct,
+        selector,
+        agg_key,
+        params,
+        &DummyConfig::default(),
+        false,
+    )
+}
+
+/// Computes the decryption pairing product without checking it against the
+/// ciphertext. With `check_threshold` unset, quorums below `t + 1` are allowed
+/// and a `bhat` that does not fit in `params` is dropped.
+fn aggregate<E: Pairing>(
+    partial_decryptions: &[E::G2],
+    ct: &impl CiphertextBody<E>,
+    selector: &[bool],
+    agg_key: &impl DecryptionHints<E>,
+    params: &PowersOfTau<E>,
+    dummies: &DummyConfig,
+    check_threshold: bool,
+) -> Result<PairingOutput<E>, SteError> {
+    let n = agg_key.n();
+    check_ciphertext_threshold(ct, n)?;
 
     // Validate inputs
     if partial_decryptions.len() != n {
@@ -72,31 +583,35 @@ pub fn agg_dec<E: Pairing>(
             n
         )));
     }
-    if selector.len() != n {
-        return Err(SteError::ValidationError(format!(
-            "selector length ({}) must equal n ({})",
-            selector.len(),
-            n
-        )));
-    }
-    if !n.is_power_of_two() {
-        return Err(SteError::InvalidParameter(format!(
-            "n must be a power of 2, got {}",
-            n
-        )));
-    }
+    check_selector(selector, agg_key, dummies)?;
 
-    // Validate selector: count selected parties
+    // Must have at least t+1 parties selected (including dummy party) for threshold t
     let num_selected = selector.iter().filter(|&&selected| selected).count();
+    if check_threshold {
+        check_quorum(num_selected, ct.t())?;
+    }
 
-    // Party 0 (dummy party) must always be selected
-    if !selector.first().copied().unwrap_or(false) {
-        return Err(SteError::ValidationError(
-            "Party 0 (dummy party) must always be selected".to_string(),
-        ));
+    let pre = DecryptionPrecompute::compute(selector, agg_key, params, dummies)?;
+    pre.finish(partial_decryptions, ct, params, check_threshold)
+}
+
+/// Rejects a ciphertext whose claimed threshold could never have been
+/// produced by encrypt; the degree of bhat depends on it.
+fn check_ciphertext_threshold<E: Pairing>(
+    ct: &impl CiphertextBody<E>,
+    n: usize,
+) -> Result<(), SteError> {
+    if ct.t() == 0 || ct.t() >= n {
+        return Err(SteError::InvalidThreshold(format!(
+            "ciphertext threshold t={} must be in [1, n - 1] for n={}",
+            ct.t(),
+            n
+        )));
     }
+    Ok(())
+}
 
-    // Must have at least t+1 parties selected (including dummy party) for threshold t
+fn check_quorum(num_selected: usize, t: usize) -> Result<(), SteError> {
     if num_selected < t + 1 {
         return Err(SteError::InvalidThreshold(
             format!(
@@ -105,220 +620,679 @@ pub fn agg_dec<E: Pairing>(
             )
         ));
     }
+    Ok(())
+}
 
-    // Cannot have more than n parties selected
-    if num_selected > n {
+/// Checks that `selector` covers the committee, selects every dummy and no
+/// padding slot.
+fn check_selector<E: Pairing>(
+    selector: &[bool],
+    agg_key: &impl DecryptionHints<E>,
+    dummies: &DummyConfig,
+) -> Result<(), SteError> {
+    let n = agg_key.n();
+    if selector.len() != n {
         return Err(SteError::ValidationError(format!(
-            "Too many parties selected: {} selected, but only {} parties exist",
-            num_selected, n
-        )));
-    }
-
-    let domain = Radix2EvaluationDomain::<E::ScalarField>::new(n).ok_or_else(|| {
-        SteError::DomainError(format!(
-            "Failed to create domain for n = {} (must be a power of 2)",
+            "selector length ({}) must equal n ({})",
+            selector.len(),
             n
-        ))
-    })?;
-    let domain_elements: Vec<E::ScalarField> = domain.elements().collect();
-
-    // points is where B is set to zero
-    // parties is the set of parties who have signed
-    let mut points = vec![domain_elements[0]]; // 0 is the dummy party that is always true
-    let mut parties: Vec<usize> = Vec::new(); // parties indexed from 0..n-1
-    for i in 0..n {
-        if selector[i] {
-            parties.push(i);
-        } else {
-            points.push(domain_elements[i]);
-        }
+        )));
     }
+    validate_committee_size(n)?;
 
-    let b = interp_mostly_zero(E::ScalarField::one(), &points);
-    let b_evals = domain.fft(&b.coeffs);
+    // Every dummy party must always be selected
+    dummies.check_selector(selector)?;
 
-    // Validate polynomial properties
-    if b.degree() != points.len() - 1 {
+    // A padding slot's share needs no secret key, so it must never count
+    if let Some(i) = (0..n).find(|&i| selector[i] && agg_key.bls_pk(i).is_zero()) {
         return Err(SteError::ValidationError(format!(
-            "b.degree() ({}) != points.len() - 1 ({})",
-            b.degree(),
-            points.len() - 1
+            "party {} is a padding slot and cannot be selected",
+            i
         )));
     }
-    if b.evaluate(&domain_elements[0]) != E::ScalarField::one() {
-        return Err(SteError::ValidationError(
-            "b(omega^0) != 1, polynomial construction failed".to_string(),
-        ));
-    }
+    Ok(())
+}
+
+/// The commitment to `bhat` and sigma: the terms of the pairing product
+/// that depend on the ciphertext.
+type CiphertextTerms<E> = (<E as Pairing>::G1, <E as Pairing>::G2);
 
-    // commit to b in g2
-    let b_g2: E::G2 = KZG10::<E, DensePolynomial<E::ScalarField>>::commit_g2(params, &b)?.into();
+/// The part of [`agg_dec`] that depends only on the selector and the
+/// aggregate key, not on the ciphertext or the partial decryptions.
+#[derive(Clone, Debug)]
+struct DecryptionPrecompute<E: Pairing> {
+    /// Selected party indices
+    parties: Vec<usize>,
+    b: DensePolynomial<E::ScalarField>,
+    b_evals: Vec<E::ScalarField>,
+    n_inv: E::ScalarField,
+    b_g2: E::G2,
+    q0_g1: E::G1,
+    apk: E::G1,
+    qx: E::G1,
+    qz: E::G1,
+    qhatx: E::G1,
+}
 
-    // q0 = (b-1)/(x-domain_elements[0])
-    let mut bminus1 = b.clone();
-    bminus1.coeffs[0] -= E::ScalarField::one();
+impl<E: Pairing> DecryptionPrecompute<E> {
+    /// Commits to the selector polynomial and runs the hint MSMs. The
+    /// selector must already have passed [`check_selector`].
+    fn compute(
+        selector: &[bool],
+        agg_key: &impl DecryptionHints<E>,
+        params: &PowersOfTau<E>,
+        dummies: &DummyConfig,
+    ) -> Result<Self, SteError> {
+        let n = agg_key.n();
+        let SelectorPolynomial {
+            domain_elements,
+            parties,
+            b,
+            b_evals,
+        } = traced!(
+            "interpolation",
+            selector_polynomial::<E::ScalarField>(selector, dummies.anchor())
+        )?;
 
-    if bminus1.evaluate(&domain_elements[0]) != E::ScalarField::zero() {
-        return Err(SteError::ValidationError(
-            "bminus1(omega^0) != 0, polynomial construction failed".to_string(),
-        ));
-    }
+        // commit to b in g2
+        let b_g2: E::G2 = traced!(
+            "b_g2_commit",
+            KZG10::<E, DensePolynomial<E::ScalarField>>::commit_g2(params, &b)
+        )?
+        .into();
 
-    let xminus1 =
-        DensePolynomial::from_coefficients_vec(vec![-domain_elements[0], E::ScalarField::one()]);
-    let q0 = bminus1.div(&xminus1);
+        // q0 = (b-1)/(x-omega^a) for the anchor dummy a
+        let anchor = domain_elements[dummies.anchor()];
+        let mut bminus1 = b.clone();
+        bminus1.coeffs[0] -= E::ScalarField::one();
 
-    let q0_g1: E::G1 = KZG10::<E, DensePolynomial<E::ScalarField>>::commit_g1(params, &q0)?.into();
+        if bminus1.evaluate(&anchor) != E::ScalarField::zero() {
+            return Err(SteError::ValidationError(
+                "bminus1(omega^a) != 0, polynomial construction failed".to_string(),
+            ));
+        }
 
-    // bhat = x^{t+1} * b
-    // insert t+1 0s at the beginning of bhat.coeffs
-    let mut bhat_coeffs = vec![E::ScalarField::zero(); ct.t + 1];
-    bhat_coeffs.append(&mut b.coeffs.clone());
-    let bhat = DensePolynomial::from_coefficients_vec(bhat_coeffs);
+        let xminus1 = DensePolynomial::from_coefficients_vec(vec![-anchor, E::ScalarField::one()]);
+        let q0 = bminus1.div(&xminus1);
 
-    if bhat.degree() != n {
-        return Err(SteError::ValidationError(format!(
-            "bhat.degree() ({}) != n ({})",
-            bhat.degree(),
-            n
-        )));
-    }
+        let q0_g1: E::G1 = traced!(
+            "q0_commit",
+            KZG10::<E, DensePolynomial<E::ScalarField>>::commit_g1(params, &q0)
+        )?
+        .into();
 
-    let bhat_g1: E::G1 =
-        KZG10::<E, DensePolynomial<E::ScalarField>>::commit_g1(params, &bhat)?.into();
+        // Convert n to field element using u64 for better precision with large values
+        let n_inv = E::ScalarField::one() / E::ScalarField::from(n as u64);
 
-    // Convert n to field element using u64 for better precision with large values
-    let n_inv = E::ScalarField::one() / E::ScalarField::from(n as u64);
+        // compute the aggregate public key
+        let apk = traced!(
+            "msm_apk",
+            apk_from_b_evals(agg_key, &parties, &b_evals, n_inv)
+        )?;
 
-    // compute the aggregate public key
-    let mut bases: Vec<<E as Pairing>::G1Affine> = Vec::with_capacity(parties.len());
-    let mut scalars: Vec<<E as Pairing>::ScalarField> = Vec::with_capacity(parties.len());
-    for &i in &parties {
-        bases.push(agg_key.pk[i].bls_pk.into());
-        scalars.push(b_evals[i]);
-    }
-    let mut apk = compute_msm_g1::<E>(bases.as_slice(), scalars.as_slice(), "apk computation")?;
-    apk *= n_inv;
-
-    // compute sigma = (\sum B(omega^i)partial_decryptions[i])/(n) for i in parties
-    bases.clear();
-    scalars.clear();
-    let mut bases_g2: Vec<<E as Pairing>::G2Affine> = Vec::with_capacity(parties.len());
-    let mut scalars_g2: Vec<<E as Pairing>::ScalarField> = Vec::with_capacity(parties.len());
-    for &i in &parties {
-        bases_g2.push(partial_decryptions[i].into());
-        scalars_g2.push(b_evals[i]);
-    }
-    let mut sigma = compute_msm_g2::<E>(
-        bases_g2.as_slice(),
-        scalars_g2.as_slice(),
-        "sigma computation",
-    )?;
-    sigma *= n_inv;
+        let mut bases: Vec<<E as Pairing>::G1Affine> = Vec::with_capacity(parties.len());
+        let mut scalars: Vec<<E as Pairing>::ScalarField> = Vec::with_capacity(parties.len());
 
-    // compute Qx, Qhatx and Qz
-    bases.clear();
-    scalars.clear();
-    for &i in &parties {
-        bases.push(agg_key.pk[i].sk_li_x.into());
-        scalars.push(b_evals[i]);
-    }
-    let qx = compute_msm_g1::<E>(bases.as_slice(), scalars.as_slice(), "qx computation")?;
+        // compute Qx, Qhatx and Qz
+        bases.clear();
+        scalars.clear();
+        for &i in &parties {
+            bases.push(agg_key.sk_li_x(i).into());
+            scalars.push(b_evals[i]);
+        }
+        let qx = traced!(
+            "msm_qx",
+            compute_msm_g1::<E>(bases.as_slice(), scalars.as_slice(), "qx computation")
+        )?;
+
+        bases.clear();
+        scalars.clear();
+        for &i in &parties {
+            bases.push(agg_key.agg_sk_li_lj_z(i).into());
+            scalars.push(b_evals[i]);
+        }
+        let qz = traced!(
+            "msm_qz",
+            compute_msm_g1::<E>(bases.as_slice(), scalars.as_slice(), "qz computation")
+        )?;
+
+        bases.clear();
+        scalars.clear();
+        for &i in &parties {
+            bases.push(agg_key.sk_li_minus0(i).into());
+            scalars.push(b_evals[i]);
+        }
+        let qhatx = traced!(
+            "msm_qhatx",
+            compute_msm_g1::<E>(bases.as_slice(), scalars.as_slice(), "qhatx computation")
+        )?;
 
-    bases.clear();
-    scalars.clear();
-    for &i in &parties {
-        bases.push(agg_key.agg_sk_li_lj_z[i].into());
-        scalars.push(b_evals[i]);
+        Ok(Self {
+            parties,
+            b,
+            b_evals,
+            n_inv,
+            b_g2,
+            q0_g1,
+            apk,
+            qx,
+            qz,
+            qhatx,
+        })
     }
-    let qz = compute_msm_g1::<E>(bases.as_slice(), scalars.as_slice(), "qz computation")?;
 
-    bases.clear();
-    scalars.clear();
-    for &i in &parties {
-        bases.push(agg_key.pk[i].sk_li_minus0.into());
-        scalars.push(b_evals[i]);
+    /// Completes the aggregation for one ciphertext: commits to `bhat`,
+    /// combines the partial decryptions and evaluates the pairing product.
+    fn finish(
+        &self,
+        partial_decryptions: &[E::G2],
+        ct: &impl CiphertextBody<E>,
+        params: &PowersOfTau<E>,
+        check_threshold: bool,
+    ) -> Result<PairingOutput<E>, SteError> {
+        let (bhat_g1, sigma) =
+            self.ciphertext_terms(partial_decryptions, ct, params, check_threshold)?;
+        Ok(self.pairing_product(ct, bhat_g1, sigma))
     }
-    let qhatx = compute_msm_g1::<E>(bases.as_slice(), scalars.as_slice(), "qhatx computation")?;
 
-    // e(w1||sa1, sa2||w2)
-    let minus1 = -E::ScalarField::one();
-    let w1 = [
-        apk * (minus1),
-        qz * (minus1),
-        qx * (minus1),
-        qhatx,
-        bhat_g1 * (minus1),
-        q0_g1 * (minus1),
-    ];
-    let w2 = [b_g2, sigma];
+    /// Computes the [`CiphertextTerms`] of `ct`.
+    fn ciphertext_terms(
+        &self,
+        partial_decryptions: &[E::G2],
+        ct: &impl CiphertextBody<E>,
+        params: &PowersOfTau<E>,
+        check_threshold: bool,
+    ) -> Result<CiphertextTerms<E>, SteError> {
+        let n = self.b_evals.len();
 
-    let mut enc_key_lhs = w1.to_vec();
-    enc_key_lhs.append(&mut ct.sa1.to_vec());
+        // bhat = x^{t+1} * b
+        // insert t+1 0s at the beginning of bhat.coeffs
+        let mut bhat_coeffs = vec![E::ScalarField::zero(); ct.t() + 1];
+        bhat_coeffs.extend_from_slice(&self.b.coeffs);
+        let bhat = DensePolynomial::from_coefficients_vec(bhat_coeffs);
 
-    let mut enc_key_rhs = ct.sa2.to_vec();
-    enc_key_rhs.append(&mut w2.to_vec());
+        // deg(bhat) = t + 1 + (n - |selected|), which reaches n exactly when t + 1
+        // parties are selected and is smaller for larger quorums
+        let bhat_g1: E::G1 = if bhat.degree() <= n {
+            traced!(
+                "bhat_commit",
+                KZG10::<E, DensePolynomial<E::ScalarField>>::commit_g1(params, &bhat)
+            )?
+            .into()
+        } else if check_threshold {
+            return Err(SteError::ValidationError(format!(
+                "bhat.degree() ({}) > n ({})",
+                bhat.degree(),
+                n
+            )));
+        } else {
+            E::G1::zero()
+        };
 
-    let enc_key = E::multi_pairing(enc_key_lhs, enc_key_rhs);
+        let sigma = traced!(
+            "msm_sigma",
+            sigma_from_b_evals::<E>(
+                partial_decryptions,
+                &self.parties,
+                &self.b_evals,
+                self.n_inv
+            )
+        )?;
 
-    if enc_key != ct.enc_key {
-        return Err(SteError::ValidationError(
-            "Decrypted key does not match encrypted key. Decryption verification failed."
-                .to_string(),
-        ));
+        Ok((bhat_g1, sigma))
     }
 
-    Ok(enc_key)
-}
+    /// e(w1||sa1, sa2||w2)
+    fn pairing_product(
+        &self,
+        ct: &impl CiphertextBody<E>,
+        bhat_g1: E::G1,
+        sigma: E::G2,
+    ) -> PairingOutput<E> {
+        let minus1 = -E::ScalarField::one();
+        let w1 = [
+            self.apk * (minus1),
+            self.qz * (minus1),
+            self.qx * (minus1),
+            self.qhatx,
+            bhat_g1 * (minus1),
+            self.q0_g1 * (minus1),
+        ];
+        let w2 = [self.b_g2, sigma];
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::{
-        encryption::encrypt,
-        kzg::KZG10,
-        setup::{PublicKey, SecretKey},
-    };
-    use ark_poly::univariate::DensePolynomial;
-    use ark_std::UniformRand;
+        let mut enc_key_lhs = w1.to_vec();
+        enc_key_lhs.append(&mut ct.sa1().to_vec());
 
-    type E = ark_bls12_381::Bls12_381;
-    type G2 = <E as Pairing>::G2;
-    type Fr = <E as Pairing>::ScalarField;
-    type UniPoly381 = DensePolynomial<<E as Pairing>::ScalarField>;
+        let mut enc_key_rhs = ct.sa2().to_vec();
+        enc_key_rhs.append(&mut w2.to_vec());
 
-    #[test]
-    fn test_decryption() {
-        let mut rng = ark_std::test_rng();
-        let n = 1 << 4; // actually n-1 total parties. one party is a dummy party that is always true
-        let t: usize = n / 2;
-        debug_assert!(t < n);
+        #[cfg(test)]
+        tests::count_pairings::<E>(&enc_key_lhs, &enc_key_rhs);
 
-        let tau = Fr::rand(&mut rng);
-        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        traced!("pairing", E::multi_pairing(enc_key_lhs, enc_key_rhs))
+    }
+}
 
-        let mut sk: Vec<SecretKey<E>> = Vec::new();
-        let mut pk: Vec<PublicKey<E>> = Vec::new();
+/// The polynomial `B` that is 1 at `omega^0` and vanishes on every unselected party.
+struct SelectorPolynomial<F: FftField> {
+    domain_elements: Vec<F>,
+    /// Selected party indices
+    parties: Vec<usize>,
+    b: DensePolynomial<F>,
+    /// Evaluations of `b` over the domain
+    b_evals: Vec<F>,
+}
 
-        // create the dummy party's keys
-        sk.push(SecretKey::<E>::new(&mut rng));
-        sk[0].nullify();
-        pk.push(sk[0].get_pk(0, &params, n).unwrap());
+fn selector_polynomial<F: FftField>(
+    selector: &[bool],
+    anchor: usize,
+) -> Result<SelectorPolynomial<F>, SteError> {
+    let n = selector.len();
+    let domain = make_domain::<F>(n)?;
+    let domain_elements: Vec<F> = domain.elements().collect();
 
-        for i in 1..n {
-            sk.push(SecretKey::<E>::new(&mut rng));
-            pk.push(sk[i].get_pk(i, &params, n).unwrap())
-        }
+    // points is where B is set to zero
+    // parties is the set of parties who have signed
+    let mut points = vec![domain_elements[anchor]]; // the anchor dummy party is always true
+    points.extend((0..n).filter(|&i| !selector[i]).map(|i| domain_elements[i]));
+    let parties = selector_to_parties(selector);
 
-        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
-        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+    let b = interp_mostly_zero(F::one(), &points);
+    let b_evals = domain.fft(&b.coeffs);
 
-        // compute partial decryptions
-        let mut partial_decryptions: Vec<G2> = Vec::new();
-        for sk_i in sk.iter().take(t + 1) {
-            partial_decryptions.push(sk_i.partial_decryption(&ct));
-        }
-        for _ in t + 1..n {
+    // Validate polynomial properties
+    if b.degree() != points.len() - 1 {
+        return Err(SteError::ValidationError(format!(
+            "b.degree() ({}) != points.len() - 1 ({})",
+            b.degree(),
+            points.len() - 1
+        )));
+    }
+    if b.evaluate(&domain_elements[anchor]) != F::one() {
+        return Err(SteError::ValidationError(
+            "b(omega^a) != 1, polynomial construction failed".to_string(),
+        ));
+    }
+
+    Ok(SelectorPolynomial {
+        domain_elements,
+        parties,
+        b,
+        b_evals,
+    })
+}
+
+/// `apk = (sum_i B(omega^i) * bls_pk_i) / n` over the selected parties.
+fn apk_from_b_evals<E: Pairing>(
+    agg_key: &impl DecryptionHints<E>,
+    parties: &[usize],
+    b_evals: &[E::ScalarField],
+    n_inv: E::ScalarField,
+) -> Result<E::G1, SteError> {
+    let bases: Vec<E::G1Affine> = parties.iter().map(|&i| agg_key.bls_pk(i).into()).collect();
+    let scalars: Vec<E::ScalarField> = parties.iter().map(|&i| b_evals[i]).collect();
+    let apk = compute_msm_g1::<E>(&bases, &scalars, "apk computation")?;
+    Ok(apk * n_inv)
+}
+
+/// Computes sigma = (\sum B(omega^i) partials[i]) / n over the selected parties.
+fn sigma_from_b_evals<E: Pairing>(
+    partials: &[E::G2],
+    parties: &[usize],
+    b_evals: &[E::ScalarField],
+    n_inv: E::ScalarField,
+) -> Result<E::G2, SteError> {
+    let bases: Vec<E::G2Affine> = parties.iter().map(|&i| partials[i].into()).collect();
+    let scalars: Vec<E::ScalarField> = parties.iter().map(|&i| b_evals[i]).collect();
+    let sigma = compute_msm_g2::<E>(&bases, &scalars, "sigma computation")?;
+    Ok(sigma * n_inv)
+}
+
+/// Computes the aggregate public key `apk` that [`agg_dec`] derives for `selector`.
+///
+/// The partial decryptions of the selected parties, weighted the same way,
+/// form a BLS signature under this key, so it can be used to check signatures
+/// produced by the selected subset of the committee.
+///
+/// # Arguments
+/// * `agg_key` - The aggregate public key
+/// * `selector` - Which parties are selected; party 0 must be selected
+///
+/// # Errors
+/// Returns an error if `selector.len() != n` or party 0 is not selected
+pub fn compute_apk<E: Pairing>(
+    agg_key: &AggregateKey<E>,
+    selector: &[bool],
+) -> Result<E::G1, SteError> {
+    let n = agg_key.pk.len();
+    if selector.len() != n {
+        return Err(SteError::ValidationError(format!(
+            "selector length ({}) must equal n ({})",
+            selector.len(),
+            n
+        )));
+    }
+    validate_committee_size(n)?;
+    if !selector[0] {
+        return Err(SteError::ValidationError(
+            "Party 0 (dummy party) must always be selected".to_string(),
+        ));
+    }
+
+    let poly = selector_polynomial::<E::ScalarField>(selector, 0)?;
+    let n_inv = E::ScalarField::one() / E::ScalarField::from(n as u64);
+    apk_from_b_evals(agg_key, &poly.parties, &poly.b_evals, n_inv)
+}
+
+/// Combines partial BLS signatures of the selected parties into one signature
+/// under [`compute_apk`] for the same selector.
+///
+/// Applied to partial decryptions this returns the `sigma` that [`agg_dec`]
+/// computes internally: an aggregate BLS signature on `ct.gamma_g2` that can be
+/// published on its own. Party 0's secret key is 1, so its entry must be the
+/// message itself (`ct.gamma_g2` for decryptions).
+///
+/// # Arguments
+/// * `partial_sigs` - `sk_i * message` for each selected party (ignored otherwise)
+/// * `selector` - Which parties signed; party 0 must be selected
+/// * `agg_key` - The aggregate public key
+///
+/// # Errors
+/// Returns an error if the lengths differ from `n` or party 0 is not selected
+pub fn aggregate_signature<E: Pairing>(
+    partial_sigs: &[E::G2],
+    selector: &[bool],
+    agg_key: &AggregateKey<E>,
+) -> Result<E::G2, SteError> {
+    let n = agg_key.pk.len();
+    if partial_sigs.len() != n || selector.len() != n {
+        return Err(SteError::ValidationError(format!(
+            "partial_sigs ({}) and selector ({}) lengths must equal n ({})",
+            partial_sigs.len(),
+            selector.len(),
+            n
+        )));
+    }
+    validate_committee_size(n)?;
+    if !selector[0] {
+        return Err(SteError::ValidationError(
+            "Party 0 (dummy party) must always be selected".to_string(),
+        ));
+    }
+
+    let poly = selector_polynomial::<E::ScalarField>(selector, 0)?;
+    let n_inv = E::ScalarField::one() / E::ScalarField::from(n as u64);
+    sigma_from_b_evals::<E>(partial_sigs, &poly.parties, &poly.b_evals, n_inv)
+}
+
+/// Encodes a partial decryption of `ct` as a standard BLS signature, for
+/// verification by other BLS libraries (e.g. `blst`) against the party's
+/// `bls_pk`.
+///
+/// Returns `(message, signature)`: `ct.gamma_g2` and `pd`, each as a
+/// compressed G2 point. On BLS12-381 this is the ZCash encoding used by the
+/// "minimal-pubkey-size" BLS variant (public keys in G1, signatures in G2):
+/// 96 bytes, the big-endian x coordinate `c1 || c0` with the three most
+/// significant bits of the first byte as flags (`0x80` compressed, `0x40`
+/// point at infinity, `0x20` y is the lexicographically larger root). The
+/// public key is `bls_pk` compressed the same way, 48 bytes.
+///
+/// The message is already a G2 point, not the output of hash-to-curve, so
+/// verify with the pairing check `e(pk, message) = e(g1, signature)` rather
+/// than an API that hashes message bytes first.
+///
+/// # Errors
+/// Returns `SteError::SerializationError` if a point fails to serialize
+pub fn partial_decryption_as_bls<E: Pairing>(
+    pd: &E::G2,
+    ct: &Ciphertext<E>,
+) -> Result<(Vec<u8>, Vec<u8>), SteError> {
+    let encode = |point: &E::G2| -> Result<Vec<u8>, SteError> {
+        let mut bytes = Vec::new();
+        point
+            .serialize_compressed(&mut bytes)
+            .map_err(|e| SteError::SerializationError(format!("Failed to encode point: {}", e)))?;
+        Ok(bytes)
+    };
+    Ok((encode(&ct.gamma_g2)?, encode(pd)?))
+}
+
+/// Verifies a signature aggregated by [`aggregate_signature`] against the
+/// committee, treating the setup as a silent threshold signature scheme.
+///
+/// Only the selected parties' keys are checked; the caller decides whether the
+/// selected set is large enough for its threshold.
+///
+/// # Returns
+/// `true` if `aggregated_sig` is a valid signature on `message` by the
+/// selected parties, `false` otherwise (including for an invalid selector)
+pub fn threshold_verify<E: Pairing>(
+    message: &E::G2,
+    aggregated_sig: &E::G2,
+    agg_key: &AggregateKey<E>,
+    selector: &[bool],
+) -> bool {
+    match compute_apk(agg_key, selector) {
+        Ok(apk) => verify_bls_signature_ct::<E>(aggregated_sig, &apk, message),
+        Err(_) => false,
+    }
+}
+
+/// Domain separation tag for partial decryption commitments.
+const PD_COMMITMENT_DST: &[u8] = b"silent-threshold-encryption/pd-commitment/v1";
+
+/// A hash commitment to one partial decryption of a ciphertext.
+///
+/// In a commit-reveal round every party first publishes the commitment from
+/// [`SecretKey::partial_decryption_commitment`](crate::setup::SecretKey::partial_decryption_commitment)
+/// and only reveals its share once all commitments are in, so no party can
+/// pick its share after seeing the others. The commitment is deterministic:
+/// it hides the share only because the share itself is unpredictable to
+/// anyone without the secret key.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Commitment([u8; 32]);
+
+impl Commitment {
+    /// Commits to the partial decryption `pd` of `ct`.
+    pub fn new<E: Pairing>(ct: &Ciphertext<E>, pd: &E::G2) -> Self {
+        let mut hasher = Blake2b512::new();
+        hasher.update(PD_COMMITMENT_DST);
+        let mut bytes = Vec::new();
+        ct.gamma_g2
+            .serialize_compressed(&mut bytes)
+            .expect("serializing into a Vec cannot fail");
+        pd.serialize_compressed(&mut bytes)
+            .expect("serializing into a Vec cannot fail");
+        hasher.update(&bytes);
+
+        let mut digest = [0u8; 32];
+        digest.copy_from_slice(&hasher.finalize()[..32]);
+        Self(digest)
+    }
+
+    /// Wraps commitment bytes received from another party.
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// The commitment bytes to publish.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Checks that a revealed partial decryption is the one committed to.
+    ///
+    /// This only binds the reveal to the commitment; whether the share is a
+    /// correct partial decryption is still checked when aggregating.
+    ///
+    /// # Errors
+    /// Returns `SteError::ValidationError` if `pd` does not open the commitment
+    pub fn verify_reveal<E: Pairing>(
+        &self,
+        ct: &Ciphertext<E>,
+        pd: &E::G2,
+    ) -> Result<(), SteError> {
+        if Self::new(ct, pd) != *self {
+            return Err(SteError::ValidationError(
+                "revealed partial decryption does not match its commitment".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Aggregates partial decryptions and returns the recovered key in a zeroizing wrapper.
+///
+/// Behaves exactly like [`agg_dec`], but the result is wrapped in a
+/// [`RecoveredKey`] so that it is erased from memory once dropped.
+///
+/// # Errors
+/// Returns the same errors as [`agg_dec`]
+pub fn agg_dec_sealed<E: Pairing>(
+    partial_decryptions: &[E::G2],
+    ct: &Ciphertext<E>,
+    selector: &[bool],
+    agg_key: &AggregateKey<E>,
+    params: &PowersOfTau<E>,
+) -> Result<RecoveredKey<E>, SteError> {
+    agg_dec(partial_decryptions, ct, selector, agg_key, params).map(RecoveredKey::new)
+}
+
+/// Recovers the key of a ciphertext published as an [`Encapsulation`].
+///
+/// Unlike [`agg_dec`] there is no `enc_key` to compare the result with, so a
+/// bad partial decryption yields a wrong key instead of an error. Use the key
+/// only with an authenticated cipher, which rejects a wrong one.
+///
+/// # Errors
+/// Returns the errors of [`agg_dec`], except the key mismatch
+pub fn agg_dec_encapsulated<E: Pairing>(
+    partial_decryptions: &[E::G2],
+    enc: &Encapsulation<E>,
+    selector: &[bool],
+    agg_key: &AggregateKey<E>,
+    params: &PowersOfTau<E>,
+) -> Result<RecoveredKey<E>, SteError> {
+    aggregate(
+        partial_decryptions,
+        enc,
+        selector,
+        agg_key,
+        params,
+        &DummyConfig::default(),
+        true,
+    )
+    .map(RecoveredKey::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        encryption::{
+            encrypt, encrypt_deterministic, encrypt_from_bundle, encrypt_with_dummies, ExportBundle,
+        },
+        kzg::KZG10,
+        security::DefaultKdf,
+        setup::{EncryptOnlyKey, PublicKey, SecretKey},
+    };
+    use ark_poly::univariate::DensePolynomial;
+    use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+    use ark_std::{
+        rand::{rngs::StdRng, SeedableRng},
+        UniformRand,
+    };
+
+    type E = ark_bls12_381::Bls12_381;
+    type G1 = <E as Pairing>::G1;
+    type G2 = <E as Pairing>::G2;
+    type Fr = <E as Pairing>::ScalarField;
+    type UniPoly381 = DensePolynomial<<E as Pairing>::ScalarField>;
+
+    thread_local! {
+        static PAIRINGS: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+    }
+
+    /// Records the pairs `multi_pairing` will evaluate; like arkworks, pairs
+    /// with an identity on either side are skipped.
+    pub(super) fn count_pairings<P: Pairing>(lhs: &[P::G1], rhs: &[P::G2]) {
+        let evaluated = lhs
+            .iter()
+            .zip(rhs)
+            .filter(|(p, q)| !p.is_zero() && !q.is_zero())
+            .count();
+        PAIRINGS.with(|count| count.set(count.get() + evaluated));
+    }
+
+    #[test]
+    fn test_agg_dec_pairing_count_matches_instrumented_run() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let mut sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::new(&mut rng)).collect();
+        sk[0].nullify();
+        let pk = (0..n)
+            .map(|i| sk[i].get_pk(i, &params, n).unwrap())
+            .collect();
+        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+        let partials: Vec<G2> = sk.iter().map(|sk| sk.partial_decryption(&ct)).collect();
+
+        let quorum =
+            |parties: &[usize]| -> Vec<bool> { (0..n).map(|i| parties.contains(&i)).collect() };
+        for selector in [
+            quorum(&[0, 1, 2, 3]),
+            quorum(&[0, 2, 5, 6, 7]),
+            vec![true; n],
+            quorum(&[0, 1, 2]),
+            quorum(&[1, 2, 3, 4]),
+        ] {
+            PAIRINGS.with(|count| count.set(0));
+            let result = agg_dec(&partials, &ct, &selector, &agg_key, &params);
+            let expected = agg_dec_pairing_count(n, t, &selector);
+            assert_eq!(PAIRINGS.with(|count| count.get()), expected, "{selector:?}");
+            assert_eq!(result.is_ok(), expected > 0, "{selector:?}");
+        }
+
+        assert_eq!(agg_dec_pairing_count(n, t, &[true; 4]), 0);
+        assert_eq!(agg_dec_pairing_count(n, n, &vec![true; n]), 0);
+        assert_eq!(agg_dec_pairing_count(6, 2, &[true; 6]), 0);
+    }
+
+    #[test]
+    fn test_decryption() {
+        let mut rng = ark_std::test_rng();
+        let n = 1 << 4; // actually n-1 total parties. one party is a dummy party that is always true
+        let t: usize = n / 2;
+        debug_assert!(t < n);
+
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+
+        let mut sk: Vec<SecretKey<E>> = Vec::new();
+        let mut pk: Vec<PublicKey<E>> = Vec::new();
+
+        // create the dummy party's keys
+        sk.push(SecretKey::<E>::new(&mut rng));
+        sk[0].nullify();
+        pk.push(sk[0].get_pk(0, &params, n).unwrap());
+
+        for i in 1..n {
+            sk.push(SecretKey::<E>::new(&mut rng));
+            pk.push(sk[i].get_pk(i, &params, n).unwrap())
+        }
+
+        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+
+        // compute partial decryptions
+        let mut partial_decryptions: Vec<G2> = Vec::new();
+        for sk_i in sk.iter().take(t + 1) {
+            partial_decryptions.push(sk_i.partial_decryption(&ct));
+        }
+        for _ in t + 1..n {
             partial_decryptions.push(G2::zero());
         }
 
@@ -329,4 +1303,851 @@ mod tests {
 
         let _dec_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
     }
+
+    #[test]
+    fn test_full_cycle_for_several_sizes() {
+        let mut rng = ark_std::test_rng();
+        for (n, t) in [(2, 1), (4, 2), (8, 1), (8, 7), (16, 8)] {
+            assert!(
+                crate::testing::run_full_cycle::<E, _>(n, t, &mut rng).unwrap(),
+                "n={n}, t={t}"
+            );
+        }
+        assert!(crate::testing::run_full_cycle::<E, _>(6, 2, &mut rng).is_err());
+        assert!(crate::testing::run_full_cycle::<E, _>(8, 8, &mut rng).is_err());
+    }
+
+    /// Builds a committee of `n` parties with party 0 as the dummy party.
+    fn setup_committee(
+        n: usize,
+        rng: &mut impl ark_std::rand::RngCore,
+    ) -> (PowersOfTau<E>, Vec<SecretKey<E>>, AggregateKey<E>) {
+        setup_committee_with_dummies(n, &DummyConfig::default(), rng)
+    }
+
+    /// Builds a committee of `n` parties whose dummy parties are `dummies`.
+    fn setup_committee_with_dummies(
+        n: usize,
+        dummies: &DummyConfig,
+        rng: &mut impl ark_std::rand::RngCore,
+    ) -> (PowersOfTau<E>, Vec<SecretKey<E>>, AggregateKey<E>) {
+        let tau = Fr::rand(rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+
+        let mut sk: Vec<SecretKey<E>> = Vec::new();
+        let mut pk: Vec<PublicKey<E>> = Vec::new();
+        for i in 0..n {
+            sk.push(SecretKey::<E>::new(rng));
+            if dummies.indices().contains(&i) {
+                sk[i].nullify();
+            }
+            pk.push(sk[i].get_pk(i, &params, n).unwrap());
+        }
+
+        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+        (params, sk, agg_key)
+    }
+
+    /// Collects partial decryptions from the first `k` parties.
+    fn first_k_partials(sk: &[SecretKey<E>], ct: &Ciphertext<E>, k: usize) -> (Vec<G2>, Vec<bool>) {
+        partials_from(sk, ct, &(0..k).collect::<Vec<_>>())
+    }
+
+    /// Collects partial decryptions from the given parties.
+    fn partials_from(
+        sk: &[SecretKey<E>],
+        ct: &Ciphertext<E>,
+        parties: &[usize],
+    ) -> (Vec<G2>, Vec<bool>) {
+        let n = sk.len();
+        let mut partial_decryptions = vec![G2::zero(); n];
+        let mut selector = vec![false; n];
+        for &i in parties {
+            selector[i] = true;
+            partial_decryptions[i] = sk[i].partial_decryption(ct);
+        }
+        (partial_decryptions, selector)
+    }
+
+    #[test]
+    fn test_decryption_max_threshold() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = n - 1;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+
+        // every party, including the dummy party, is required
+        let (partial_decryptions, selector) = first_k_partials(&sk, &ct, n);
+        let dec_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+        assert_eq!(dec_key, ct.enc_key);
+
+        let (partial_decryptions, selector) = first_k_partials(&sk, &ct, n - 1);
+        let err = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params)
+            .expect_err("expected decryption to fail with n - 1 parties at t = n - 1");
+        assert!(
+            matches!(err, SteError::InvalidThreshold(_)),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn test_decryption_with_more_than_t_plus_one_parties() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 2;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+
+        for k in t + 1..=n {
+            let (partial_decryptions, selector) = first_k_partials(&sk, &ct, k);
+            let dec_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+            assert_eq!(dec_key, ct.enc_key, "decryption failed with {} parties", k);
+        }
+    }
+
+    #[test]
+    fn test_agg_dec_sealed() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+        let (partial_decryptions, selector) = first_k_partials(&sk, &ct, t + 1);
+
+        let plain = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+        let sealed =
+            agg_dec_sealed(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+
+        assert_eq!(sealed.expose(), &plain);
+        assert_eq!(sealed.derive_symmetric_key::<DefaultKdf>().len(), 32);
+
+        let debug_str = format!("{:?}", sealed);
+        assert!(debug_str.contains("[REDACTED]"));
+        assert!(!debug_str.contains(&format!("{:?}", plain)));
+    }
+
+    #[test]
+    fn test_agg_dec_encapsulated_matches_agg_dec() {
+        use ark_serialize::CanonicalSerialize;
+
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+        let (partial_decryptions, selector) = first_k_partials(&sk, &ct, t + 1);
+        let plain = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+
+        let (enc, key) = ct.into_encapsulation();
+        let mut bytes = Vec::new();
+        enc.serialize_compressed(&mut bytes).unwrap();
+        let enc = Encapsulation::<E>::deserialize_validated(&bytes).unwrap();
+        assert_eq!(
+            sk[1].partial_decryption_encapsulated(&enc),
+            partial_decryptions[1]
+        );
+
+        let recovered =
+            agg_dec_encapsulated(&partial_decryptions, &enc, &selector, &agg_key, &params).unwrap();
+        assert_eq!(recovered.expose(), &plain);
+        assert_eq!(key.expose(), &plain);
+
+        // The quorum is still enforced without a key to compare against
+        let mut short = selector.clone();
+        short[t] = false;
+        assert!(
+            agg_dec_encapsulated(&partial_decryptions, &enc, &short, &agg_key, &params).is_err()
+        );
+    }
+
+    #[test]
+    fn test_encrypt_only_key_decrypts_under_full_key() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+
+        let mut full_bytes = Vec::new();
+        agg_key.serialize_compressed(&mut full_bytes).unwrap();
+        let bytes = agg_key.serialize_encrypt_only().unwrap();
+        assert!(bytes.len() < full_bytes.len());
+
+        let encrypt_key = EncryptOnlyKey::<E>::from_bytes(&bytes).unwrap();
+        assert_eq!(encrypt_key, agg_key.encrypt_only());
+
+        for _ in 0..3 {
+            let ct = encrypt::<E, _>(&encrypt_key, t, &params, &mut rng).unwrap();
+            let (partial_decryptions, selector) = first_k_partials(&sk, &ct, t + 1);
+            let dec_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+            assert_eq!(dec_key, ct.enc_key);
+        }
+    }
+
+    #[test]
+    fn test_export_bundle_decrypts_under_full_key() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+
+        let bundle = ExportBundle::for_publisher(&agg_key, &params).unwrap();
+        let mut bytes = Vec::new();
+        bundle.serialize_compressed(&mut bytes).unwrap();
+        assert!(bytes.len() < agg_key.compressed_size() + params.compressed_size());
+        let bundle = ExportBundle::<E>::deserialize_compressed(&bytes[..]).unwrap();
+
+        for t in [1, 3, n - 1] {
+            let ct = encrypt_from_bundle(&bundle, t, &mut rng).unwrap();
+            let (partial_decryptions, selector) = first_k_partials(&sk, &ct, t + 1);
+            let dec_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+            assert_eq!(dec_key, ct.enc_key);
+        }
+    }
+
+    #[test]
+    fn test_verify_decryption_transcript() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+        let (partial_decryptions, selector) = first_k_partials(&sk, &ct, t + 1);
+        let result = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+
+        assert!(verify_decryption(
+            &result,
+            &partial_decryptions,
+            &ct,
+            &selector,
+            &agg_key,
+            &params
+        ));
+
+        let doctored = result + agg_key.e_gh;
+        assert!(!verify_decryption(
+            &doctored,
+            &partial_decryptions,
+            &ct,
+            &selector,
+            &agg_key,
+            &params
+        ));
+
+        // A forged share makes the transcript fail even for the true result
+        let mut forged = partial_decryptions.clone();
+        forged[1] = G2::rand(&mut rng);
+        assert!(!verify_decryption(
+            &result, &forged, &ct, &selector, &agg_key, &params
+        ));
+    }
+
+    #[test]
+    fn test_batch_verify_partials_finds_bad_share() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+
+        let mut shares: HashMap<usize, G2> = [0, 2, 5, 7]
+            .into_iter()
+            .map(|i| (i, sk[i].partial_decryption(&ct)))
+            .collect();
+        assert!(batch_verify_partials(&shares, &agg_key, &ct).is_empty());
+
+        shares.insert(5, G2::rand(&mut rng));
+        assert_eq!(batch_verify_partials(&shares, &agg_key, &ct), vec![5]);
+
+        // Another party's valid share is not valid under this party's key
+        shares.insert(2, sk[3].partial_decryption(&ct));
+        shares.insert(n, G2::zero());
+        assert_eq!(batch_verify_partials(&shares, &agg_key, &ct), vec![2, 5, n]);
+    }
+
+    #[test]
+    fn test_decryption_below_threshold_fails() {
+        let n = 8;
+        let t = 3;
+        for seed in 0..5u64 {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let (params, sk, agg_key) = setup_committee(n, &mut rng);
+            let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+
+            for k in 1..=t {
+                let (partial_decryptions, selector) = first_k_partials(&sk, &ct, k);
+                let err = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params)
+                    .expect_err("agg_dec must reject a quorum below t + 1");
+                assert!(matches!(err, SteError::InvalidThreshold(_)), "{err:?}");
+
+                let attempt = attempt_dec_below_threshold(
+                    &partial_decryptions,
+                    &ct,
+                    &selector,
+                    &agg_key,
+                    &params,
+                )
+                .unwrap();
+                assert_ne!(attempt, ct.enc_key, "seed {} with {} parties", seed, k);
+            }
+
+            let (partial_decryptions, selector) = first_k_partials(&sk, &ct, t + 1);
+            assert!(attempt_dec_below_threshold(
+                &partial_decryptions,
+                &ct,
+                &selector,
+                &agg_key,
+                &params
+            )
+            .is_err());
+        }
+    }
+
+    #[test]
+    fn test_threshold_signature() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let (_, sk, agg_key) = setup_committee(n, &mut rng);
+        let message = G2::rand(&mut rng);
+
+        // t + 1 = 4 signers for t = 3, including the dummy party
+        let mut selector = vec![false; n];
+        let mut partial_sigs = vec![G2::zero(); n];
+        for i in [0, 2, 5, 7] {
+            selector[i] = true;
+            partial_sigs[i] = sk[i].sign(&message);
+        }
+        let sig = aggregate_signature(&partial_sigs, &selector, &agg_key).unwrap();
+        assert!(threshold_verify(&message, &sig, &agg_key, &selector));
+
+        // Wrong message, wrong selector, or a forged share
+        assert!(!threshold_verify(
+            &G2::rand(&mut rng),
+            &sig,
+            &agg_key,
+            &selector
+        ));
+        let mut other = selector.clone();
+        other[1] = true;
+        assert!(!threshold_verify(&message, &sig, &agg_key, &other));
+        partial_sigs[2] = G2::rand(&mut rng);
+        let forged = aggregate_signature(&partial_sigs, &selector, &agg_key).unwrap();
+        assert!(!threshold_verify(&message, &forged, &agg_key, &selector));
+    }
+
+    #[test]
+    fn test_commit_reveal_partial_decryptions() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+
+        // Commit round, then reveal round
+        let commitments: Vec<Commitment> = (0..=t)
+            .map(|i| sk[i].partial_decryption_commitment(&ct))
+            .collect();
+        let mut selector = vec![false; n];
+        let mut partial_decryptions = vec![G2::zero(); n];
+        for (i, commitment) in commitments.iter().enumerate() {
+            let pd = sk[i].partial_decryption(&ct);
+            commitment.verify_reveal(&ct, &pd).unwrap();
+            selector[i] = true;
+            partial_decryptions[i] = pd;
+        }
+        let dec_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+        assert_eq!(dec_key, ct.enc_key);
+
+        // A reveal that differs from the committed share is rejected
+        assert!(matches!(
+            commitments[1].verify_reveal(&ct, &G2::rand(&mut rng)),
+            Err(SteError::ValidationError(_))
+        ));
+        assert!(commitments[1]
+            .verify_reveal(&ct, &partial_decryptions[2])
+            .is_err());
+
+        // Commitments are bound to the ciphertext
+        let other_ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+        assert!(commitments[1]
+            .verify_reveal(&other_ct, &partial_decryptions[1])
+            .is_err());
+    }
+
+    /// Records the name of every span created while it is the default subscriber.
+    #[cfg(feature = "tracing")]
+    struct SpanRecorder(std::sync::Mutex<Vec<&'static str>>);
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for SpanRecorder {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            let mut names = self.0.lock().unwrap();
+            names.push(span.metadata().name());
+            tracing::span::Id::from_u64(names.len() as u64)
+        }
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+        fn event(&self, _: &tracing::Event<'_>) {}
+        fn enter(&self, _: &tracing::span::Id) {}
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_encrypt_and_agg_dec_emit_phase_spans() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+
+        let recorder = std::sync::Arc::new(SpanRecorder(Default::default()));
+        tracing::subscriber::with_default(recorder.clone(), || {
+            let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+            let mut selector = vec![false; n];
+            let mut partial_decryptions = vec![G2::zero(); n];
+            for i in 0..=t {
+                selector[i] = true;
+                partial_decryptions[i] = sk[i].partial_decryption(&ct);
+            }
+            let dec_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params);
+            assert_eq!(dec_key.unwrap(), ct.enc_key);
+        });
+
+        let names = recorder.0.lock().unwrap().clone();
+        assert_eq!(
+            names,
+            [
+                "encrypt",
+                "gamma_g2",
+                "sa1",
+                "sa2",
+                "enc_key",
+                "agg_dec",
+                "interpolation",
+                "b_g2_commit",
+                "q0_commit",
+                "msm_apk",
+                "msm_qx",
+                "msm_qz",
+                "msm_qhatx",
+                "bhat_commit",
+                "msm_sigma",
+                "pairing",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_aggregate_signature_of_partial_decryptions() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+        let (partial_decryptions, selector) = first_k_partials(&sk, &ct, t + 2);
+
+        let sigma = aggregate_signature(&partial_decryptions, &selector, &agg_key).unwrap();
+        let apk = compute_apk(&agg_key, &selector).unwrap();
+        assert!(verify_bls_signature_ct::<E>(&sigma, &apk, &ct.gamma_g2));
+
+        assert!(aggregate_signature(&partial_decryptions[..4], &selector, &agg_key).is_err());
+    }
+
+    #[test]
+    fn test_encrypt_deterministic_is_reproducible() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+
+        let bytes = |ct: &Ciphertext<E>| {
+            let mut bytes = Vec::new();
+            ct.serialize_compressed(&mut bytes).unwrap();
+            bytes
+        };
+        let ct1 = encrypt_deterministic::<E>(&agg_key, t, &params, b"seed").unwrap();
+        let ct2 = encrypt_deterministic::<E>(&agg_key, t, &params, b"seed").unwrap();
+        let other = encrypt_deterministic::<E>(&agg_key, t, &params, b"other seed").unwrap();
+        assert_eq!(bytes(&ct1), bytes(&ct2));
+        assert_ne!(bytes(&ct1), bytes(&other));
+
+        for ct in [&ct1, &ct2] {
+            let (partial_decryptions, selector) = first_k_partials(&sk, ct, t + 1);
+            let dec_key = agg_dec(&partial_decryptions, ct, &selector, &agg_key, &params).unwrap();
+            assert_eq!(dec_key, ct.enc_key);
+        }
+    }
+
+    #[test]
+    fn test_decrypt_only_key_matches_full_key() {
+        let mut rng = ark_std::test_rng();
+        let n = 16;
+        let t = 5;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+        let (partial_decryptions, selector) = first_k_partials(&sk, &ct, t + 3);
+
+        let key = agg_key.decrypt_only();
+        let mut bytes = Vec::new();
+        key.serialize_compressed(&mut bytes).unwrap();
+        assert!(bytes.len() < agg_key.compressed_size() / 4);
+        let key = DecryptOnlyKey::<E>::deserialize_compressed(&bytes[..]).unwrap();
+
+        let expected = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+        let dec_key =
+            agg_dec_with_decrypt_key(&partial_decryptions, &ct, &selector, &key, &params).unwrap();
+        assert_eq!(dec_key, expected);
+
+        let (short, short_selector) = first_k_partials(&sk, &ct, t);
+        assert!(agg_dec_with_decrypt_key(&short, &ct, &short_selector, &key, &params).is_err());
+
+        let mut truncated = key.clone();
+        truncated.sk_li_x.pop();
+        assert!(agg_dec_with_decrypt_key(
+            &partial_decryptions,
+            &ct,
+            &selector,
+            &truncated,
+            &params
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_dummy_party_at_index_one() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+
+        let dummies = DummyConfig::new(vec![1]).unwrap();
+        let (params, sk, agg_key) = setup_committee_with_dummies(n, &dummies, &mut rng);
+        let ct = encrypt_with_dummies(&agg_key, t, &params, &dummies, &mut rng).unwrap();
+
+        let (pds, selector) = partials_from(&sk, &ct, &[1, 2, 5, 6]);
+        let dec_key =
+            agg_dec_with_dummies(&pds, &ct, &selector, &agg_key, &params, &dummies).unwrap();
+        assert_eq!(dec_key, ct.enc_key);
+
+        // A quorum of the same size without the dummy is rejected
+        let (pds, selector) = partials_from(&sk, &ct, &[0, 2, 5, 6]);
+        let err =
+            agg_dec_with_dummies(&pds, &ct, &selector, &agg_key, &params, &dummies).unwrap_err();
+        assert!(
+            matches!(err, SteError::ValidationError(ref msg) if msg.contains("Party 1 (dummy party)")),
+            "{err:?}"
+        );
+
+        // Two dummies: both must be selected, and t must leave room for a real party
+        let dummies = DummyConfig::new(vec![5, 1]).unwrap();
+        assert_eq!(dummies.anchor(), 1);
+        let (params, sk, agg_key) = setup_committee_with_dummies(n, &dummies, &mut rng);
+        let ct = encrypt_with_dummies(&agg_key, t, &params, &dummies, &mut rng).unwrap();
+        let (pds, selector) = partials_from(&sk, &ct, &[1, 5, 3, 7]);
+        let dec_key =
+            agg_dec_with_dummies(&pds, &ct, &selector, &agg_key, &params, &dummies).unwrap();
+        assert_eq!(dec_key, ct.enc_key);
+        let (pds, selector) = partials_from(&sk, &ct, &[1, 2, 3, 7]);
+        assert!(agg_dec_with_dummies(&pds, &ct, &selector, &agg_key, &params, &dummies).is_err());
+        assert!(encrypt_with_dummies(&agg_key, 1, &params, &dummies, &mut rng).is_err());
+
+        let out_of_range = DummyConfig::new(vec![n]).unwrap();
+        assert!(encrypt_with_dummies(&agg_key, t, &params, &out_of_range, &mut rng).is_err());
+        assert!(DummyConfig::new(vec![]).is_err());
+        assert!(DummyConfig::new(vec![2, 2]).is_err());
+    }
+
+    #[test]
+    fn test_committee_bls_key_verifies_externally() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+        let (partial_decryptions, selector) = first_k_partials(&sk, &ct, t + 1);
+
+        let committee_pk = agg_key.committee_bls_key(&selector).unwrap();
+        let sigma = aggregate_signature(&partial_decryptions, &selector, &agg_key).unwrap();
+
+        // A plain BLS verifier: e(g, sigma) == e(pk, H(m)), with gamma_g2 as H(m)
+        assert_eq!(
+            E::pairing(G1::generator(), sigma),
+            E::pairing(committee_pk, ct.gamma_g2)
+        );
+        assert_ne!(
+            committee_pk,
+            agg_key.committee_bls_key(&vec![true; n]).unwrap()
+        );
+
+        let mut without_dummy = selector.clone();
+        without_dummy[0] = false;
+        assert!(agg_key.committee_bls_key(&without_dummy).is_err());
+    }
+
+    #[test]
+    fn test_fixed_selector_key_matches_agg_dec() {
+        let mut rng = ark_std::test_rng();
+        let n = 16;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let selector: Vec<bool> = (0..n).map(|i| i % 3 == 0).collect();
+        let key = agg_key.fix_selector(&selector, &params).unwrap();
+        assert_eq!(key.selector(), &selector[..]);
+        assert!(key.matches(&selector));
+        assert!(!key.matches(&vec![true; n]));
+
+        // The same quorum of 6 serves every threshold up to 5
+        for t in [1, 3, 5] {
+            let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+            let partial_decryptions: Vec<G2> = (0..n)
+                .map(|i| {
+                    if selector[i] {
+                        sk[i].partial_decryption(&ct)
+                    } else {
+                        G2::zero()
+                    }
+                })
+                .collect();
+            let expected =
+                agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+            let dec_key = agg_dec_with(&partial_decryptions, &ct, &key, &params).unwrap();
+            assert_eq!(dec_key, expected);
+            assert_eq!(dec_key, ct.enc_key);
+        }
+
+        let ct = encrypt::<E, _>(&agg_key, 6, &params, &mut rng).unwrap();
+        let err = agg_dec_with(&vec![G2::zero(); n], &ct, &key, &params).unwrap_err();
+        assert!(matches!(err, SteError::InvalidThreshold(_)), "{err:?}");
+
+        let mut without_dummy = selector.clone();
+        without_dummy[0] = false;
+        assert!(agg_key.fix_selector(&without_dummy, &params).is_err());
+    }
+
+    #[test]
+    fn test_agg_dec_batch_matches_agg_dec() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let selector: Vec<bool> = (0..n).map(|i| i != 3 && i != 6).collect();
+        let key = agg_key.fix_selector(&selector, &params).unwrap();
+
+        let ciphertexts: Vec<Ciphertext<E>> = (0..10)
+            .map(|i| encrypt::<E, _>(&agg_key, 1 + i % 5, &params, &mut rng).unwrap())
+            .collect();
+        let mut partials_per_ct: Vec<Vec<G2>> = ciphertexts
+            .iter()
+            .map(|ct| {
+                (0..n)
+                    .map(|i| {
+                        if selector[i] {
+                            sk[i].partial_decryption(ct)
+                        } else {
+                            G2::zero()
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // The combined check passes, so no item is paired on its own
+        PAIRINGS.with(|count| count.set(0));
+        let results = agg_dec_batch(&key, &ciphertexts, &partials_per_ct, &params);
+        assert_eq!(PAIRINGS.with(|count| count.get()), 0);
+        assert_eq!(results.len(), ciphertexts.len());
+        for ((result, ct), partials) in results.iter().zip(&ciphertexts).zip(&partials_per_ct) {
+            let expected = agg_dec(partials, ct, &selector, &agg_key, &params).unwrap();
+            assert_eq!(result.as_ref().unwrap(), &expected);
+        }
+
+        // A bad share fails only its own item
+        partials_per_ct[4][1] = partials_per_ct[5][1];
+        let results = agg_dec_batch(&key, &ciphertexts, &partials_per_ct, &params);
+        for (i, (result, ct)) in results.iter().zip(&ciphertexts).enumerate() {
+            if i == 4 {
+                assert!(result.is_err());
+            } else {
+                assert_eq!(result.as_ref().unwrap(), &ct.enc_key);
+            }
+        }
+
+        let results = agg_dec_batch(&key, &ciphertexts, &partials_per_ct[..9], &params);
+        assert!(results.iter().all(Result::is_err));
+    }
+
+    #[test]
+    fn test_refreshed_keys_cannot_decrypt_old_ciphertexts() {
+        use crate::setup::LagrangePowers;
+
+        let mut rng = ark_std::test_rng();
+        let (n, t) = (8, 3);
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+        let mut sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::new(&mut rng)).collect();
+        sk[0].nullify();
+        let pk: Vec<PublicKey<E>> = sk
+            .iter()
+            .enumerate()
+            .map(|(i, sk)| sk.lagrange_get_pk(i, &lagrange_params, n).unwrap())
+            .collect();
+        let mut agg_key = AggregateKey::<E>::new(pk.clone(), &params).unwrap();
+        let old_ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+
+        // The dummy party cannot refresh
+        let dummy_delta = SecretKey::<E>::new(&mut rng)
+            .lagrange_get_pk(0, &lagrange_params, n)
+            .unwrap();
+        assert!(agg_key.apply_refresh(&dummy_delta, &params).is_err());
+
+        // Deltas whose hints don't match their bls_pk are rejected before
+        // they touch the aggregate key
+        let honest = SecretKey::<E>::new(&mut rng)
+            .lagrange_get_pk(2, &lagrange_params, n)
+            .unwrap();
+        let mut bad_hint = honest.clone();
+        bad_hint.sk_li_lj_z[5] += G1::generator();
+        let mut bad_li = honest.clone();
+        bad_li.sk_li = honest.sk_li * Fr::from(2u64);
+        let mut other_slot = SecretKey::<E>::new(&mut rng)
+            .lagrange_get_pk(3, &lagrange_params, n)
+            .unwrap();
+        other_slot.id = 2;
+        for bad in [bad_hint, bad_li, other_slot] {
+            let err = agg_key.apply_refresh(&bad, &params).unwrap_err();
+            assert!(
+                matches!(err, SteError::ValidationError(ref msg) if msg.contains("does not match")),
+                "{err:?}"
+            );
+        }
+        assert!(agg_key.verify(&params).is_ok());
+        assert_eq!(agg_key.pk[2].bls_pk, pk[2].bls_pk);
+
+        for (i, sk) in sk.iter_mut().enumerate().skip(1) {
+            let delta = sk.refresh(i, &lagrange_params, n, &mut rng).unwrap();
+            agg_key.apply_refresh(&delta, &params).unwrap();
+        }
+        assert!(agg_key.verify(&params).is_ok());
+        for (i, sk) in sk.iter().enumerate() {
+            let expected = sk.lagrange_get_pk(i, &lagrange_params, n).unwrap();
+            assert_eq!(agg_key.pk[i].bls_pk, expected.bls_pk);
+            assert_eq!(agg_key.pk[i].sk_li_lj_z, expected.sk_li_lj_z);
+        }
+        assert_ne!(agg_key.pk[1].bls_pk, pk[1].bls_pk);
+
+        // Same n and t, so new ciphertexts decrypt with the refreshed keys
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+        let (partial_decryptions, selector) = first_k_partials(&sk, &ct, t + 1);
+        let dec_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+        assert_eq!(dec_key, ct.enc_key);
+
+        // Old ciphertexts do not
+        let (partial_decryptions, selector) = first_k_partials(&sk, &old_ct, t + 1);
+        assert!(agg_dec(&partial_decryptions, &old_ct, &selector, &agg_key, &params).is_err());
+    }
+
+    #[test]
+    fn test_partial_decryption_as_bls_verifies() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let ct = encrypt::<E, _>(&agg_key, 3, &params, &mut rng).unwrap();
+        let pd = sk[2].partial_decryption(&ct);
+
+        let (message, signature) = partial_decryption_as_bls(&pd, &ct).unwrap();
+        let mut pk = Vec::new();
+        agg_key.pk[2].bls_pk.serialize_compressed(&mut pk).unwrap();
+        assert_eq!((message.len(), signature.len(), pk.len()), (96, 96, 48));
+        for bytes in [&message, &signature, &pk] {
+            assert_eq!(bytes[0] & 0xc0, 0x80, "compressed, not infinity");
+        }
+
+        // What an external verifier would decode
+        let message = G2::deserialize_compressed(&message[..]).unwrap();
+        let signature = G2::deserialize_compressed(&signature[..]).unwrap();
+        let pk = G1::deserialize_compressed(&pk[..]).unwrap();
+        assert!(verify_bls_signature_ct::<E>(&signature, &pk, &message));
+        assert!(!verify_bls_signature_ct::<E>(
+            &signature,
+            &agg_key.pk[3].bls_pk,
+            &message
+        ));
+    }
+
+    #[test]
+    fn test_selecting_padding_slot_is_rejected() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+
+        // Parties 6 and 7 are absent and padded with empty keys
+        let mut sk: Vec<SecretKey<E>> = (0..6).map(|_| SecretKey::new(&mut rng)).collect();
+        sk[0].nullify();
+        let mut pk: Vec<PublicKey<E>> = sk
+            .iter()
+            .enumerate()
+            .map(|(i, sk)| sk.get_pk(i, &params, n).unwrap())
+            .collect();
+        pk.extend((6..n).map(|i| PublicKey::zero_for_domain(i, n)));
+        assert!(pk[6].is_padding() && !pk[0].is_padding());
+        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+
+        let mut partial_decryptions = vec![G2::zero(); n];
+        let mut selector = vec![false; n];
+        for i in 0..=t {
+            selector[i] = true;
+            partial_decryptions[i] = sk[i].partial_decryption(&ct);
+        }
+        let dec_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+        assert_eq!(dec_key, ct.enc_key);
+
+        // Swap a real party for a padding slot, whose share is the identity
+        selector[t] = false;
+        partial_decryptions[t] = G2::zero();
+        selector[6] = true;
+        let err = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap_err();
+        assert!(matches!(err, SteError::ValidationError(_)), "{err:?}");
+    }
+
+    #[test]
+    fn test_agg_dec_rejects_out_of_range_ciphertext_threshold() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let (params, sk, agg_key) = setup_committee(n, &mut rng);
+        let mut ct = encrypt::<E, _>(&agg_key, 3, &params, &mut rng).unwrap();
+        let (partial_decryptions, selector) = first_k_partials(&sk, &ct, n);
+
+        for t in [0, n, n + 5] {
+            ct.t = t;
+            let err = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params)
+                .expect_err("agg_dec must reject an out-of-range threshold");
+            assert!(
+                matches!(err, SteError::InvalidThreshold(ref msg) if msg.contains("ciphertext threshold")),
+                "t={}: {err:?}",
+                t
+            );
+        }
+    }
+
+    #[test]
+    fn test_compute_apk() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let (_, _, agg_key) = setup_committee(n, &mut rng);
+
+        // For the full committee B = 1, so apk is the plain average of all keys
+        let n_inv = Fr::one() / Fr::from(n as u64);
+        let expected: G1 = agg_key.pk.iter().map(|pk| pk.bls_pk).sum::<G1>() * n_inv;
+        assert_eq!(compute_apk(&agg_key, &vec![true; n]).unwrap(), expected);
+
+        let mut selector = vec![false; n];
+        selector[1] = true;
+        assert!(compute_apk(&agg_key, &selector).is_err());
+        assert!(compute_apk(&agg_key, &selector[..4]).is_err());
+    }
 }
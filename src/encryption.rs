@@ -1,13 +1,26 @@
 use std::ops::Mul;
 
 use crate::error::SteError;
-use crate::{kzg::PowersOfTau, setup::AggregateKey};
+use crate::{
+    kzg::PowersOfTau,
+    rng::SecureRng,
+    security::RecoveredKey,
+    setup::{
+        check_params_degree, deserialize_validated, validate_committee_size, AggregateKey,
+        DummyConfig, EncryptOnlyKey, EncryptionKey,
+    },
+    utils::make_domain,
+};
 use ark_ec::{
     pairing::{Pairing, PairingOutput},
     PrimeGroup,
 };
+use ark_ff::PrimeField;
+use ark_poly::EvaluationDomain;
 use ark_serialize::*;
 use ark_std::{rand::RngCore, UniformRand, Zero};
+use blake2::{Blake2b512, Digest};
+use zeroize::Zeroizing;
 
 /// Number of G1 elements in the sa1 proof array.
 pub const SA1_SIZE: usize = 2;
@@ -18,6 +31,12 @@ pub const SA2_SIZE: usize = 6;
 /// Number of random scalar values used during encryption.
 pub const ENCRYPTION_RANDOMNESS_SIZE: usize = 5;
 
+/// Domain separation tag for deriving the batch validation coefficients.
+const BATCH_VALIDATE_DST: &[u8] = b"silent-threshold-encryption/batch-validate/v1";
+
+/// Domain separation tag for deriving the RNG seed of [`encrypt_deterministic`].
+const DETERMINISTIC_ENCRYPT_DST: &[u8] = b"silent-threshold-encryption/deterministic-encrypt/v1";
+
 /// A ciphertext in the silent threshold encryption scheme.
 ///
 /// Contains the encrypted message key along with proof elements.
@@ -59,32 +78,478 @@ impl<E: Pairing> Ciphertext<E> {
             t,
         }
     }
+
+    /// Whether `selector` picks exactly the `t + 1` parties (including the dummy
+    /// party) that [`agg_dec`](crate::decryption::agg_dec) requires.
+    pub fn is_minimal_quorum(&self, selector: &[bool]) -> bool {
+        count_selected(selector) == self.t + 1
+    }
+
+    /// How many parties `selector` picks beyond the `t + 1` required, i.e. how
+    /// many could be dropped before decryption fails. Returns 0 for a quorum
+    /// that is too small.
+    pub fn quorum_margin(&self, selector: &[bool]) -> usize {
+        count_selected(selector).saturating_sub(self.t + 1)
+    }
+
+    /// Checks the invariants every ciphertext from [`encrypt`] satisfies but
+    /// the type does not enforce.
+    ///
+    /// The `SA1_SIZE` and `SA2_SIZE` component counts are fixed by the array
+    /// types, so a ciphertext with the wrong number of components cannot be
+    /// deserialized at all. This checks that the threshold is at least 1 and
+    /// that no component is the identity, which an honest encryption produces
+    /// only with negligible probability.
+    ///
+    /// # Errors
+    /// Returns `SteError::ValidationError` naming the offending component
+    pub fn validate_structure(&self) -> Result<(), SteError> {
+        validate_components::<E>(self.t, &self.gamma_g2, &self.sa1, &self.sa2)?;
+        if self.enc_key.is_zero() {
+            return Err(SteError::ValidationError(
+                "ciphertext enc_key is the identity".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Deserializes a compressed ciphertext received from another party.
+    ///
+    /// Every group element is checked to be on the curve and in the
+    /// prime-order subgroup, trailing bytes are rejected, and the result must
+    /// pass [`Self::validate_structure`].
+    ///
+    /// # Errors
+    /// Returns `SteError::SerializationError` if the bytes are malformed or
+    /// contain an invalid point, or `SteError::ValidationError` if the
+    /// ciphertext is structurally invalid
+    pub fn deserialize_validated(bytes: &[u8]) -> Result<Self, SteError> {
+        let ct: Self = deserialize_validated(bytes, "ciphertext")?;
+        ct.validate_structure()?;
+        Ok(ct)
+    }
+
+    /// Validates the well-formedness of many ciphertexts under one aggregate key.
+    ///
+    /// Every honestly generated ciphertext satisfies
+    ///
+    /// ```text
+    /// e(g, sa2[0] + sa2[1]) - e(sa1[1], gamma_g2)
+    ///     = e(g^{tau^{n-1}}, sa2[2]) - e(g^{tau^n}, sa2[3])
+    /// ```
+    ///
+    /// which ties the randomness `s0`, `s1` and `s2` together. The equations of all
+    /// ciphertexts are combined with random coefficients, so a batch of `m`
+    /// ciphertexts costs `m + 3` pairings instead of `5m`. If the combined check
+    /// fails, the batch is bisected to locate the malformed ciphertexts.
+    ///
+    /// The coefficients are derived by hashing the aggregate key and all
+    /// ciphertexts, so the check is deterministic and needs no RNG.
+    ///
+    /// Components that only enter the decryption equation (`sa1[0]`, `sa2[4]`,
+    /// `sa2[5]`, `enc_key`) cannot be checked without partial decryptions; for
+    /// those only the threshold and non-identity checks apply.
+    ///
+    /// # Arguments
+    /// * `cts` - The ciphertexts to validate
+    /// * `agg_key` - The aggregate key the ciphertexts were encrypted under
+    /// * `params` - The KZG parameters (powers of tau)
+    ///
+    /// # Returns
+    /// One entry per ciphertext, `true` if it passed validation
+    pub fn batch_validate(
+        cts: &[Ciphertext<E>],
+        agg_key: &AggregateKey<E>,
+        params: &PowersOfTau<E>,
+    ) -> Vec<bool> {
+        let n = agg_key.pk.len();
+        let mut results = vec![false; cts.len()];
+        if n == 0 || params.powers_of_g.len() <= n {
+            return results;
+        }
+
+        let candidates: Vec<usize> = (0..cts.len())
+            .filter(|&i| cts[i].is_structurally_valid(n, params))
+            .collect();
+        let coeffs = batch_coefficients(cts, agg_key);
+
+        validate_subset(cts, &candidates, &coeffs, params, n, &mut results);
+        results
+    }
+
+    /// Checks the parts of the ciphertext that do not require a pairing.
+    fn is_structurally_valid(&self, n: usize, params: &PowersOfTau<E>) -> bool {
+        self.t >= 1
+            && self.t < n
+            && params.powers_of_g.len() > self.t + 1
+            && !self.gamma_g2.is_zero()
+            && !self.enc_key.is_zero()
+    }
+
+    /// Separates the encapsulated key from the rest of the ciphertext, which
+    /// can then be published.
+    pub fn into_encapsulation(self) -> (Encapsulation<E>, RecoveredKey<E>) {
+        let encapsulation = Encapsulation {
+            gamma_g2: self.gamma_g2,
+            sa1: self.sa1,
+            sa2: self.sa2,
+            t: self.t,
+        };
+        (encapsulation, RecoveredKey::new(self.enc_key))
+    }
+}
+
+/// A [`Ciphertext`] without its encapsulated key.
+///
+/// `Ciphertext::enc_key` is the key itself, so a ciphertext whose key
+/// protects other data must never be published whole. This holds everything
+/// the committee needs to decrypt and nothing more; the key comes back only
+/// from [`agg_dec_encapsulated`](crate::decryption::agg_dec_encapsulated).
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
+pub struct Encapsulation<E: Pairing> {
+    /// G2 element: gamma * H, all a party needs for its partial decryption
+    pub gamma_g2: E::G2,
+    /// G1 elements for proof (size = SA1_SIZE)
+    pub sa1: [E::G1; SA1_SIZE],
+    /// G2 elements for proof (size = SA2_SIZE)
+    pub sa2: [E::G2; SA2_SIZE],
+    /// The threshold value
+    pub t: usize,
+}
+
+impl<E: Pairing> Encapsulation<E> {
+    /// Checks the invariants of [`Ciphertext::validate_structure`] that do
+    /// not involve the key.
+    ///
+    /// # Errors
+    /// Returns `SteError::ValidationError` naming the offending component
+    pub fn validate_structure(&self) -> Result<(), SteError> {
+        validate_components::<E>(self.t, &self.gamma_g2, &self.sa1, &self.sa2)
+    }
+
+    /// Deserializes a compressed encapsulation with the checks of
+    /// [`Ciphertext::deserialize_validated`].
+    ///
+    /// # Errors
+    /// Returns `SteError::SerializationError` if the bytes are malformed or
+    /// contain an invalid point, or `SteError::ValidationError` if the
+    /// encapsulation is structurally invalid
+    pub fn deserialize_validated(bytes: &[u8]) -> Result<Self, SteError> {
+        let enc: Self = deserialize_validated(bytes, "encapsulation")?;
+        enc.validate_structure()?;
+        Ok(enc)
+    }
+}
+
+/// Read access to the parts of a ciphertext that decryption uses, shared by
+/// [`Ciphertext`] and [`Encapsulation`].
+pub(crate) trait CiphertextBody<E: Pairing> {
+    fn t(&self) -> usize;
+    fn sa1(&self) -> &[E::G1; SA1_SIZE];
+    fn sa2(&self) -> &[E::G2; SA2_SIZE];
+}
+
+impl<E: Pairing> CiphertextBody<E> for Ciphertext<E> {
+    fn t(&self) -> usize {
+        self.t
+    }
+    fn sa1(&self) -> &[E::G1; SA1_SIZE] {
+        &self.sa1
+    }
+    fn sa2(&self) -> &[E::G2; SA2_SIZE] {
+        &self.sa2
+    }
+}
+
+impl<E: Pairing> CiphertextBody<E> for Encapsulation<E> {
+    fn t(&self) -> usize {
+        self.t
+    }
+    fn sa1(&self) -> &[E::G1; SA1_SIZE] {
+        &self.sa1
+    }
+    fn sa2(&self) -> &[E::G2; SA2_SIZE] {
+        &self.sa2
+    }
+}
+
+fn validate_components<E: Pairing>(
+    t: usize,
+    gamma_g2: &E::G2,
+    sa1: &[E::G1],
+    sa2: &[E::G2],
+) -> Result<(), SteError> {
+    if t == 0 {
+        return Err(SteError::ValidationError(
+            "ciphertext threshold must be at least 1".to_string(),
+        ));
+    }
+    if gamma_g2.is_zero() {
+        return Err(SteError::ValidationError(
+            "ciphertext gamma_g2 is the identity".to_string(),
+        ));
+    }
+    if let Some(i) = sa1.iter().position(Zero::is_zero) {
+        return Err(SteError::ValidationError(format!(
+            "ciphertext sa1[{}] is the identity",
+            i
+        )));
+    }
+    if let Some(i) = sa2.iter().position(Zero::is_zero) {
+        return Err(SteError::ValidationError(format!(
+            "ciphertext sa2[{}] is the identity",
+            i
+        )));
+    }
+    Ok(())
+}
+
+fn count_selected(selector: &[bool]) -> usize {
+    selector.iter().filter(|&&selected| selected).count()
+}
+
+/// Derives one batching coefficient per ciphertext from a hash of the inputs.
+fn batch_coefficients<E: Pairing>(
+    cts: &[Ciphertext<E>],
+    agg_key: &AggregateKey<E>,
+) -> Vec<E::ScalarField> {
+    let mut hasher = Blake2b512::new();
+    hasher.update(BATCH_VALIDATE_DST);
+    let mut bytes = Vec::new();
+    agg_key
+        .z_g2
+        .serialize_compressed(&mut bytes)
+        .expect("serializing into a Vec cannot fail");
+    for ct in cts {
+        ct.serialize_compressed(&mut bytes)
+            .expect("serializing into a Vec cannot fail");
+    }
+    hasher.update(&bytes);
+    let seed = hasher.finalize();
+
+    (0..cts.len())
+        .map(|i| {
+            let mut hasher = Blake2b512::new();
+            hasher.update(seed);
+            hasher.update((i as u64).to_le_bytes());
+            E::ScalarField::from_le_bytes_mod_order(&hasher.finalize())
+        })
+        .collect()
+}
+
+/// Marks `indices` valid if their combined equation holds, bisecting on failure.
+fn validate_subset<E: Pairing>(
+    cts: &[Ciphertext<E>],
+    indices: &[usize],
+    coeffs: &[E::ScalarField],
+    params: &PowersOfTau<E>,
+    n: usize,
+    results: &mut [bool],
+) {
+    if indices.is_empty() {
+        return;
+    }
+    if check_combined_equation(cts, indices, coeffs, params, n) {
+        indices.iter().for_each(|&i| results[i] = true);
+        return;
+    }
+    if indices.len() == 1 {
+        return;
+    }
+
+    let (left, right) = indices.split_at(indices.len() / 2);
+    validate_subset(cts, left, coeffs, params, n, results);
+    validate_subset(cts, right, coeffs, params, n, results);
+}
+
+/// Evaluates the random linear combination of the ciphertext equations.
+fn check_combined_equation<E: Pairing>(
+    cts: &[Ciphertext<E>],
+    indices: &[usize],
+    coeffs: &[E::ScalarField],
+    params: &PowersOfTau<E>,
+    n: usize,
+) -> bool {
+    let mut acc_g = E::G2::zero();
+    let mut acc_tau_n1 = E::G2::zero();
+    let mut acc_tau_n = E::G2::zero();
+
+    let mut lhs: Vec<E::G1> = Vec::with_capacity(indices.len() + 3);
+    let mut rhs: Vec<E::G2> = Vec::with_capacity(indices.len() + 3);
+    for &i in indices {
+        let ct = &cts[i];
+        let r = coeffs[i];
+        acc_g += (ct.sa2[0] + ct.sa2[1]) * r;
+        acc_tau_n1 += ct.sa2[2] * r;
+        acc_tau_n += ct.sa2[3] * r;
+
+        lhs.push(-(ct.sa1[1] * r));
+        rhs.push(ct.gamma_g2);
+    }
+
+    lhs.push(params.powers_of_g[0].into());
+    rhs.push(acc_g);
+    lhs.push(-E::G1::from(params.powers_of_g[n - 1]));
+    rhs.push(acc_tau_n1);
+    lhs.push(params.powers_of_g[n].into());
+    rhs.push(acc_tau_n);
+
+    E::multi_pairing(lhs, rhs).is_zero()
 }
 
 /// Encrypts a message key using the aggregate public key.
 ///
 /// # Arguments
-/// * `apk` - The aggregate public key
+/// * `apk` - The aggregate public key, or just its [`EncryptOnlyKey`]
 /// * `t` - The threshold (must be < number of parties)
 /// * `params` - The KZG parameters (powers of tau)
 /// * `rng` - A random number generator
 ///
+/// The largest supported threshold is `t = n - 1`, for which decryption needs
+/// every party including the dummy party 0. It requires `params` to hold at
+/// least `n + 1` powers of g, which any setup of degree `n` provides.
+///
+/// No pairing is computed here: `e(g, h)` is cached in the key as `e_gh`, and
+/// `z_g2` and `h_minus1` are precomputed too, so each call costs a few G1 and
+/// G2 scalar multiplications and one GT exponentiation.
+///
 /// # Errors
 /// Returns an error if t >= n, t + 1 exceeds params length, or other validation fails
 pub fn encrypt<E: Pairing, R: RngCore>(
-    apk: &AggregateKey<E>,
+    apk: &impl EncryptionKey<E>,
     t: usize,
     params: &PowersOfTau<E>,
     rng: &mut R,
 ) -> Result<Ciphertext<E>, SteError> {
-    let n = apk.pk.len();
+    encrypt_with_dummies(apk, t, params, &DummyConfig::default(), rng)
+}
 
-    // Validate inputs
-    if n == 0 {
-        return Err(SteError::ValidationError(
-            "number of parties must be at least 1".to_string(),
-        ));
+/// Like [`encrypt`], for a committee whose dummy parties are given by
+/// `dummies` instead of the single dummy party 0.
+///
+/// Decrypt with [`agg_dec_with_dummies`](crate::decryption::agg_dec_with_dummies)
+/// and the same config.
+///
+/// # Errors
+/// Returns the errors of [`encrypt`], or an error if a dummy index is not
+/// below `n` or `t` is less than the number of dummies
+pub fn encrypt_with_dummies<E: Pairing, R: RngCore>(
+    apk: &impl EncryptionKey<E>,
+    t: usize,
+    params: &PowersOfTau<E>,
+    dummies: &DummyConfig,
+    rng: &mut R,
+) -> Result<Ciphertext<E>, SteError> {
+    encrypt_with_powers(
+        apk.encryption_key(),
+        t,
+        &params.powers_of_g,
+        &params.powers_of_h,
+        dummies,
+        rng,
+    )
+}
+
+/// Encrypts with all randomness derived from `seed`, so the same key,
+/// threshold, parameters and seed always give byte-identical ciphertexts.
+///
+/// The seed is hashed with a domain separation tag into the key of a ChaCha
+/// CSPRNG ([`SecureRng::from_seed`]), which then stands in for `rng` in
+/// [`encrypt`].
+///
+/// # Security
+/// Only for tests and systems that need reproducible ciphertexts. Anyone who
+/// knows the seed can recompute `enc_key` without the committee, and every
+/// ciphertext made from one seed encapsulates the same key. In production
+/// use [`encrypt`] with fresh randomness.
+///
+/// # Errors
+/// Returns the same errors as [`encrypt`]
+pub fn encrypt_deterministic<E: Pairing>(
+    apk: &impl EncryptionKey<E>,
+    t: usize,
+    params: &PowersOfTau<E>,
+    seed: &[u8],
+) -> Result<Ciphertext<E>, SteError> {
+    let mut rng_seed = Zeroizing::new([0u8; 32]);
+    rng_seed.copy_from_slice(
+        &Blake2b512::new()
+            .chain_update(DETERMINISTIC_ENCRYPT_DST)
+            .chain_update(seed)
+            .finalize()[..32],
+    );
+    encrypt(apk, t, params, &mut SecureRng::from_seed(*rng_seed))
+}
+
+/// Everything an encrypt-only publisher needs, without the per-party public
+/// keys or the G2 powers that only setup and decryption read.
+///
+/// For a committee of size `n` this is `n + 1` G1 points, two G2 points and
+/// the [`EncryptOnlyKey`], instead of the full aggregate key and parameters.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
+pub struct ExportBundle<E: Pairing> {
+    pub key: EncryptOnlyKey<E>,
+    /// `g^{tau^i}` for `i` in `0..=n`, enough for any threshold
+    pub powers_of_g: Vec<E::G1Affine>,
+    /// `h` and `h^tau`
+    pub powers_of_h: [E::G2Affine; 2],
+}
+
+impl<E: Pairing> ExportBundle<E> {
+    /// Extracts the publisher's bundle from a full aggregate key and parameters.
+    ///
+    /// # Errors
+    /// Returns `SteError::ValidationError` if `params` are too small for the committee
+    pub fn for_publisher(
+        agg_key: &AggregateKey<E>,
+        params: &PowersOfTau<E>,
+    ) -> Result<Self, SteError> {
+        let key = agg_key.encrypt_only();
+        check_params_degree(params, key.n)?;
+        Ok(Self {
+            key,
+            powers_of_g: params.powers_of_g[..=key.n].to_vec(),
+            powers_of_h: [params.powers_of_h[0], params.powers_of_h[1]],
+        })
     }
+}
+
+/// Encrypts with a publisher's [`ExportBundle`]; equivalent to [`encrypt`]
+/// with the aggregate key and parameters the bundle was taken from.
+///
+/// # Errors
+/// Returns the same errors as [`encrypt`]
+pub fn encrypt_from_bundle<E: Pairing, R: RngCore>(
+    bundle: &ExportBundle<E>,
+    t: usize,
+    rng: &mut R,
+) -> Result<Ciphertext<E>, SteError> {
+    encrypt_with_powers(
+        bundle.key,
+        t,
+        &bundle.powers_of_g,
+        &bundle.powers_of_h,
+        &DummyConfig::default(),
+        rng,
+    )
+}
+
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(name = "encrypt", level = "debug", skip_all, fields(n = apk.n, t))
+)]
+fn encrypt_with_powers<E: Pairing, R: RngCore>(
+    apk: EncryptOnlyKey<E>,
+    t: usize,
+    powers_of_g: &[E::G1Affine],
+    powers_of_h: &[E::G2Affine],
+    dummies: &DummyConfig,
+    rng: &mut R,
+) -> Result<Ciphertext<E>, SteError> {
+    let n = apk.n;
+
+    // Validate inputs
+    validate_committee_size(n)?;
     if t == 0 {
         return Err(SteError::ValidationError(
             "threshold must be at least 1".to_string(),
@@ -96,24 +561,32 @@ pub fn encrypt<E: Pairing, R: RngCore>(
             t, n
         )));
     }
-    if params.powers_of_g.len() <= t + 1 {
+    if powers_of_g.len() <= t + 1 {
         return Err(SteError::ValidationError(format!(
             "KZG parameters must contain at least t + 2 powers of g (need {}, have {})",
             t + 2,
-            params.powers_of_g.len()
+            powers_of_g.len()
         )));
     }
-    if params.powers_of_h.len() < 2 {
+    if powers_of_h.len() < 2 {
         return Err(SteError::ValidationError(format!(
             "KZG parameters must contain at least 2 powers of h (have {})",
-            params.powers_of_h.len()
+            powers_of_h.len()
         )));
     }
+    dummies.validate(n, t)?;
+
+    // h^{-omega^a} for the anchor dummy a; the key caches -h for a = 0
+    let h_minus_anchor = match dummies.anchor() {
+        0 => apk.h_minus1,
+        a => apk.h_minus1 * make_domain::<E::ScalarField>(n)?.element(a),
+    };
+
     let gamma = E::ScalarField::rand(rng);
-    let gamma_g2 = params.powers_of_h[0] * gamma;
+    let gamma_g2 = traced!("gamma_g2", powers_of_h[0] * gamma);
 
-    let g = params.powers_of_g[0];
-    let h = params.powers_of_h[0];
+    let g = powers_of_g[0];
+    let h = powers_of_h[0];
 
     let mut sa1 = [E::G1::generator(); SA1_SIZE];
     let mut sa2 = [E::G2::generator(); SA2_SIZE];
@@ -124,32 +597,36 @@ pub fn encrypt<E: Pairing, R: RngCore>(
     s.iter_mut()
         .for_each(|s_elem| *s_elem = E::ScalarField::rand(rng));
 
-    // sa1[0] = s0*ask + s3*g^{tau^{t+1}} + s4*g
-    sa1[0] = (apk.ask * s[0]) + (params.powers_of_g[t + 1] * s[3]) + (params.powers_of_g[0] * s[4]);
+    traced!("sa1", {
+        // sa1[0] = s0*ask + s3*g^{tau^{t+1}} + s4*g
+        sa1[0] = (apk.ask * s[0]) + (powers_of_g[t + 1] * s[3]) + (powers_of_g[0] * s[4]);
 
-    // sa1[1] = s2*g
-    sa1[1] = g * s[2];
+        // sa1[1] = s2*g
+        sa1[1] = g * s[2];
+    });
 
-    // sa2[0] = s0*h + s2*gamma_g2
-    sa2[0] = (h * s[0]) + (gamma_g2 * s[2]);
+    traced!("sa2", {
+        // sa2[0] = s0*h + s2*gamma_g2
+        sa2[0] = (h * s[0]) + (gamma_g2 * s[2]);
 
-    // sa2[1] = s0*z_g2
-    sa2[1] = apk.z_g2 * s[0];
+        // sa2[1] = s0*z_g2
+        sa2[1] = apk.z_g2 * s[0];
 
-    // sa2[2] = s0*h^tau + s1*h^tau
-    sa2[2] = params.powers_of_h[1] * (s[0] + s[1]);
+        // sa2[2] = s0*h^tau + s1*h^tau
+        sa2[2] = powers_of_h[1] * (s[0] + s[1]);
 
-    // sa2[3] = s1*h
-    sa2[3] = h * s[1];
+        // sa2[3] = s1*h
+        sa2[3] = h * s[1];
 
-    // sa2[4] = s3*h
-    sa2[4] = h * s[3];
+        // sa2[4] = s3*h
+        sa2[4] = h * s[3];
 
-    // sa2[5] = s4*h^{tau - omega^0}
-    sa2[5] = (params.powers_of_h[1] + apk.h_minus1) * s[4];
+        // sa2[5] = s4*h^{tau - omega^a}
+        sa2[5] = (powers_of_h[1] + h_minus_anchor) * s[4];
+    });
 
     // enc_key = s4*e_gh
-    let enc_key = apk.e_gh.mul(s[4]);
+    let enc_key = traced!("enc_key", apk.e_gh.mul(s[4]));
 
     Ok(Ciphertext {
         gamma_g2,
@@ -160,6 +637,20 @@ pub fn encrypt<E: Pairing, R: RngCore>(
     })
 }
 
+/// Like [`encrypt`], but returns the key apart from an [`Encapsulation`]
+/// that is safe to publish. Use this whenever the key protects a payload.
+///
+/// # Errors
+/// Returns the errors of [`encrypt`]
+pub fn encapsulate<E: Pairing, R: RngCore>(
+    apk: &impl EncryptionKey<E>,
+    t: usize,
+    params: &PowersOfTau<E>,
+    rng: &mut R,
+) -> Result<(Encapsulation<E>, RecoveredKey<E>), SteError> {
+    encrypt(apk, t, params, rng).map(Ciphertext::into_encapsulation)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -253,4 +744,172 @@ mod tests {
             "unexpected error: {err:?}"
         );
     }
+
+    #[test]
+    fn test_encrypt_max_threshold() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+
+        let pk: Vec<PublicKey<E>> = (0..n)
+            .map(|i| SecretKey::<E>::new(&mut rng).get_pk(i, &params, n).unwrap())
+            .collect();
+        let ak = AggregateKey::<E>::new(pk, &params).unwrap();
+
+        let ct = encrypt::<E, _>(&ak, n - 1, &params, &mut rng).unwrap();
+        assert_eq!(ct.t, n - 1);
+
+        let err = encrypt::<E, _>(&ak, n, &params, &mut rng)
+            .expect_err("expected encryption to fail with t = n");
+        assert!(
+            matches!(err, SteError::ValidationError(ref msg) if msg.contains("must be <")),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn test_ciphertext_deserialize_validated() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let pk: Vec<PublicKey<E>> = (0..n)
+            .map(|i| SecretKey::<E>::new(&mut rng).get_pk(i, &params, n).unwrap())
+            .collect();
+        let ak = AggregateKey::<E>::new(pk, &params).unwrap();
+        let ct = encrypt::<E, _>(&ak, 3, &params, &mut rng).unwrap();
+
+        let mut bytes = Vec::new();
+        ct.serialize_compressed(&mut bytes).unwrap();
+        let decoded = Ciphertext::<E>::deserialize_validated(&bytes).unwrap();
+        assert_eq!(decoded.gamma_g2, ct.gamma_g2);
+        assert_eq!(decoded.enc_key, ct.enc_key);
+
+        // Swap gamma_g2 for a point on the curve but outside the subgroup
+        let mut c0 = ark_bls12_381::Fq::from(1u64);
+        let outside = loop {
+            let x = ark_bls12_381::Fq2::new(c0, ark_bls12_381::Fq::from(0u64));
+            if let Some(p) = ark_bls12_381::G2Affine::get_point_from_x_unchecked(x, true) {
+                if !p.is_in_correct_subgroup_assuming_on_curve() {
+                    break p;
+                }
+            }
+            c0 += ark_bls12_381::Fq::from(1u64);
+        };
+        let mut point_bytes = Vec::new();
+        outside.serialize_compressed(&mut point_bytes).unwrap();
+        bytes[..point_bytes.len()].copy_from_slice(&point_bytes);
+
+        let err = Ciphertext::<E>::deserialize_validated(&bytes).unwrap_err();
+        assert!(matches!(err, SteError::SerializationError(_)), "{err:?}");
+    }
+
+    #[test]
+    fn test_deserialize_validated_rejects_malformed_structure() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let pk: Vec<PublicKey<E>> = (0..n)
+            .map(|i| SecretKey::<E>::new(&mut rng).get_pk(i, &params, n).unwrap())
+            .collect();
+        let ak = AggregateKey::<E>::new(pk, &params).unwrap();
+        let ct = encrypt::<E, _>(&ak, 3, &params, &mut rng).unwrap();
+        ct.validate_structure().unwrap();
+
+        let encode = |ct: &Ciphertext<E>| {
+            let mut bytes = Vec::new();
+            ct.serialize_compressed(&mut bytes).unwrap();
+            bytes
+        };
+        let expect_invalid =
+            |ct: &Ciphertext<E>, needle: &str| match Ciphertext::<E>::deserialize_validated(
+                &encode(ct),
+            ) {
+                Err(SteError::ValidationError(msg)) => assert!(msg.contains(needle), "{msg}"),
+                other => panic!("expected a structure error, got {:?}", other.map(|_| ())),
+            };
+
+        let mut zero_sa2 = ct.clone();
+        zero_sa2.sa2[4] = G2::zero();
+        expect_invalid(&zero_sa2, "sa2[4]");
+
+        let mut zero_t = ct.clone();
+        zero_t.t = 0;
+        expect_invalid(&zero_t, "threshold");
+
+        // A ciphertext missing an sa2 component does not decode
+        let bytes = encode(&ct);
+        let sa2_end = 96 + SA1_SIZE * 48 + SA2_SIZE * 96;
+        let mut short = bytes[..sa2_end - 96].to_vec();
+        short.extend_from_slice(&bytes[sa2_end..]);
+        assert!(matches!(
+            Ciphertext::<E>::deserialize_validated(&short),
+            Err(SteError::SerializationError(_))
+        ));
+
+        // The encapsulation runs the same checks on its components
+        let (mut enc, _) = zero_sa2.into_encapsulation();
+        let mut bytes = Vec::new();
+        enc.serialize_compressed(&mut bytes).unwrap();
+        assert!(Encapsulation::<E>::deserialize_validated(&bytes).is_err());
+        enc.sa2[4] = ct.sa2[4];
+        enc.validate_structure().unwrap();
+    }
+
+    #[test]
+    fn test_quorum_margin() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let pk: Vec<PublicKey<E>> = (0..n)
+            .map(|i| SecretKey::<E>::new(&mut rng).get_pk(i, &params, n).unwrap())
+            .collect();
+        let ak = AggregateKey::<E>::new(pk, &params).unwrap();
+        let ct = encrypt::<E, _>(&ak, t, &params, &mut rng).unwrap();
+
+        let select = |k: usize| -> Vec<bool> { (0..n).map(|i| i < k).collect() };
+
+        // Exact quorum of t + 1
+        assert!(ct.is_minimal_quorum(&select(t + 1)));
+        assert_eq!(ct.quorum_margin(&select(t + 1)), 0);
+
+        // Under quorum
+        assert!(!ct.is_minimal_quorum(&select(t)));
+        assert_eq!(ct.quorum_margin(&select(t)), 0);
+
+        // Over quorum
+        assert!(!ct.is_minimal_quorum(&select(n)));
+        assert_eq!(ct.quorum_margin(&select(n)), n - t - 1);
+    }
+
+    #[test]
+    fn test_batch_validate() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+
+        let pk: Vec<PublicKey<E>> = (0..n)
+            .map(|i| SecretKey::<E>::new(&mut rng).get_pk(i, &params, n).unwrap())
+            .collect();
+        let ak = AggregateKey::<E>::new(pk, &params).unwrap();
+
+        let mut cts: Vec<Ciphertext<E>> = (0..6)
+            .map(|i| encrypt::<E, _>(&ak, 1 + i % 3, &params, &mut rng).unwrap())
+            .collect();
+
+        cts[1].sa2[2] = G2::rand(&mut rng);
+        cts[3].sa1[1] = G1::rand(&mut rng);
+        cts[4].t = n;
+
+        assert_eq!(
+            Ciphertext::batch_validate(&cts, &ak, &params),
+            vec![true, false, true, false, false, true]
+        );
+        assert!(Ciphertext::batch_validate(&cts[..1], &ak, &params)[0]);
+    }
 }
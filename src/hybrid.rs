@@ -0,0 +1,463 @@
+//! Hybrid encryption of arbitrary payloads.
+//!
+//! The threshold ciphertext is the KEM: it encapsulates a GT element from
+//! which a ChaCha20-Poly1305 key is derived. It is stored as an
+//! [`Encapsulation`], without the GT element, so only a quorum can recover
+//! the key. The DEM is the payload sealed under that key, with the serialized
+//! KEM hashed into the associated data so the two halves cannot be mixed with
+//! other halves.
+//!
+//! The halves can be split and stored apart, e.g. the KEM on-chain and the
+//! DEM in bulk storage, and joined again before decryption. A fresh key is
+//! encapsulated for every ciphertext, so the DEM uses a fixed nonce.
+//!
+//! [`encrypt_multi_committee`] seals a payload once for several independent
+//! committees: a random DEM key is sealed under each committee's KEM key, so
+//! any one committee reaching quorum can open it. The per-committee KEMs are
+//! [`Encapsulation`]s too.
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+use blake2::{Blake2b512, Digest};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+
+use crate::{
+    encryption::{encapsulate, Encapsulation},
+    error::SteError,
+    kzg::PowersOfTau,
+    security::{DefaultKdf, RecoveredKey},
+    setup::{AggregateKey, EncryptionKey},
+};
+use zeroize::Zeroizing;
+
+/// Domain separation tag for the DEM's associated data.
+const HYBRID_AAD_DST: &[u8] = b"silent-threshold-encryption/hybrid-aad/v1";
+
+/// Domain separation tag for a multi-committee DEM's associated data.
+const MULTI_AAD_DST: &[u8] = b"silent-threshold-encryption/hybrid-multi-aad/v1";
+
+/// Domain separation tag for the associated data of a sealed DEM key.
+const MULTI_WRAP_DST: &[u8] = b"silent-threshold-encryption/hybrid-multi-wrap/v1";
+
+/// The symmetric half of a [`HybridCiphertext`]: the sealed payload.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DemPart {
+    /// ChaCha20-Poly1305 output, the payload followed by the 16-byte tag
+    pub sealed: Vec<u8>,
+}
+
+/// A threshold ciphertext together with the payload sealed under its key.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
+pub struct HybridCiphertext<E: Pairing> {
+    pub kem: Encapsulation<E>,
+    pub dem: DemPart,
+}
+
+impl<E: Pairing> HybridCiphertext<E> {
+    /// Encrypts `plaintext` to the committee with threshold `t`.
+    ///
+    /// # Errors
+    /// Returns any error from [`encapsulate`] or from serializing the KEM
+    pub fn encrypt<R: RngCore>(
+        agg_key: &impl EncryptionKey<E>,
+        t: usize,
+        params: &PowersOfTau<E>,
+        plaintext: &[u8],
+        rng: &mut R,
+    ) -> Result<Self, SteError> {
+        let (kem, key) = encapsulate(agg_key, t, params, rng)?;
+        let sealed = dem_cipher(&key)
+            .encrypt(
+                &Nonce::default(),
+                Payload {
+                    msg: plaintext,
+                    aad: &dem_aad(&kem)?,
+                },
+            )
+            .map_err(|_| SteError::CryptoError("Failed to seal payload".to_string()))?;
+        Ok(Self {
+            kem,
+            dem: DemPart { sealed },
+        })
+    }
+
+    /// Opens the payload with the key recovered from `self.kem` by
+    /// [`agg_dec_encapsulated`](crate::decryption::agg_dec_encapsulated).
+    ///
+    /// # Errors
+    /// Returns `SteError::DecryptionFailure` if the key is wrong or the DEM
+    /// was tampered with or belongs to a different KEM ciphertext
+    pub fn decrypt(&self, key: &RecoveredKey<E>) -> Result<Vec<u8>, SteError> {
+        dem_cipher(key)
+            .decrypt(
+                &Nonce::default(),
+                Payload {
+                    msg: &self.dem.sealed,
+                    aad: &dem_aad(&self.kem)?,
+                },
+            )
+            .map_err(|_| SteError::DecryptionFailure("payload failed authentication".to_string()))
+    }
+
+    /// Separates the KEM and DEM halves so they can be stored apart.
+    pub fn split(self) -> (Encapsulation<E>, DemPart) {
+        (self.kem, self.dem)
+    }
+
+    /// Reassembles a ciphertext from halves produced by [`Self::split`].
+    ///
+    /// Halves from different ciphertexts are only detected by [`Self::decrypt`].
+    pub fn join(kem: Encapsulation<E>, dem: DemPart) -> Self {
+        Self { kem, dem }
+    }
+}
+
+/// One committee's share of a [`MultiCommitteeCiphertext`].
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
+pub struct WrappedKey<E: Pairing> {
+    pub kem: Encapsulation<E>,
+    /// The DEM key sealed under the key encapsulated by `kem`
+    pub sealed_key: Vec<u8>,
+}
+
+/// A payload sealed once and decryptable by any of several committees.
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
+pub struct MultiCommitteeCiphertext<E: Pairing> {
+    /// One entry per committee, in the order the committees were given
+    pub committees: Vec<WrappedKey<E>>,
+    pub dem: DemPart,
+}
+
+/// Encrypts `payload` so that any one of the committees can decrypt it.
+///
+/// # Arguments
+/// * `agg_keys` - The committees' aggregate keys
+/// * `thresholds` - The threshold for each committee
+/// * `params` - The KZG parameters, large enough for every committee
+/// * `payload` - The bytes to seal
+/// * `rng` - Randomness for the DEM key and the threshold ciphertexts
+///
+/// # Errors
+/// Returns an error if no committee is given or `thresholds` has a different
+/// length, or any error from [`encapsulate`]
+pub fn encrypt_multi_committee<E: Pairing, R: RngCore>(
+    agg_keys: &[&AggregateKey<E>],
+    thresholds: &[usize],
+    params: &PowersOfTau<E>,
+    payload: &[u8],
+    rng: &mut R,
+) -> Result<MultiCommitteeCiphertext<E>, SteError> {
+    if agg_keys.is_empty() {
+        return Err(SteError::InvalidParameter(
+            "at least one committee is required".to_string(),
+        ));
+    }
+    if thresholds.len() != agg_keys.len() {
+        return Err(SteError::ValidationError(format!(
+            "got {} thresholds for {} committees",
+            thresholds.len(),
+            agg_keys.len()
+        )));
+    }
+
+    let mut dem_key = Zeroizing::new([0u8; 32]);
+    rng.fill_bytes(dem_key.as_mut_slice());
+
+    let mut committees = Vec::with_capacity(agg_keys.len());
+    for (&agg_key, &t) in agg_keys.iter().zip(thresholds) {
+        let (kem, key) = encapsulate(agg_key, t, params, rng)?;
+        let sealed_key = dem_cipher(&key)
+            .encrypt(
+                &Nonce::default(),
+                Payload {
+                    msg: dem_key.as_slice(),
+                    aad: &kems_aad(MULTI_WRAP_DST, [&kem])?,
+                },
+            )
+            .map_err(|_| SteError::CryptoError("Failed to seal DEM key".to_string()))?;
+        committees.push(WrappedKey { kem, sealed_key });
+    }
+
+    let sealed = ChaCha20Poly1305::new(Key::from_slice(dem_key.as_slice()))
+        .encrypt(
+            &Nonce::default(),
+            Payload {
+                msg: payload,
+                aad: &kems_aad(MULTI_AAD_DST, committees.iter().map(|c| &c.kem))?,
+            },
+        )
+        .map_err(|_| SteError::CryptoError("Failed to seal payload".to_string()))?;
+
+    Ok(MultiCommitteeCiphertext {
+        committees,
+        dem: DemPart { sealed },
+    })
+}
+
+impl<E: Pairing> MultiCommitteeCiphertext<E> {
+    /// Opens the payload with the key committee `committee` recovered from
+    /// `self.committees[committee].kem` by
+    /// [`agg_dec_encapsulated`](crate::decryption::agg_dec_encapsulated).
+    ///
+    /// # Errors
+    /// Returns `SteError::ValidationError` if `committee` is out of range and
+    /// `SteError::DecryptionFailure` if the key is wrong or any part was
+    /// tampered with
+    pub fn decrypt(&self, committee: usize, key: &RecoveredKey<E>) -> Result<Vec<u8>, SteError> {
+        let wrapped = self.committees.get(committee).ok_or_else(|| {
+            SteError::ValidationError(format!(
+                "committee {} out of range (have {})",
+                committee,
+                self.committees.len()
+            ))
+        })?;
+
+        let dem_key = Zeroizing::new(
+            dem_cipher(key)
+                .decrypt(
+                    &Nonce::default(),
+                    Payload {
+                        msg: &wrapped.sealed_key,
+                        aad: &kems_aad(MULTI_WRAP_DST, [&wrapped.kem])?,
+                    },
+                )
+                .map_err(|_| {
+                    SteError::DecryptionFailure("DEM key failed authentication".to_string())
+                })?,
+        );
+        if dem_key.len() != 32 {
+            return Err(SteError::DecryptionFailure(
+                "DEM key has the wrong length".to_string(),
+            ));
+        }
+
+        ChaCha20Poly1305::new(Key::from_slice(&dem_key))
+            .decrypt(
+                &Nonce::default(),
+                Payload {
+                    msg: &self.dem.sealed,
+                    aad: &kems_aad(MULTI_AAD_DST, self.committees.iter().map(|c| &c.kem))?,
+                },
+            )
+            .map_err(|_| SteError::DecryptionFailure("payload failed authentication".to_string()))
+    }
+}
+
+fn dem_cipher<E: Pairing>(key: &RecoveredKey<E>) -> ChaCha20Poly1305 {
+    let symmetric_key = key.derive_symmetric_key::<DefaultKdf>();
+    ChaCha20Poly1305::new(Key::from_slice(symmetric_key.as_slice()))
+}
+
+fn dem_aad<E: Pairing>(kem: &Encapsulation<E>) -> Result<[u8; 32], SteError> {
+    kems_aad(HYBRID_AAD_DST, [kem])
+}
+
+/// `Blake2b512(dst || kem_1 || ... || kem_k)` truncated to 32 bytes
+fn kems_aad<'a, K: CanonicalSerialize + 'a>(
+    dst: &[u8],
+    kems: impl IntoIterator<Item = &'a K>,
+) -> Result<[u8; 32], SteError> {
+    let mut hasher = Blake2b512::new().chain_update(dst);
+    for kem in kems {
+        let mut kem_bytes = Vec::new();
+        kem.serialize_compressed(&mut kem_bytes)
+            .map_err(|e| SteError::SerializationError(format!("Failed to serialize KEM: {}", e)))?;
+        hasher.update(kem_bytes);
+    }
+    let mut aad = [0u8; 32];
+    aad.copy_from_slice(&hasher.finalize()[..32]);
+    Ok(aad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decryption::agg_dec_encapsulated,
+        kzg::KZG10,
+        setup::{AggregateKey, SecretKey},
+        testing::setup_committee,
+    };
+    use ark_poly::univariate::DensePolynomial;
+    use ark_std::{UniformRand, Zero};
+
+    type E = ark_bls12_381::Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+    type G2 = <E as Pairing>::G2;
+    type UniPoly381 = DensePolynomial<Fr>;
+
+    fn recover_encapsulated(
+        sk: &[SecretKey<E>],
+        k: usize,
+        kem: &Encapsulation<E>,
+        agg_key: &AggregateKey<E>,
+        params: &PowersOfTau<E>,
+    ) -> Result<RecoveredKey<E>, SteError> {
+        let n = sk.len();
+        let mut selector = vec![false; n];
+        let mut partial_decryptions = vec![G2::zero(); n];
+        for i in 0..k + 1 {
+            selector[i] = true;
+            partial_decryptions[i] = sk[i].partial_decryption_encapsulated(kem);
+        }
+        agg_dec_encapsulated(&partial_decryptions, kem, &selector, agg_key, params)
+    }
+
+    /// Whether the compressed encoding of `key` occurs anywhere in `bytes`.
+    fn contains_key(bytes: &[u8], key: &RecoveredKey<E>) -> bool {
+        let mut key_bytes = Vec::new();
+        key.expose().serialize_compressed(&mut key_bytes).unwrap();
+        bytes.windows(key_bytes.len()).any(|w| w == key_bytes)
+    }
+
+    #[test]
+    fn test_published_ciphertext_does_not_open_the_payload() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let (sk, agg_key) = setup_committee(n, tau, &params, &mut rng).unwrap();
+
+        let plaintext = b"only a quorum may read this".to_vec();
+        let hybrid = HybridCiphertext::encrypt(&agg_key, t, &params, &plaintext, &mut rng).unwrap();
+        let mut bytes = Vec::new();
+        hybrid.serialize_compressed(&mut bytes).unwrap();
+        let published = HybridCiphertext::<E>::deserialize_compressed(&bytes[..]).unwrap();
+
+        // The key is nowhere in the published bytes, and a quorum recovers it
+        let key = recover_encapsulated(&sk, t, &published.kem, &agg_key, &params).unwrap();
+        assert!(!contains_key(&bytes, &key));
+        assert_eq!(published.decrypt(&key).unwrap(), plaintext);
+
+        // One party short of the threshold gets nothing
+        assert!(recover_encapsulated(&sk, t - 1, &published.kem, &agg_key, &params).is_err());
+        // Nor does a key from another ciphertext under the same committee
+        let other = HybridCiphertext::encrypt(&agg_key, t, &params, &plaintext, &mut rng).unwrap();
+        let other_key = recover_encapsulated(&sk, t, &other.kem, &agg_key, &params).unwrap();
+        assert!(published.decrypt(&other_key).is_err());
+    }
+
+    #[test]
+    fn test_split_serialize_join_decrypt() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let (sk, agg_key) = setup_committee(n, tau, &params, &mut rng).unwrap();
+
+        let plaintext = b"stored in two places".to_vec();
+        let hybrid = HybridCiphertext::encrypt(&agg_key, t, &params, &plaintext, &mut rng).unwrap();
+        let other = HybridCiphertext::encrypt(&agg_key, t, &params, &plaintext, &mut rng).unwrap();
+
+        // Each half round-trips through its own encoding
+        let (kem, dem) = hybrid.split();
+        let mut kem_bytes = Vec::new();
+        kem.serialize_compressed(&mut kem_bytes).unwrap();
+        let mut dem_bytes = Vec::new();
+        dem.serialize_compressed(&mut dem_bytes).unwrap();
+        let kem = Encapsulation::<E>::deserialize_validated(&kem_bytes).unwrap();
+        let dem = DemPart::deserialize_compressed(&dem_bytes[..]).unwrap();
+        let joined = HybridCiphertext::join(kem, dem);
+
+        let key = recover_encapsulated(&sk, t, &joined.kem, &agg_key, &params).unwrap();
+        assert_eq!(joined.decrypt(&key).unwrap(), plaintext);
+
+        // A DEM joined to the wrong KEM does not open
+        let mismatched = HybridCiphertext::join(joined.kem.clone(), other.dem);
+        assert!(mismatched.decrypt(&key).is_err());
+    }
+
+    #[test]
+    fn test_multi_committee_each_committee_decrypts() {
+        let mut rng = ark_std::test_rng();
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(16, tau).unwrap();
+        let (sk_a, agg_key_a) = setup_committee(8, tau, &params, &mut rng).unwrap();
+        let (sk_b, agg_key_b) = setup_committee(16, tau, &params, &mut rng).unwrap();
+
+        let payload = b"either committee may open this".to_vec();
+        let ct = encrypt_multi_committee(
+            &[&agg_key_a, &agg_key_b],
+            &[3, 5],
+            &params,
+            &payload,
+            &mut rng,
+        )
+        .unwrap();
+
+        let mut bytes = Vec::new();
+        ct.serialize_compressed(&mut bytes).unwrap();
+        let ct = MultiCommitteeCiphertext::<E>::deserialize_compressed(&bytes[..]).unwrap();
+
+        let key_a =
+            recover_encapsulated(&sk_a, 3, &ct.committees[0].kem, &agg_key_a, &params).unwrap();
+        let key_b =
+            recover_encapsulated(&sk_b, 5, &ct.committees[1].kem, &agg_key_b, &params).unwrap();
+        assert_eq!(ct.decrypt(0, &key_a).unwrap(), payload);
+        assert_eq!(ct.decrypt(1, &key_b).unwrap(), payload);
+
+        // A key only opens its own committee's entry
+        assert!(ct.decrypt(1, &key_a).is_err());
+        assert!(ct.decrypt(2, &key_a).is_err());
+
+        // Swapping in another ciphertext's entry breaks the DEM binding
+        let other = encrypt_multi_committee(
+            &[&agg_key_a, &agg_key_b],
+            &[3, 5],
+            &params,
+            &payload,
+            &mut rng,
+        )
+        .unwrap();
+        let mut mixed = ct.clone();
+        mixed.committees[1] = other.committees[1].clone();
+        assert!(mixed.decrypt(0, &key_a).is_err());
+
+        assert!(
+            encrypt_multi_committee(&[&agg_key_a], &[3, 5], &params, &payload, &mut rng).is_err()
+        );
+    }
+
+    #[test]
+    fn test_multi_committee_public_ciphertext_does_not_open() {
+        let mut rng = ark_std::test_rng();
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(8, tau).unwrap();
+        let (sk_a, agg_key_a) = setup_committee(8, tau, &params, &mut rng).unwrap();
+        let (sk_b, agg_key_b) = setup_committee(8, tau, &params, &mut rng).unwrap();
+
+        let payload = b"no committee has decrypted this".to_vec();
+        let ct = encrypt_multi_committee(
+            &[&agg_key_a, &agg_key_b],
+            &[3, 3],
+            &params,
+            &payload,
+            &mut rng,
+        )
+        .unwrap();
+        let mut bytes = Vec::new();
+        ct.serialize_compressed(&mut bytes).unwrap();
+        let ct = MultiCommitteeCiphertext::<E>::deserialize_compressed(&bytes[..]).unwrap();
+
+        // Neither committee's key is in the published bytes
+        let key_a =
+            recover_encapsulated(&sk_a, 3, &ct.committees[0].kem, &agg_key_a, &params).unwrap();
+        let key_b =
+            recover_encapsulated(&sk_b, 3, &ct.committees[1].kem, &agg_key_b, &params).unwrap();
+        assert!(!contains_key(&bytes, &key_a));
+        assert!(!contains_key(&bytes, &key_b));
+
+        // Below quorum in both committees nothing is recovered
+        for (sk, agg_key, wrapped) in [(&sk_a, &agg_key_a, 0), (&sk_b, &agg_key_b, 1)] {
+            let kem = &ct.committees[wrapped].kem;
+            assert!(recover_encapsulated(sk, 2, kem, agg_key, &params).is_err());
+        }
+        assert_eq!(ct.decrypt(0, &key_a).unwrap(), payload);
+    }
+}
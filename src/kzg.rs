@@ -12,6 +12,9 @@ use ark_std::{format, marker::PhantomData, ops::*, vec};
 
 use ark_std::rand::RngCore;
 
+use crate::error::SteError;
+use sha2::{Digest, Sha256};
+
 pub struct KZG10<E: Pairing, P: DenseUVPolynomial<E::ScalarField>> {
     _engine: PhantomData<E>,
     _poly: PhantomData<P>,
@@ -25,6 +28,225 @@ pub struct PowersOfTau<E: Pairing> {
     pub powers_of_h: Vec<E::G2Affine>,
 }
 
+/// Size of a compressed BLS12-381 G1 point in the Zcash/EIP-4844 encoding.
+pub const ETH_G1_POINT_SIZE: usize = 48;
+/// Size of a compressed BLS12-381 G2 point in the Zcash/EIP-4844 encoding.
+pub const ETH_G2_POINT_SIZE: usize = 96;
+
+/// Shape of a [`PowersOfTau`], for logging and diagnosing parameter mismatches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PowersOfTauSummary {
+    /// Highest power of tau committed in G1 (`powers_of_g.len() - 1`)
+    pub max_degree: usize,
+    /// Number of G1 powers
+    pub num_g1_powers: usize,
+    /// Number of G2 powers
+    pub num_g2_powers: usize,
+}
+
+impl<E: Pairing> PowersOfTau<E> {
+    /// Highest power of tau available in G1, i.e. `powers_of_g.len() - 1`.
+    ///
+    /// Returns 0 for empty parameters.
+    pub fn max_degree(&self) -> usize {
+        self.powers_of_g.len().saturating_sub(1)
+    }
+
+    /// Check that the G1 and G2 vectors commit to the same number of powers.
+    pub fn validate_lengths(&self) -> Result<(), Error> {
+        if self.powers_of_g.len() != self.powers_of_h.len() {
+            return Err(Error::MismatchedPowers {
+                num_g1_powers: self.powers_of_g.len(),
+                num_g2_powers: self.powers_of_h.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Lengths of both power vectors, for logging.
+    pub fn summary(&self) -> PowersOfTauSummary {
+        PowersOfTauSummary {
+            max_degree: self.max_degree(),
+            num_g1_powers: self.powers_of_g.len(),
+            num_g2_powers: self.powers_of_h.len(),
+        }
+    }
+
+    /// Encodes what a peer holding `self` needs to rebuild `other`: the powers
+    /// that are new or differ, plus a hash of `self` so the diff is only
+    /// applied to the parameters it was made against.
+    ///
+    /// Extending a ceremony to a higher degree keeps the existing powers, so
+    /// the diff then holds just the appended ones.
+    pub fn diff(&self, other: &Self) -> Vec<u8> {
+        let diff = PowersDiff::<E> {
+            base_hash: self.digest(),
+            g1_len: other.powers_of_g.len() as u64,
+            g2_len: other.powers_of_h.len() as u64,
+            g1: changed_powers(&self.powers_of_g, &other.powers_of_g),
+            g2: changed_powers(&self.powers_of_h, &other.powers_of_h),
+        };
+        let mut bytes = Vec::new();
+        diff.serialize_compressed(&mut bytes)
+            .expect("serializing into a Vec cannot fail");
+        bytes
+    }
+
+    /// Applies a diff from [`Self::diff`], turning `self` into the parameters
+    /// it was made from. `self` is unchanged on error.
+    ///
+    /// # Errors
+    /// Returns `SteError::SerializationError` if the diff is malformed or a
+    /// point is invalid, and `SteError::ValidationError` if it was made
+    /// against different parameters or leaves a power missing
+    pub fn apply_diff(&mut self, diff: &[u8]) -> Result<(), SteError> {
+        let diff: PowersDiff<E> = crate::setup::deserialize_validated(diff, "powers of tau diff")?;
+        if diff.base_hash != self.digest() {
+            return Err(SteError::ValidationError(
+                "diff was made against different parameters".to_string(),
+            ));
+        }
+        let powers_of_g = patch_powers(&self.powers_of_g, diff.g1_len, &diff.g1, "G1")?;
+        let powers_of_h = patch_powers(&self.powers_of_h, diff.g2_len, &diff.g2, "G2")?;
+        self.powers_of_g = powers_of_g;
+        self.powers_of_h = powers_of_h;
+        Ok(())
+    }
+
+    fn digest(&self) -> [u8; 32] {
+        let mut bytes = Vec::new();
+        self.serialize_compressed(&mut bytes)
+            .expect("serializing into a Vec cannot fail");
+        Sha256::digest(&bytes).into()
+    }
+}
+
+/// Wire form of [`PowersOfTau::diff`].
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+struct PowersDiff<E: Pairing> {
+    base_hash: [u8; 32],
+    g1_len: u64,
+    g2_len: u64,
+    /// `(index, power)` for every G1 power that is new or changed, by index
+    g1: Vec<(u64, E::G1Affine)>,
+    g2: Vec<(u64, E::G2Affine)>,
+}
+
+fn changed_powers<G: PartialEq + Copy>(old: &[G], new: &[G]) -> Vec<(u64, G)> {
+    new.iter()
+        .enumerate()
+        .filter(|&(i, power)| old.get(i) != Some(power))
+        .map(|(i, power)| (i as u64, *power))
+        .collect()
+}
+
+/// Rebuilds a power vector of length `len` from `base` and the changed
+/// entries, which must be in increasing order and leave no gap.
+fn patch_powers<G: Copy>(
+    base: &[G],
+    len: u64,
+    entries: &[(u64, G)],
+    group: &str,
+) -> Result<Vec<G>, SteError> {
+    let mut powers = base[..base.len().min(len as usize)].to_vec();
+    let mut prev = None;
+    for &(index, power) in entries {
+        if prev.is_some_and(|prev| index <= prev) || index >= len {
+            return Err(SteError::ValidationError(format!(
+                "{} diff entry {} is out of order or beyond length {}",
+                group, index, len
+            )));
+        }
+        prev = Some(index);
+        match (index as usize).cmp(&powers.len()) {
+            std::cmp::Ordering::Less => powers[index as usize] = power,
+            std::cmp::Ordering::Equal => powers.push(power),
+            std::cmp::Ordering::Greater => break,
+        }
+    }
+    if powers.len() as u64 != len {
+        return Err(SteError::ValidationError(format!(
+            "{} diff leaves power {} of {} missing",
+            group,
+            powers.len(),
+            len
+        )));
+    }
+    Ok(powers)
+}
+
+impl PowersOfTau<ark_bls12_381::Bls12_381> {
+    /// Imports powers of tau from an Ethereum KZG (EIP-4844) ceremony.
+    ///
+    /// Both inputs are concatenations of compressed points in the standard
+    /// BLS12-381 encoding: big-endian coordinates with the compression,
+    /// infinity and sign flags in the top three bits of the first byte
+    /// (48 bytes per G1 point, 96 per G2 point). The G1 points must be the
+    /// monomial-basis powers (`g1_monomial`), not the Lagrange-basis ones.
+    ///
+    /// Both lists are truncated to the shorter one, since the scheme needs
+    /// matching G1 and G2 powers. The mainnet EIP-4844 setup (4096 G1, 65 G2
+    /// points) therefore supports committees up to n = 64.
+    ///
+    /// # Errors
+    /// Returns `SteError::SerializationError` if a length is not a multiple of
+    /// the point size, a point is not in compressed form, or a point is not on
+    /// the curve or not in the prime-order subgroup
+    pub fn from_eth_kzg(g1_points: &[u8], g2_points: &[u8]) -> Result<Self, SteError> {
+        let powers_of_g: Vec<ark_bls12_381::G1Affine> =
+            decode_eth_points(g1_points, ETH_G1_POINT_SIZE, "G1")?;
+        let powers_of_h: Vec<ark_bls12_381::G2Affine> =
+            decode_eth_points(g2_points, ETH_G2_POINT_SIZE, "G2")?;
+
+        let len = powers_of_g.len().min(powers_of_h.len());
+        if len < 2 {
+            return Err(SteError::SerializationError(format!(
+                "need at least 2 G1 and 2 G2 points, got {} and {}",
+                powers_of_g.len(),
+                powers_of_h.len()
+            )));
+        }
+
+        Ok(PowersOfTau {
+            powers_of_g: powers_of_g[..len].to_vec(),
+            powers_of_h: powers_of_h[..len].to_vec(),
+        })
+    }
+}
+
+fn decode_eth_points<G: CanonicalDeserialize>(
+    bytes: &[u8],
+    point_size: usize,
+    group: &str,
+) -> Result<Vec<G>, SteError> {
+    if !bytes.len().is_multiple_of(point_size) {
+        return Err(SteError::SerializationError(format!(
+            "{} input length {} is not a multiple of {}",
+            group,
+            bytes.len(),
+            point_size
+        )));
+    }
+
+    bytes
+        .chunks_exact(point_size)
+        .enumerate()
+        .map(|(i, chunk)| {
+            // The compression flag sits in the most significant bit of the
+            // first byte; without it the input is uncompressed or little-endian.
+            if chunk[0] & 0x80 == 0 {
+                return Err(SteError::SerializationError(format!(
+                    "{} point {} is not a compressed big-endian point",
+                    group, i
+                )));
+            }
+            G::deserialize_compressed(chunk).map_err(|e| {
+                SteError::SerializationError(format!("invalid {} point {}: {}", group, i, e))
+            })
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 pub enum Error {
     /// The degree provided in setup was too small; degree 0 polynomials
@@ -39,6 +261,12 @@ pub enum Error {
         /// The maximum number of powers provided in `Powers`.
         num_powers: usize,
     },
+
+    /// The G1 and G2 powers have different lengths.
+    MismatchedPowers {
+        num_g1_powers: usize,
+        num_g2_powers: usize,
+    },
 }
 
 impl std::fmt::Display for Error {
@@ -55,6 +283,16 @@ impl std::fmt::Display for Error {
                     num_coefficients, num_powers
                 )
             }
+            Error::MismatchedPowers {
+                num_g1_powers,
+                num_g2_powers,
+            } => {
+                write!(
+                    f,
+                    "Powers of tau have {} G1 powers but {} G2 powers",
+                    num_g1_powers, num_g2_powers
+                )
+            }
         }
     }
 }
@@ -157,3 +395,147 @@ fn check_degree_is_too_large(degree: usize, num_powers: usize) -> Result<(), Err
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_ec::AffineRepr;
+    use ark_poly::univariate::DensePolynomial;
+
+    type E = ark_bls12_381::Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+    type UniPoly381 = DensePolynomial<Fr>;
+
+    #[test]
+    fn test_powers_of_tau_summary() {
+        let mut rng = ark_std::test_rng();
+        let params = KZG10::<E, UniPoly381>::setup(8, Fr::rand(&mut rng)).unwrap();
+
+        assert_eq!(params.max_degree(), 8);
+        assert!(params.validate_lengths().is_ok());
+        assert_eq!(
+            params.summary(),
+            PowersOfTauSummary {
+                max_degree: 8,
+                num_g1_powers: 9,
+                num_g2_powers: 9,
+            }
+        );
+    }
+
+    fn unhex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_from_eth_kzg() {
+        // tau = 1: every power is the generator, in the standard encoding
+        let g1 = unhex("97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb");
+        let g2 = unhex("93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8");
+        let params = PowersOfTau::from_eth_kzg(&g1.repeat(3), &g2.repeat(2)).unwrap();
+        assert_eq!(
+            params.powers_of_g,
+            vec![ark_bls12_381::G1Affine::generator(); 2]
+        );
+        assert_eq!(
+            params.powers_of_h,
+            vec![ark_bls12_381::G2Affine::generator(); 2]
+        );
+
+        // Round trip of a real setup
+        let mut rng = ark_std::test_rng();
+        let expected = KZG10::<E, UniPoly381>::setup(8, Fr::rand(&mut rng)).unwrap();
+        let mut g1_bytes = Vec::new();
+        let mut g2_bytes = Vec::new();
+        for (g, h) in expected.powers_of_g.iter().zip(&expected.powers_of_h) {
+            g.serialize_compressed(&mut g1_bytes).unwrap();
+            h.serialize_compressed(&mut g2_bytes).unwrap();
+        }
+        let params = PowersOfTau::from_eth_kzg(&g1_bytes, &g2_bytes).unwrap();
+        assert_eq!(params.powers_of_g, expected.powers_of_g);
+        assert_eq!(params.powers_of_h, expected.powers_of_h);
+    }
+
+    #[test]
+    fn test_from_eth_kzg_rejects_bad_points() {
+        let g1 = ark_bls12_381::G1Affine::generator();
+        let g2 = ark_bls12_381::G2Affine::generator();
+        let mut g1_bytes = Vec::new();
+        let mut g2_bytes = Vec::new();
+        g1.serialize_compressed(&mut g1_bytes).unwrap();
+        g2.serialize_compressed(&mut g2_bytes).unwrap();
+        let g2_bytes = g2_bytes.repeat(2);
+
+        // Truncated input
+        assert!(PowersOfTau::from_eth_kzg(&g1_bytes.repeat(2)[..95], &g2_bytes).is_err());
+
+        // Compression flag cleared, as in an uncompressed or little-endian encoding
+        let mut unflagged = g1_bytes.repeat(2);
+        unflagged[0] &= 0x7f;
+        assert!(PowersOfTau::from_eth_kzg(&unflagged, &g2_bytes).is_err());
+
+        // On the curve but outside the prime-order subgroup
+        let mut x = ark_bls12_381::Fq::from(1u64);
+        let outside = loop {
+            if let Some(p) = ark_bls12_381::G1Affine::get_point_from_x_unchecked(x, true) {
+                if !p.is_in_correct_subgroup_assuming_on_curve() {
+                    break p;
+                }
+            }
+            x += ark_bls12_381::Fq::from(1u64);
+        };
+        let mut bad = g1_bytes.clone();
+        outside.serialize_compressed(&mut bad).unwrap();
+        let result = PowersOfTau::from_eth_kzg(&bad, &g2_bytes);
+        assert!(matches!(result, Err(SteError::SerializationError(_))));
+    }
+
+    #[test]
+    fn test_diff_extends_to_higher_degree() {
+        let mut rng = ark_std::test_rng();
+        let tau = Fr::rand(&mut rng);
+        let mut small = KZG10::<E, UniPoly381>::setup(8, tau).unwrap();
+        let large = KZG10::<E, UniPoly381>::setup(16, tau).unwrap();
+
+        // Only the 8 appended powers of each group travel
+        let diff = small.diff(&large);
+        let decoded = PowersDiff::<E>::deserialize_compressed(&*diff).unwrap();
+        assert_eq!(
+            decoded.g1.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+            (9..17).collect::<Vec<_>>()
+        );
+        assert_eq!(decoded.g2.len(), 8);
+
+        let mut other = KZG10::<E, UniPoly381>::setup(8, Fr::rand(&mut rng)).unwrap();
+        assert!(other.apply_diff(&diff).is_err());
+        assert!(small.apply_diff(&diff[..diff.len() - 1]).is_err());
+
+        small.apply_diff(&diff).unwrap();
+        assert_eq!(small.powers_of_g, large.powers_of_g);
+        assert_eq!(small.powers_of_h, large.powers_of_h);
+
+        // A diff can also shrink or replace parameters
+        let mut replaced = large.clone();
+        replaced.apply_diff(&large.diff(&other)).unwrap();
+        assert_eq!(replaced.powers_of_g, other.powers_of_g);
+        assert_eq!(replaced.powers_of_h, other.powers_of_h);
+    }
+
+    #[test]
+    fn test_validate_lengths_rejects_mismatch() {
+        let mut rng = ark_std::test_rng();
+        let mut params = KZG10::<E, UniPoly381>::setup(8, Fr::rand(&mut rng)).unwrap();
+        params.powers_of_h.truncate(5);
+
+        assert!(matches!(
+            params.validate_lengths(),
+            Err(Error::MismatchedPowers {
+                num_g1_powers: 9,
+                num_g2_powers: 5,
+            })
+        ));
+    }
+}
@@ -64,13 +64,36 @@
 //! let dec_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
 //! ```
 
+/// Evaluates `$body` inside a `debug`-level tracing span named `$name`.
+///
+/// Without the `tracing` feature this is just `$body`.
+macro_rules! traced {
+    ($name:literal, $body:expr) => {{
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!($name).entered();
+        $body
+    }};
+}
+
+pub mod container;
 pub mod decryption;
 pub mod encryption;
 pub mod error;
+pub mod hybrid;
 pub mod kzg;
+pub mod policy;
+pub mod rng;
 pub mod security;
 pub mod setup;
+pub mod sizes;
+pub mod stream;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
+#[cfg(feature = "serde")]
+pub mod testvectors;
+pub mod transfer;
 pub mod trusted_setup;
 pub mod utils;
+pub mod weighted;
 
 pub use error::SteError;
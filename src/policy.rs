@@ -0,0 +1,332 @@
+//! Threshold-of-groups access structures.
+//!
+//! A policy such as "1 from group X and 2 from group Y" is a conjunction of
+//! per-group thresholds. A single weighted threshold cannot express it, so each
+//! group gets its own committee: a [`WeightedCommittee`] in which every member
+//! has weight 1. A party keys its slot in every group it belongs to with the
+//! same secret key.
+//!
+//! Encryption produces one ciphertext per group, and the policy key is the sum
+//! of their keys in GT. Recovering it needs every group's key, i.e. a quorum
+//! of each group.
+
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_std::rand::RngCore;
+
+use crate::{
+    encryption::Ciphertext,
+    error::SteError,
+    kzg::PowersOfTau,
+    setup::PublicKey,
+    weighted::{agg_dec_weighted, encrypt_weighted, WeightedAggregateKey, WeightedCommittee},
+};
+
+/// A group of parties of which at least `threshold` must take part.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ThresholdGroup {
+    /// Application-level party IDs, in slot order
+    pub members: Vec<usize>,
+    pub threshold: usize,
+}
+
+/// A conjunction of [`ThresholdGroup`]s: a set of parties is authorized if it
+/// meets the threshold of every group.
+#[derive(Clone, Debug)]
+pub struct AccessStructure {
+    groups: Vec<ThresholdGroup>,
+    committees: Vec<WeightedCommittee>,
+}
+
+impl AccessStructure {
+    /// Builds the access structure and the committee layout of each group.
+    ///
+    /// # Errors
+    /// Returns an error if there are no groups, or a group is empty, repeats a
+    /// member, or has a threshold outside `[1, members.len()]`
+    pub fn new(groups: Vec<ThresholdGroup>) -> Result<Self, SteError> {
+        if groups.is_empty() {
+            return Err(SteError::InvalidParameter(
+                "access structure needs at least one group".to_string(),
+            ));
+        }
+
+        let mut committees = Vec::with_capacity(groups.len());
+        for (g, group) in groups.iter().enumerate() {
+            let mut members = group.members.clone();
+            members.sort_unstable();
+            if members.windows(2).any(|w| w[0] == w[1]) {
+                return Err(SteError::InvalidParameter(format!(
+                    "group {} lists a member twice",
+                    g
+                )));
+            }
+            if group.threshold == 0 || group.threshold > group.members.len() {
+                return Err(SteError::InvalidThreshold(format!(
+                    "group {} threshold must be in [1, {}], got {}",
+                    g,
+                    group.members.len(),
+                    group.threshold
+                )));
+            }
+            committees.push(WeightedCommittee::new(&vec![1; group.members.len()])?);
+        }
+
+        Ok(Self { groups, committees })
+    }
+
+    pub fn groups(&self) -> &[ThresholdGroup] {
+        &self.groups
+    }
+
+    /// The committee layout of group `g`. Member `j` of the group owns slot
+    /// `committee(g).slots(j)`.
+    ///
+    /// # Panics
+    /// Panics if `g` is out of range
+    pub fn committee(&self, g: usize) -> &WeightedCommittee {
+        &self.committees[g]
+    }
+
+    /// Whether `parties` meet the threshold of every group.
+    pub fn is_satisfied_by(&self, parties: &[usize]) -> bool {
+        self.groups.iter().all(|group| {
+            group
+                .members
+                .iter()
+                .filter(|member| parties.contains(member))
+                .count()
+                >= group.threshold
+        })
+    }
+}
+
+/// One aggregate key per group of an [`AccessStructure`].
+#[derive(Clone, Debug)]
+pub struct PolicyAggregateKey<E: Pairing> {
+    pub structure: AccessStructure,
+    pub group_keys: Vec<WeightedAggregateKey<E>>,
+}
+
+impl<E: Pairing> PolicyAggregateKey<E> {
+    /// Builds the per-group aggregate keys.
+    ///
+    /// # Arguments
+    /// * `structure` - The access structure
+    /// * `group_pks` - For each group, each member's public key for its slot in that group's committee
+    /// * `params` - The KZG parameters, large enough for the largest group committee
+    /// * `rng` - Randomness for the padding keys
+    ///
+    /// # Errors
+    /// Returns an error if the keys don't match the groups' layouts, or if
+    /// aggregation fails
+    pub fn new<R: RngCore>(
+        structure: AccessStructure,
+        group_pks: Vec<Vec<PublicKey<E>>>,
+        params: &PowersOfTau<E>,
+        rng: &mut R,
+    ) -> Result<Self, SteError> {
+        if group_pks.len() != structure.groups.len() {
+            return Err(SteError::ValidationError(format!(
+                "expected public keys for {} groups, got {}",
+                structure.groups.len(),
+                group_pks.len()
+            )));
+        }
+
+        let group_keys = group_pks
+            .into_iter()
+            .zip(&structure.committees)
+            .map(|(pks, committee)| {
+                let party_pks = pks.into_iter().map(|pk| vec![pk]).collect();
+                WeightedAggregateKey::new(committee.clone(), party_pks, params, rng)
+            })
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            structure,
+            group_keys,
+        })
+    }
+}
+
+/// A ciphertext under an [`AccessStructure`]: one threshold ciphertext per group.
+#[derive(Clone, Debug)]
+pub struct PolicyCiphertext<E: Pairing> {
+    pub parts: Vec<Ciphertext<E>>,
+}
+
+impl<E: Pairing> PolicyCiphertext<E> {
+    /// The encapsulated key, recovered by [`agg_dec_policy`].
+    pub fn enc_key(&self) -> PairingOutput<E> {
+        self.parts.iter().map(|ct| ct.enc_key).sum()
+    }
+}
+
+/// Encrypts so that only sets satisfying the access structure can decrypt.
+///
+/// # Errors
+/// Returns any error from [`encrypt_weighted`]
+pub fn encrypt_policy<E: Pairing, R: RngCore>(
+    pak: &PolicyAggregateKey<E>,
+    params: &PowersOfTau<E>,
+    rng: &mut R,
+) -> Result<PolicyCiphertext<E>, SteError> {
+    let parts = pak
+        .group_keys
+        .iter()
+        .zip(&pak.structure.groups)
+        .map(|(wak, group)| encrypt_weighted(wak, group.threshold, params, rng))
+        .collect::<Result<_, _>>()?;
+    Ok(PolicyCiphertext { parts })
+}
+
+/// Aggregates partial decryptions of every group to recover the policy key.
+///
+/// # Arguments
+/// * `partial_decryptions` - For each group, one entry per member: the member's
+///   partial decryption of `ct.parts[g]`, or `None` if it did not respond
+/// * `ct` - The ciphertext to decrypt
+/// * `pak` - The policy aggregate key
+/// * `params` - The KZG parameters
+///
+/// # Errors
+/// Returns `SteError::InvalidThreshold` if some group is below its threshold,
+/// or any error from [`agg_dec_weighted`]
+pub fn agg_dec_policy<E: Pairing>(
+    partial_decryptions: &[Vec<Option<E::G2>>],
+    ct: &PolicyCiphertext<E>,
+    pak: &PolicyAggregateKey<E>,
+    params: &PowersOfTau<E>,
+) -> Result<PairingOutput<E>, SteError> {
+    let num_groups = pak.structure.groups.len();
+    if partial_decryptions.len() != num_groups || ct.parts.len() != num_groups {
+        return Err(SteError::ValidationError(format!(
+            "expected partial decryptions and ciphertexts for {} groups, got {} and {}",
+            num_groups,
+            partial_decryptions.len(),
+            ct.parts.len()
+        )));
+    }
+
+    for (g, (pds, group)) in partial_decryptions
+        .iter()
+        .zip(&pak.structure.groups)
+        .enumerate()
+    {
+        let responded = pds.iter().filter(|pd| pd.is_some()).count();
+        if responded < group.threshold {
+            return Err(SteError::InvalidThreshold(format!(
+                "group {} has {} partial decryptions, needs {}",
+                g, responded, group.threshold
+            )));
+        }
+    }
+
+    let mut key = PairingOutput::<E>::default();
+    for ((pds, part), wak) in partial_decryptions
+        .iter()
+        .zip(&ct.parts)
+        .zip(&pak.group_keys)
+    {
+        key += agg_dec_weighted(pds, part, wak, params)?;
+    }
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kzg::KZG10,
+        setup::{LagrangePowers, SecretKey},
+    };
+    use ark_poly::univariate::DensePolynomial;
+    use ark_std::UniformRand;
+
+    type E = ark_bls12_381::Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+    type UniPoly381 = DensePolynomial<Fr>;
+
+    #[test]
+    fn test_one_from_x_and_two_from_y() {
+        let mut rng = ark_std::test_rng();
+
+        // Parties 0, 1 form group X; parties 2, 3, 4 form group Y
+        let structure = AccessStructure::new(vec![
+            ThresholdGroup {
+                members: vec![0, 1],
+                threshold: 1,
+            },
+            ThresholdGroup {
+                members: vec![2, 3, 4],
+                threshold: 2,
+            },
+        ])
+        .unwrap();
+        let sk: Vec<SecretKey<E>> = (0..5).map(|_| SecretKey::new(&mut rng)).collect();
+
+        let max_n = (0..2).map(|g| structure.committee(g).n()).max().unwrap();
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(max_n, tau).unwrap();
+        let group_pks = (0..2)
+            .map(|g| {
+                let committee = structure.committee(g);
+                let lagrange_params = LagrangePowers::<E>::new(tau, committee.n()).unwrap();
+                structure.groups()[g]
+                    .members
+                    .iter()
+                    .enumerate()
+                    .map(|(j, &party)| {
+                        sk[party]
+                            .lagrange_get_pk(
+                                committee.slots(j).start,
+                                &lagrange_params,
+                                committee.n(),
+                            )
+                            .unwrap()
+                    })
+                    .collect()
+            })
+            .collect();
+        let pak = PolicyAggregateKey::new(structure, group_pks, &params, &mut rng).unwrap();
+        let ct = encrypt_policy(&pak, &params, &mut rng).unwrap();
+
+        let partials = |parties: &[usize]| -> Vec<Vec<Option<<E as Pairing>::G2>>> {
+            pak.structure
+                .groups()
+                .iter()
+                .zip(&ct.parts)
+                .map(|(group, part)| {
+                    group
+                        .members
+                        .iter()
+                        .map(|p| parties.contains(p).then(|| sk[*p].partial_decryption(part)))
+                        .collect()
+                })
+                .collect()
+        };
+
+        for parties in [vec![0, 2, 3], vec![1, 3, 4], vec![0, 1, 2, 3, 4]] {
+            assert!(pak.structure.is_satisfied_by(&parties));
+            let key = agg_dec_policy(&partials(&parties), &ct, &pak, &params).unwrap();
+            assert_eq!(key, ct.enc_key());
+        }
+
+        // Missing X, or only one from Y
+        for parties in [vec![2, 3, 4], vec![0, 1, 2]] {
+            assert!(!pak.structure.is_satisfied_by(&parties));
+            let err = agg_dec_policy(&partials(&parties), &ct, &pak, &params).unwrap_err();
+            assert!(matches!(err, SteError::InvalidThreshold(_)), "{err:?}");
+        }
+    }
+
+    #[test]
+    fn test_access_structure_rejects_bad_groups() {
+        let group = |members: Vec<usize>, threshold| ThresholdGroup { members, threshold };
+        assert!(AccessStructure::new(vec![]).is_err());
+        assert!(AccessStructure::new(vec![group(vec![], 1)]).is_err());
+        assert!(AccessStructure::new(vec![group(vec![1, 2], 0)]).is_err());
+        assert!(AccessStructure::new(vec![group(vec![1, 2], 3)]).is_err());
+        assert!(AccessStructure::new(vec![group(vec![1, 1], 1)]).is_err());
+    }
+}
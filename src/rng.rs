@@ -0,0 +1,107 @@
+//! Cryptographically secure randomness shared by the binaries and the WASM client.
+//!
+//! [`SecureRng::new`] draws every byte straight from the operating system via
+//! `getrandom` (`crypto.getRandomValues()` in the browser), so there is no
+//! userspace state to seed, fork or leak. [`SecureRng::from_seed`] gives a
+//! reproducible ChaCha stream for tests and debugging only.
+
+use ark_std::rand::{rngs::StdRng, CryptoRng, Error, RngCore, SeedableRng};
+use std::num::NonZeroU32;
+
+/// A CSPRNG implementing arkworks' `RngCore`.
+pub struct SecureRng {
+    seeded: Option<StdRng>,
+}
+
+impl SecureRng {
+    /// Reads from OS entropy on every call.
+    pub fn new() -> Self {
+        Self { seeded: None }
+    }
+
+    /// A deterministic generator for tests.
+    ///
+    /// INSECURE: anyone who knows the seed can reproduce every value drawn,
+    /// including secret keys and tau.
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        Self {
+            seeded: Some(StdRng::from_seed(seed)),
+        }
+    }
+}
+
+impl Default for SecureRng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RngCore for SecureRng {
+    fn next_u32(&mut self) -> u32 {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes);
+        u32::from_le_bytes(bytes)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes);
+        u64::from_le_bytes(bytes)
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest)
+            .expect("the operating system failed to provide randomness")
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        match &mut self.seeded {
+            Some(rng) => rng.try_fill_bytes(dest),
+            // rand 0.8's `Error` only carries a code without its std feature
+            None => getrandom::fill(dest).map_err(|_| {
+                Error::from(NonZeroU32::new(Error::CUSTOM_START).expect("CUSTOM_START is non-zero"))
+            }),
+        }
+    }
+}
+
+impl CryptoRng for SecureRng {}
+
+impl std::fmt::Debug for SecureRng {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let source = if self.seeded.is_some() {
+            "seeded"
+        } else {
+            "os"
+        };
+        write!(f, "SecureRng({})", source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_os_rng_output_is_not_constant() {
+        let mut rng = SecureRng::new();
+        let mut first = [0u8; 64];
+        let mut second = [0u8; 64];
+        rng.fill_bytes(&mut first);
+        rng.fill_bytes(&mut second);
+
+        assert_ne!(first, second);
+        assert!(first.iter().any(|&b| b != first[0]));
+    }
+
+    #[test]
+    fn test_from_seed_is_deterministic() {
+        let mut a = SecureRng::from_seed([7u8; 32]);
+        let mut b = SecureRng::from_seed([7u8; 32]);
+        let mut c = SecureRng::from_seed([8u8; 32]);
+
+        let draws: Vec<u64> = (0..4).map(|_| a.next_u64()).collect();
+        assert_eq!(draws, (0..4).map(|_| b.next_u64()).collect::<Vec<_>>());
+        assert_ne!(draws, (0..4).map(|_| c.next_u64()).collect::<Vec<_>>());
+    }
+}
@@ -4,15 +4,22 @@
 //! - Memory protection traits for sensitive data
 //! - Constant-time comparison operations
 //! - Zeroization helpers for arkworks types
+//! - Key derivation from recovered GT elements
 
-use ark_ec::pairing::Pairing;
+use ark_ec::pairing::{Pairing, PairingOutput};
 use ark_ff::Field;
 use ark_serialize::{
     CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
     Write,
 };
 use ark_std::vec::Vec;
-use zeroize::{Zeroize, ZeroizeOnDrop};
+use blake2::{Blake2b512, Digest};
+use hkdf::Hkdf;
+use sha2::{Sha256, Sha512};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+/// Domain separation tag used when deriving a symmetric key from a recovered GT element
+pub const SYMMETRIC_KEY_DST: &[u8] = b"silent-threshold-encryption/symmetric-key/v1";
 
 /// Wrapper for sensitive scalar field elements that ensures zeroization on drop
 ///
@@ -127,6 +134,143 @@ impl<F: Field> std::fmt::Debug for SensitiveScalar<F> {
     }
 }
 
+/// Wrapper for the GT element recovered by threshold decryption
+///
+/// The output of `agg_dec` is the key material protecting the payload, so it
+/// is treated like any other secret: it is overwritten when dropped and never
+/// printed through `Debug`.
+///
+/// # Security
+/// - Zeroizes the wrapped element on drop
+/// - Intermediate serializations used for key derivation are zeroized as well
+/// - Prevents accidental leakage through Debug trait
+/// - Equality is checked in constant time
+#[derive(Clone)]
+pub struct RecoveredKey<E: Pairing> {
+    value: PairingOutput<E>,
+}
+
+impl<E: Pairing> RecoveredKey<E> {
+    /// Wrap a recovered pairing output
+    pub fn new(value: PairingOutput<E>) -> Self {
+        Self { value }
+    }
+
+    /// Get a reference to the recovered GT element
+    ///
+    /// # Security Warning
+    /// The caller must ensure this reference is not used to leak the value
+    pub fn expose(&self) -> &PairingOutput<E> {
+        &self.value
+    }
+
+    /// Derive a 32-byte symmetric key from the recovered GT element with `K`
+    ///
+    /// The GT element is always fed to the KDF in compressed form, so the
+    /// input is the same whichever KDF is chosen. Use [`DefaultKdf`] unless
+    /// interoperating with a system that expects another one. The serialized
+    /// element is zeroized before returning.
+    pub fn derive_symmetric_key<K: Kdf>(&self) -> Zeroizing<[u8; 32]> {
+        let mut gt_bytes = Zeroizing::new(Vec::new());
+        self.value
+            .serialize_compressed(&mut *gt_bytes)
+            .expect("serializing a GT element into a Vec cannot fail");
+        K::derive_key(&gt_bytes)
+    }
+}
+
+/// A key derivation function for turning a recovered GT element into a
+/// symmetric key.
+///
+/// Implementations bind the output to [`SYMMETRIC_KEY_DST`] so keys derived
+/// here cannot collide with other uses of the same input.
+pub trait Kdf {
+    /// Derives a 32-byte key from the compressed GT element `ikm`
+    fn derive_key(ikm: &[u8]) -> Zeroizing<[u8; 32]>;
+}
+
+/// The KDF used by the crate's own payload encryption
+pub type DefaultKdf = HkdfSha256;
+
+/// HKDF-SHA256 with [`SYMMETRIC_KEY_DST`] as the salt and empty info
+pub struct HkdfSha256;
+
+/// HKDF-SHA512 with [`SYMMETRIC_KEY_DST`] as the salt and empty info
+pub struct HkdfSha512;
+
+/// `Blake2b512(SYMMETRIC_KEY_DST || ikm)` truncated to 32 bytes
+pub struct Blake2bKdf;
+
+impl Kdf for HkdfSha256 {
+    fn derive_key(ikm: &[u8]) -> Zeroizing<[u8; 32]> {
+        let mut key = Zeroizing::new([0u8; 32]);
+        Hkdf::<Sha256>::new(Some(SYMMETRIC_KEY_DST), ikm)
+            .expand(&[], &mut *key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        key
+    }
+}
+
+impl Kdf for HkdfSha512 {
+    fn derive_key(ikm: &[u8]) -> Zeroizing<[u8; 32]> {
+        let mut key = Zeroizing::new([0u8; 32]);
+        Hkdf::<Sha512>::new(Some(SYMMETRIC_KEY_DST), ikm)
+            .expand(&[], &mut *key)
+            .expect("32 bytes is a valid HKDF-SHA512 output length");
+        key
+    }
+}
+
+impl Kdf for Blake2bKdf {
+    fn derive_key(ikm: &[u8]) -> Zeroizing<[u8; 32]> {
+        let mut hasher = Blake2b512::new();
+        hasher.update(SYMMETRIC_KEY_DST);
+        hasher.update(ikm);
+        let mut digest = hasher.finalize();
+
+        let mut key = Zeroizing::new([0u8; 32]);
+        key.copy_from_slice(&digest[..32]);
+        digest.as_mut_slice().zeroize();
+        key
+    }
+}
+
+impl<E: Pairing> Zeroize for RecoveredKey<E> {
+    fn zeroize(&mut self) {
+        // Overwrite with the identity element. A plain assignment in `drop`
+        // is a dead store the optimizer may remove, so write it volatile and
+        // keep later accesses from being reordered before it.
+        //
+        // SAFETY: `self.value` is a valid, aligned, exclusively borrowed
+        // `PairingOutput`, which is `Copy`, so skipping the old value's drop
+        // is sound.
+        unsafe { core::ptr::write_volatile(&mut self.value, PairingOutput::<E>::default()) };
+        core::sync::atomic::compiler_fence(core::sync::atomic::Ordering::SeqCst);
+    }
+}
+
+impl<E: Pairing> ZeroizeOnDrop for RecoveredKey<E> {}
+
+impl<E: Pairing> Drop for RecoveredKey<E> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<E: Pairing> PartialEq for RecoveredKey<E> {
+    fn eq(&self, other: &Self) -> bool {
+        constant_time_eq_pairing(&self.value, &other.value)
+    }
+}
+
+impl<E: Pairing> Eq for RecoveredKey<E> {}
+
+impl<E: Pairing> std::fmt::Debug for RecoveredKey<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("RecoveredKey([REDACTED])")
+    }
+}
+
 /// Constant-time equality comparison for field elements
 ///
 /// This function attempts to perform constant-time comparison to prevent
@@ -176,6 +320,19 @@ fn subtle_constant_time_eq(a: &[u8], b: &[u8]) -> bool {
     result == 0
 }
 
+/// Constant-time equality comparison for selectors
+///
+/// Compares two participant selectors without revealing which positions
+/// differ. Only the lengths may leak, and those equal the committee size.
+///
+/// # Returns
+/// `true` if the selectors are equal, `false` otherwise
+pub fn selectors_eq_ct(a: &[bool], b: &[bool]) -> bool {
+    let a_bytes: Vec<u8> = a.iter().map(|&x| x as u8).collect();
+    let b_bytes: Vec<u8> = b.iter().map(|&x| x as u8).collect();
+    subtle_constant_time_eq(&a_bytes, &b_bytes)
+}
+
 /// Verify BLS signature in constant-time
 ///
 /// This function verifies a BLS signature by checking if the partial decryption
@@ -369,6 +526,21 @@ mod tests {
         assert_eq!(sensitive.expose_secret(), &Fr::zero());
     }
 
+    #[test]
+    fn test_recovered_key_zeroization() {
+        use ark_std::UniformRand;
+        type E = ark_bls12_381::Bls12_381;
+        let mut rng = ark_std::test_rng();
+        let value = PairingOutput::<E>::rand(&mut rng);
+
+        let mut key = RecoveredKey::new(value);
+        assert_eq!(key.expose(), &value);
+        assert!(key == RecoveredKey::new(value));
+        key.zeroize();
+        assert_eq!(key.expose(), &PairingOutput::<E>::default());
+        assert!(key != RecoveredKey::new(value));
+    }
+
     #[test]
     fn test_sensitive_scalar_drop() {
         use ark_std::UniformRand;
@@ -507,6 +679,23 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_selectors_eq_ct_matches_eq() {
+        let selectors: [&[bool]; 6] = [
+            &[],
+            &[true],
+            &[true, false, true, false],
+            &[true, false, true, true],
+            &[true, true, true, true],
+            &[true, false, true],
+        ];
+        for a in selectors {
+            for b in selectors {
+                assert_eq!(selectors_eq_ct(a, b), a == b, "{a:?} vs {b:?}");
+            }
+        }
+    }
+
     #[test]
     fn test_constant_time_eq_pairing() {
         use ark_bls12_381::Bls12_381;
@@ -533,6 +722,27 @@ mod tests {
         assert!(!constant_time_eq_pairing::<E>(&pairing1, &pairing3));
     }
 
+    #[test]
+    fn test_kdfs_are_deterministic_and_distinct() {
+        type E = ark_bls12_381::Bls12_381;
+        let mut rng = ark_std::test_rng();
+        let key = RecoveredKey::<E>::new(PairingOutput::<E>::rand(&mut rng));
+
+        let hkdf = key.derive_symmetric_key::<HkdfSha256>();
+        let hkdf512 = key.derive_symmetric_key::<HkdfSha512>();
+        let blake = key.derive_symmetric_key::<Blake2bKdf>();
+
+        assert_eq!(hkdf, key.derive_symmetric_key::<HkdfSha256>());
+        assert_eq!(hkdf512, key.derive_symmetric_key::<HkdfSha512>());
+        assert_eq!(blake, key.derive_symmetric_key::<Blake2bKdf>());
+        assert_ne!(hkdf, blake);
+        assert_ne!(hkdf, hkdf512);
+        assert_ne!(hkdf512, blake);
+
+        let other = RecoveredKey::<E>::new(PairingOutput::<E>::rand(&mut rng));
+        assert_ne!(hkdf, other.derive_symmetric_key::<HkdfSha256>());
+    }
+
     #[test]
     fn test_sensitive_scalar_debug() {
         use ark_bls12_381::Fr;
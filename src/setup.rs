@@ -1,21 +1,289 @@
-use crate::encryption::Ciphertext;
+use crate::decryption::Commitment;
+use crate::encryption::{Ciphertext, Encapsulation};
 use crate::error::SteError;
 use crate::kzg::{PowersOfTau, KZG10};
-use crate::security::SensitiveScalar;
-use crate::utils::lagrange_poly;
+use crate::security::{SensitiveScalar, SensitiveVec};
+use crate::utils::{lagrange_poly, make_domain};
 use ark_ec::pairing::PairingOutput;
-use ark_ec::{pairing::Pairing, PrimeGroup};
-use ark_ff::Field;
-use ark_poly::{
-    domain::EvaluationDomain, univariate::DensePolynomial, DenseUVPolynomial, Polynomial,
-    Radix2EvaluationDomain,
-};
+use ark_ec::{pairing::Pairing, scalar_mul::ScalarMul, CurveGroup, PrimeGroup, VariableBaseMSM};
+use ark_ff::{Field, PrimeField};
+use ark_poly::{domain::EvaluationDomain, univariate::DensePolynomial, DenseUVPolynomial};
 use ark_serialize::*;
 use ark_std::{rand::RngCore, One, UniformRand, Zero};
+use blake2::{Blake2b512, Digest};
 use rayon::prelude::*;
 use std::ops::{Mul, Sub};
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
+/// Smallest supported committee: the dummy party plus one real party.
+pub const MIN_COMMITTEE_SIZE: usize = 2;
+
+/// Largest committee size accepted by default.
+///
+/// [`LagrangePowers`] holds an n × n matrix of G1 points, so n = 4096 already
+/// needs gigabytes; a bigger `n` is far more likely a mistake or an attack
+/// than a real committee. Use [`CommitteeSizeLimit`] to raise it.
+pub const MAX_COMMITTEE_SIZE: usize = 1 << 12;
+
+/// Upper bound on `n`, checked before anything of size `n` is allocated.
+///
+/// Defaults to [`MAX_COMMITTEE_SIZE`]:
+///
+/// ```
+/// use silent_threshold_encryption::setup::{CommitteeSizeLimit, MAX_COMMITTEE_SIZE};
+///
+/// assert_eq!(CommitteeSizeLimit::default().max(), MAX_COMMITTEE_SIZE);
+/// let limit = CommitteeSizeLimit::default().with_max(1 << 14);
+/// assert!(limit.check(1 << 13).is_ok());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommitteeSizeLimit {
+    max: usize,
+}
+
+impl Default for CommitteeSizeLimit {
+    fn default() -> Self {
+        Self {
+            max: MAX_COMMITTEE_SIZE,
+        }
+    }
+}
+
+impl CommitteeSizeLimit {
+    /// Sets the largest accepted committee size.
+    pub fn with_max(mut self, max: usize) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// Returns the largest accepted committee size.
+    pub fn max(&self) -> usize {
+        self.max
+    }
+
+    /// # Errors
+    /// Returns `SteError::InvalidParameter` if `n` exceeds the limit
+    pub fn check(&self, n: usize) -> Result<(), SteError> {
+        if n > self.max {
+            return Err(SteError::InvalidParameter(format!(
+                "committee size n = {} exceeds the maximum of {}",
+                n, self.max
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Validates the committee size `n` shared by setup, key generation and aggregation.
+///
+/// # Errors
+/// Returns `SteError::InvalidParameter` if `n < MIN_COMMITTEE_SIZE` or n is not a power of 2
+pub fn validate_committee_size(n: usize) -> Result<(), SteError> {
+    if n < MIN_COMMITTEE_SIZE {
+        return Err(SteError::InvalidParameter(format!(
+            "committee size n must be at least {} (the dummy party plus one party), got {}",
+            MIN_COMMITTEE_SIZE, n
+        )));
+    }
+    if !n.is_power_of_two() {
+        return Err(SteError::InvalidParameter(format!(
+            "n must be a power of 2, got {}",
+            n
+        )));
+    }
+    Ok(())
+}
+
+/// Which committee slots hold dummy parties, whose secret key is 1 (see
+/// [`SecretKey::nullify`]) so anyone can compute their partial decryptions.
+///
+/// Every quorum must include all dummies, and the threshold `t` counts them:
+/// decryption needs `t + 1` selected parties in total. The lowest dummy index
+/// is the anchor, the point at which the selector polynomial is fixed to 1.
+/// Encryption depends on the anchor, so both sides must use the same config.
+///
+/// The default is the single dummy party 0 that [`encrypt`] and [`agg_dec`]
+/// assume.
+///
+/// [`encrypt`]: crate::encryption::encrypt
+/// [`agg_dec`]: crate::decryption::agg_dec
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DummyConfig {
+    /// Sorted, distinct dummy indices
+    indices: Vec<usize>,
+}
+
+impl DummyConfig {
+    /// Uses the given slots as dummy parties.
+    ///
+    /// # Errors
+    /// Returns `SteError::InvalidParameter` if `indices` is empty or repeats an index
+    pub fn new(mut indices: Vec<usize>) -> Result<Self, SteError> {
+        indices.sort_unstable();
+        if indices.is_empty() {
+            return Err(SteError::InvalidParameter(
+                "at least one dummy party is required".to_string(),
+            ));
+        }
+        if indices.windows(2).any(|w| w[0] == w[1]) {
+            return Err(SteError::InvalidParameter(
+                "dummy indices must be distinct".to_string(),
+            ));
+        }
+        Ok(Self { indices })
+    }
+
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// The dummy index at which the selector polynomial is 1.
+    pub fn anchor(&self) -> usize {
+        self.indices[0]
+    }
+
+    /// Checks that every dummy is a slot of a committee of size `n` and that
+    /// threshold `t` needs at least one real party besides the dummies.
+    pub(crate) fn validate(&self, n: usize, t: usize) -> Result<(), SteError> {
+        if let Some(&index) = self.indices.iter().find(|&&i| i >= n) {
+            return Err(SteError::ValidationError(format!(
+                "dummy index {} out of range for n = {}",
+                index, n
+            )));
+        }
+        if t < self.indices.len() {
+            return Err(SteError::InvalidThreshold(format!(
+                "threshold {} lets the {} dummy parties decrypt alone",
+                t,
+                self.indices.len()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks that `selector` includes every dummy.
+    pub(crate) fn check_selector(&self, selector: &[bool]) -> Result<(), SteError> {
+        match self
+            .indices
+            .iter()
+            .find(|&&i| !selector.get(i).copied().unwrap_or(false))
+        {
+            Some(i) => Err(SteError::ValidationError(format!(
+                "Party {} (dummy party) must always be selected",
+                i
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Default for DummyConfig {
+    fn default() -> Self {
+        Self { indices: vec![0] }
+    }
+}
+
+/// Smallest `PowersOfTau` degree that supports a committee of size `n`.
+///
+/// The aggregate key needs `h^(tau^n)` for `z_g2`, so parameters from
+/// `KZG10::setup(max_degree, tau)` need `max_degree >= n`.
+pub fn required_degree(n: usize) -> usize {
+    n
+}
+
+/// Checks that `params` are consistent and large enough for `n` parties.
+///
+/// # Errors
+/// Returns `SteError::ValidationError` if the degree is below
+/// [`required_degree`] or the G1 and G2 powers differ in length
+pub(crate) fn check_params_degree<E: Pairing>(
+    params: &PowersOfTau<E>,
+    n: usize,
+) -> Result<(), SteError> {
+    // z_g2 reads the G2 powers, so measure the degree there
+    let degree = params.powers_of_h.len().checked_sub(1);
+    if degree.is_none_or(|degree| degree < required_degree(n)) {
+        return Err(SteError::ValidationError(format!(
+            "params degree {} < required n = {}",
+            degree.unwrap_or(0),
+            required_degree(n)
+        )));
+    }
+    params
+        .validate_lengths()
+        .map_err(|e| SteError::ValidationError(e.to_string()))
+}
+
+/// Self-test for a committee's setup, to run before going into production.
+///
+/// Checks that `params` are large enough for `n` parties, that `lagrange` has
+/// one entry per party, and that a throwaway key gets the same public key
+/// from both: through [`SecretKey::get_pk`] and through
+/// [`SecretKey::lagrange_get_pk`], for the first and last slot. That fails if
+/// `params` and `lagrange` were made from different taus.
+///
+/// # Errors
+/// Returns `SteError::ValidationError` naming the first check that fails, or
+/// any error from computing the public keys
+pub fn selfcheck<E: Pairing>(
+    params: &PowersOfTau<E>,
+    lagrange: &LagrangePowers<E>,
+    n: usize,
+) -> Result<(), SteError> {
+    validate_committee_size(n)?;
+    check_params_degree(params, n)?;
+    let lengths = [
+        lagrange.li.len(),
+        lagrange.li_minus0.len(),
+        lagrange.li_x.len(),
+        lagrange.li_lj_z.len(),
+    ];
+    if lengths.iter().any(|&len| len != n) || lagrange.li_lj_z.iter().any(|row| row.len() < n) {
+        return Err(SteError::ValidationError(format!(
+            "Lagrange powers are not for n = {}",
+            n
+        )));
+    }
+
+    let sk = SecretKey::<E>::new(&mut crate::rng::SecureRng::new());
+    for id in [0, n - 1] {
+        let pk = sk.get_pk(id, params, n)?;
+        let lagrange_pk = sk.lagrange_get_pk(id, lagrange, n)?;
+        if pk.sk_li != lagrange_pk.sk_li
+            || pk.sk_li_minus0 != lagrange_pk.sk_li_minus0
+            || pk.sk_li_x != lagrange_pk.sk_li_x
+            || pk.sk_li_lj_z != lagrange_pk.sk_li_lj_z
+        {
+            return Err(SteError::ValidationError(format!(
+                "public keys from params and Lagrange powers differ for party {}; \
+                 were they made from the same tau?",
+                id
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Commitment in G2 to the vanishing polynomial `Z(x) = x^n - 1` of the
+/// size-`n` domain: `h^(tau^n) - h`, as stored in [`AggregateKey::z_g2`].
+///
+/// External verifiers can rebuild the decryption pairing equation from this
+/// and `params` without the whole aggregate key.
+///
+/// # Errors
+/// Returns `SteError::ValidationError` if `params` have no power `tau^n` in G2
+pub fn compute_z_g2<E: Pairing>(params: &PowersOfTau<E>, n: usize) -> Result<E::G2, SteError> {
+    let tau_n = params.powers_of_h.get(n).ok_or_else(|| {
+        SteError::ValidationError(format!(
+            "params have {} G2 powers, z_g2 for n = {} needs {}",
+            params.powers_of_h.len(),
+            n,
+            n + 1
+        ))
+    })?;
+    Ok(*tau_n - params.powers_of_h[0])
+}
+
 #[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug)]
 pub struct LagrangePowers<E: Pairing> {
     pub li: Vec<E::G1>,
@@ -29,73 +297,85 @@ impl<E: Pairing> LagrangePowers<E> {
     ///
     /// # Arguments
     /// * `tau` - The evaluation point (must be non-zero)
-    /// * `n` - The number of parties (must be a power of 2)
+    /// * `n` - The number of parties (a power of 2, at least `MIN_COMMITTEE_SIZE`
+    ///   and at most [`MAX_COMMITTEE_SIZE`])
     ///
     /// # Errors
-    /// Returns an error if tau is zero or n is not a power of 2
+    /// Returns an error if tau is zero, n < 2, n is not a power of 2 or n
+    /// exceeds [`MAX_COMMITTEE_SIZE`]
     pub fn new(tau: E::ScalarField, n: usize) -> Result<Self, SteError> {
+        Self::new_with_limit(tau, n, CommitteeSizeLimit::default())
+    }
+
+    /// Like [`Self::new`], with a custom upper bound on `n`.
+    ///
+    /// # Errors
+    /// As [`Self::new`], with `limit` in place of [`MAX_COMMITTEE_SIZE`]
+    pub fn new_with_limit(
+        tau: E::ScalarField,
+        n: usize,
+        limit: CommitteeSizeLimit,
+    ) -> Result<Self, SteError> {
         // Validate inputs
-        if n == 0 {
-            return Err(SteError::InvalidParameter(
-                "n must be at least 1".to_string(),
-            ));
-        }
+        limit.check(n)?;
+        validate_committee_size(n)?;
         if tau.is_zero() {
             return Err(SteError::InvalidParameter("tau cannot be zero".to_string()));
         }
-        if !n.is_power_of_two() {
-            return Err(SteError::InvalidParameter(format!(
-                "n must be a power of 2, got {}",
-                n
-            )));
-        }
 
-        let mut li_evals: Vec<E::ScalarField> = vec![E::ScalarField::zero(); n];
-        let mut li_evals_minus0: Vec<E::ScalarField> = vec![E::ScalarField::zero(); n];
-        let mut li_evals_x: Vec<E::ScalarField> = vec![E::ScalarField::zero(); n];
+        // Every scalar below is derived from the trapdoor tau, so they are all
+        // held in zeroizing wrappers and wiped when this function returns
+        let domain = make_domain::<E::ScalarField>(n)?;
+        let li_evals = SensitiveVec::new(domain.evaluate_all_lagrange_coefficients(tau));
+        let mut li_evals_minus0 = SensitiveVec::new(vec![E::ScalarField::zero(); n]);
+        let mut li_evals_x = SensitiveVec::new(vec![E::ScalarField::zero(); n]);
         // Since tau is already validated to be non-zero, inverse should always succeed
-        let tau_inv = tau
-            .inverse()
-            .expect("tau inverse should exist since tau was validated to be non-zero");
+        let tau_inv = SensitiveScalar::new(
+            tau.inverse()
+                .expect("tau inverse should exist since tau was validated to be non-zero"),
+        );
+        // Every L_i has constant term L_i(0) = 1/n over the roots of unity
         for i in 0..n {
-            let li = lagrange_poly(n, i);
-            li_evals[i] = li.evaluate(&tau);
-
-            li_evals_minus0[i] = li_evals[i] - li.coeffs[0];
-
-            li_evals_x[i] = li_evals_minus0[i] * tau_inv;
+            let li_eval_minus0 = li_evals.expose_secret()[i] - domain.size_inv;
+            li_evals_minus0.expose_secret_mut()[i] = li_eval_minus0;
+            li_evals_x.expose_secret_mut()[i] = li_eval_minus0 * tau_inv.expose_secret();
         }
 
-        let z_eval = tau.pow([n as u64]) - E::ScalarField::one();
-        let z_eval_inv = z_eval.inverse().ok_or_else(|| {
-            SteError::FieldInverseError("z_eval inverse computation failed".to_string())
-        })?;
+        let z_eval = SensitiveScalar::new(tau.pow([n as u64]) - E::ScalarField::one());
+        let z_eval_inv =
+            SensitiveScalar::new(z_eval.expose_secret().inverse().ok_or_else(|| {
+                SteError::FieldInverseError("z_eval inverse computation failed".to_string())
+            })?);
 
-        let mut li = vec![E::G1::zero(); n];
+        // Lay out every scalar as [li | li_minus0 | li_x | li_lj_z row-major]
+        // so all n^2 + 3n fixed-base multiplications share one windowed table.
+        let li_evals = li_evals.expose_secret();
+        let z_eval_inv = z_eval_inv.expose_secret();
+        let mut scalars = SensitiveVec::new(Vec::with_capacity(n * n + 3 * n));
+        let buf = scalars.expose_secret_mut();
+        buf.extend_from_slice(li_evals);
+        buf.extend_from_slice(li_evals_minus0.expose_secret());
+        buf.extend_from_slice(li_evals_x.expose_secret());
         for i in 0..n {
-            li[i] = E::G1::generator() * li_evals[i];
+            for j in 0..n {
+                buf.push(if i == j {
+                    (li_evals[i] * li_evals[i] - li_evals[i]) * z_eval_inv
+                } else {
+                    li_evals[i] * li_evals[j] * z_eval_inv
+                });
+            }
         }
 
-        let mut li_minus0 = vec![E::G1::zero(); n];
-        li_minus0.par_iter_mut().enumerate().for_each(|(i, elem)| {
-            *elem = E::G1::generator() * li_evals_minus0[i];
-        });
-
-        let mut li_x = vec![E::G1::zero(); n];
-        li_x.par_iter_mut().enumerate().for_each(|(i, elem)| {
-            *elem = E::G1::generator() * li_evals_x[i];
-        });
-
-        let mut li_lj_z = vec![vec![E::G1::zero(); n]; n];
-        li_lj_z.par_iter_mut().enumerate().for_each(|(i, row)| {
-            row.par_iter_mut().enumerate().for_each(|(j, elem)| {
-                *elem = if i == j {
-                    E::G1::generator() * ((li_evals[i] * li_evals[i] - li_evals[i]) * z_eval_inv)
-                } else {
-                    E::G1::generator() * (li_evals[i] * li_evals[j] * z_eval_inv)
-                }
-            });
-        });
+        let points: Vec<E::G1> = E::G1::generator()
+            .batch_mul(scalars.expose_secret())
+            .into_iter()
+            .map(Into::into)
+            .collect();
+        let mut chunks = points.chunks_exact(n).map(<[E::G1]>::to_vec);
+        let li = chunks.next().expect("n rows for li");
+        let li_minus0 = chunks.next().expect("n rows for li_minus0");
+        let li_x = chunks.next().expect("n rows for li_x");
+        let li_lj_z: Vec<Vec<E::G1>> = chunks.collect();
 
         Ok(LagrangePowers {
             li,
@@ -104,6 +384,37 @@ impl<E: Pairing> LagrangePowers<E> {
             li_lj_z,
         })
     }
+
+    /// Derives the Lagrange powers from KZG parameters, without knowing tau.
+    ///
+    /// Each row is the public key of the secret key 1 computed with the slow
+    /// method (see [`SecretKey::get_pk`]), so this costs O(n^2) commitments.
+    /// Use it when the parameters come from a ceremony and tau is unknown.
+    ///
+    /// # Arguments
+    /// * `params` - The KZG parameters (at least `n` powers of g)
+    /// * `n` - The number of parties (a power of 2, at least `MIN_COMMITTEE_SIZE`)
+    ///
+    /// # Errors
+    /// Returns an error if n is invalid or `params` is too short
+    pub fn from_powers_of_tau(params: &PowersOfTau<E>, n: usize) -> Result<Self, SteError> {
+        validate_committee_size(n)?;
+        let one = SecretKey::<E> {
+            sk: SensitiveScalar::one(),
+        };
+
+        let rows = (0..n)
+            .into_par_iter()
+            .map(|id| one.get_pk(id, params, n))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(LagrangePowers {
+            li: rows.iter().map(|pk| pk.sk_li).collect(),
+            li_minus0: rows.iter().map(|pk| pk.sk_li_minus0).collect(),
+            li_x: rows.iter().map(|pk| pk.sk_li_x).collect(),
+            li_lj_z: rows.into_iter().map(|pk| pk.sk_li_lj_z.clone()).collect(),
+        })
+    }
 }
 
 /// Secret key for a party in the threshold encryption scheme.
@@ -170,6 +481,327 @@ impl<E: Pairing> PublicKey<E> {
             sk_li_x,
         }
     }
+
+    /// An empty key for slot `id` of a committee of size `n`, for padding
+    /// absent parties.
+    ///
+    /// Every element is the identity, as if made from secret key 0, so the
+    /// slot adds nothing to the aggregate key. Its "partial decryption" is
+    /// also the identity and needs no secret, so `agg_dec` refuses selectors
+    /// that include a padding slot (see [`PublicKey::is_padding`]).
+    pub fn zero_for_domain(id: usize, n: usize) -> Self {
+        PublicKey {
+            id,
+            bls_pk: E::G1::zero(),
+            sk_li: E::G1::zero(),
+            sk_li_minus0: E::G1::zero(),
+            sk_li_lj_z: vec![E::G1::zero(); n],
+            sk_li_x: E::G1::zero(),
+        }
+    }
+
+    /// Whether this is a padding key from [`PublicKey::zero_for_domain`].
+    ///
+    /// `SecretKey::new` never samples 0, so no real party has an identity
+    /// `bls_pk`.
+    pub fn is_padding(&self) -> bool {
+        self.bls_pk.is_zero()
+    }
+
+    /// Checks that the hints are the ones [`SecretKey::lagrange_get_pk`]
+    /// derives for slot `self.id` of a committee of size `n` from the secret
+    /// key behind `bls_pk`, using only the public parameters.
+    ///
+    /// With `sk` the discrete log of `bls_pk`, `i = self.id`, `h` the G2
+    /// generator and `Z(x) = x^n - 1`, the checks are
+    ///
+    /// ```text
+    /// e(sk_li, h)                = e(bls_pk, L_i(tau) h)
+    /// e(sk_li - sk_li_minus0, h) = e(bls_pk, L_i(0) h)
+    /// e(sk_li_x, tau h)          = e(sk_li_minus0, h)
+    /// e(sum_j r_j sk_li_lj_z[j], Z(tau) h) = e(sk_li, (sum_j r_j L_j(tau) - r_i) h)
+    /// ```
+    ///
+    /// The `n` cross-term hints are folded into the last check with
+    /// coefficients `r_j` derived by hashing the key, so the whole check costs
+    /// eight pairings and three MSMs of size `n`.
+    ///
+    /// # Errors
+    /// Returns a `ValidationError` naming the first hint that fails, or an
+    /// error if `n` is invalid or `params` are too small for it
+    pub fn verify_hints(&self, params: &PowersOfTau<E>, n: usize) -> Result<(), SteError> {
+        validate_committee_size(n)?;
+        check_params_degree(params, n)?;
+        let i = self.id;
+        if i >= n {
+            return Err(SteError::ValidationError(format!(
+                "party id {} out of range for n = {}",
+                i, n
+            )));
+        }
+        if self.sk_li_lj_z.len() != n {
+            return Err(SteError::ValidationError(format!(
+                "party {} has {} hints, expected {}",
+                i,
+                self.sk_li_lj_z.len(),
+                n
+            )));
+        }
+
+        let check = |hint: &str, a: E::G1, b: E::G2, c: E::G1, d: E::G2| {
+            if E::multi_pairing([a, -c], [b, d]).is_zero() {
+                Ok(())
+            } else {
+                Err(SteError::ValidationError(format!(
+                    "party {}'s {} does not match its bls_pk",
+                    i, hint
+                )))
+            }
+        };
+
+        let h: E::G2 = params.powers_of_h[0].into();
+        let h_powers = &params.powers_of_h[..n];
+        let li = lagrange_poly::<E::ScalarField>(n, i);
+        let li_h = E::G2::msm_unchecked(h_powers, &li.coeffs);
+        check("sk_li", self.sk_li, h, self.bls_pk, li_h)?;
+        check(
+            "sk_li_minus0",
+            self.sk_li - self.sk_li_minus0,
+            h,
+            self.bls_pk,
+            h * li.coeffs[0],
+        )?;
+        check(
+            "sk_li_x",
+            self.sk_li_x,
+            params.powers_of_h[1].into(),
+            self.sk_li_minus0,
+            h,
+        )?;
+
+        // sum_j r_j L_j(x) is the polynomial with evaluations r over the domain
+        let r = self.hint_coefficients(n);
+        let r_poly = make_domain::<E::ScalarField>(n)?.ifft(&r);
+        let r_h = E::G2::msm_unchecked(h_powers, &r_poly) - h * r[i];
+        let hints = E::G1::normalize_batch(&self.sk_li_lj_z);
+        let folded = E::G1::msm_unchecked(&hints, &r);
+        check(
+            "sk_li_lj_z",
+            folded,
+            compute_z_g2(params, n)?,
+            self.sk_li,
+            r_h,
+        )
+    }
+
+    /// Derives the coefficients of [`Self::verify_hints`] from a hash of the
+    /// key, so a key cannot be crafted against them.
+    fn hint_coefficients(&self, n: usize) -> Vec<E::ScalarField> {
+        let mut bytes = Vec::new();
+        self.serialize_compressed(&mut bytes)
+            .expect("serializing into a Vec cannot fail");
+        let seed = Blake2b512::new()
+            .chain_update(VERIFY_HINTS_DST)
+            .chain_update(&bytes)
+            .finalize();
+        (0..n)
+            .map(|j| {
+                let digest = Blake2b512::new()
+                    .chain_update(seed)
+                    .chain_update((j as u64).to_le_bytes())
+                    .finalize();
+                E::ScalarField::from_le_bytes_mod_order(&digest)
+            })
+            .collect()
+    }
+
+    /// Deserializes a compressed public key received from another party.
+    ///
+    /// Every group element is checked to be on the curve and in the
+    /// prime-order subgroup, and trailing bytes are rejected. Use this instead
+    /// of `deserialize_compressed_unchecked` for any untrusted input.
+    ///
+    /// # Errors
+    /// Returns `SteError::SerializationError` if the bytes are malformed or
+    /// contain an invalid point
+    pub fn deserialize_validated(bytes: &[u8]) -> Result<Self, SteError> {
+        deserialize_validated(bytes, "public key")
+    }
+
+    /// Like [`Self::deserialize_validated`], but trusts the points: the
+    /// subgroup checks on the key's `n + 4` group elements are skipped.
+    ///
+    /// Only use this for bytes this process wrote itself or read back from
+    /// storage it controls, such as keys it just generated. A point outside
+    /// the prime-order subgroup would pass, and such points let a malicious
+    /// party bias pairing checks and the aggregate key. Anything received from
+    /// another party must go through [`Self::deserialize_validated`].
+    ///
+    /// # Errors
+    /// Returns `SteError::SerializationError` if the bytes are malformed or
+    /// have trailing data
+    pub fn deserialize_trusted(bytes: &[u8]) -> Result<Self, SteError> {
+        deserialize_exact(bytes, "public key", Validate::No)
+    }
+}
+
+/// Domain separation tag for the coefficients of [`PublicKey::verify_hints`].
+const VERIFY_HINTS_DST: &[u8] = b"silent-threshold-encryption/verify-hints/v1";
+
+/// Version tag of the JSON interchange format emitted by [`PublicKey::to_json`].
+#[cfg(feature = "serde")]
+pub const PUBLIC_KEY_JSON_VERSION: u32 = 1;
+
+/// JSON interchange representation of a [`PublicKey`].
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct PublicKeyJson {
+    version: u32,
+    id: usize,
+    n: usize,
+    bls_pk: String,
+    sk_li: String,
+    sk_li_minus0: String,
+    sk_li_x: String,
+    sk_li_lj_z: Vec<String>,
+}
+
+#[cfg(feature = "serde")]
+impl<E: Pairing> PublicKey<E> {
+    /// Exports the public key in the versioned JSON interchange format.
+    ///
+    /// Group elements are hex-encoded compressed points:
+    ///
+    /// ```text
+    /// {
+    ///   "version": 1,
+    ///   "id": 2,
+    ///   "n": 4,
+    ///   "bls_pk": "<hex>",
+    ///   "sk_li": "<hex>",
+    ///   "sk_li_minus0": "<hex>",
+    ///   "sk_li_x": "<hex>",
+    ///   "sk_li_lj_z": ["<hex>", "<hex>", "<hex>", "<hex>"]
+    /// }
+    /// ```
+    ///
+    /// The committee size `n` is taken from the number of `sk_li_lj_z` hints.
+    ///
+    /// # Errors
+    /// Returns an error if a group element fails to serialize
+    pub fn to_json(&self) -> Result<String, SteError> {
+        let json = PublicKeyJson {
+            version: PUBLIC_KEY_JSON_VERSION,
+            id: self.id,
+            n: self.sk_li_lj_z.len(),
+            bls_pk: encode_point_hex(&self.bls_pk)?,
+            sk_li: encode_point_hex(&self.sk_li)?,
+            sk_li_minus0: encode_point_hex(&self.sk_li_minus0)?,
+            sk_li_x: encode_point_hex(&self.sk_li_x)?,
+            sk_li_lj_z: self
+                .sk_li_lj_z
+                .iter()
+                .map(encode_point_hex)
+                .collect::<Result<_, _>>()?,
+        };
+        serde_json::to_string_pretty(&json).map_err(|e| {
+            SteError::SerializationError(format!("Failed to encode public key JSON: {}", e))
+        })
+    }
+
+    /// Imports a public key from the versioned JSON interchange format.
+    ///
+    /// # Errors
+    /// Returns an error if the version is unsupported, `id >= n`, the number of
+    /// `sk_li_lj_z` entries differs from `n`, or any point is malformed
+    pub fn from_json(json: &str) -> Result<Self, SteError> {
+        let json: PublicKeyJson = serde_json::from_str(json).map_err(|e| {
+            SteError::SerializationError(format!("Failed to parse public key JSON: {}", e))
+        })?;
+
+        if json.version != PUBLIC_KEY_JSON_VERSION {
+            return Err(SteError::SerializationError(format!(
+                "Unsupported public key JSON version {} (expected {})",
+                json.version, PUBLIC_KEY_JSON_VERSION
+            )));
+        }
+        if json.id >= json.n {
+            return Err(SteError::ValidationError(format!(
+                "id ({}) must be < n ({})",
+                json.id, json.n
+            )));
+        }
+        if json.sk_li_lj_z.len() != json.n {
+            return Err(SteError::ValidationError(format!(
+                "sk_li_lj_z has {} entries, but n is {}",
+                json.sk_li_lj_z.len(),
+                json.n
+            )));
+        }
+
+        Ok(PublicKey {
+            id: json.id,
+            bls_pk: decode_point_hex(&json.bls_pk, "bls_pk")?,
+            sk_li: decode_point_hex(&json.sk_li, "sk_li")?,
+            sk_li_minus0: decode_point_hex(&json.sk_li_minus0, "sk_li_minus0")?,
+            sk_li_lj_z: json
+                .sk_li_lj_z
+                .iter()
+                .map(|hint| decode_point_hex(hint, "sk_li_lj_z"))
+                .collect::<Result<_, _>>()?,
+            sk_li_x: decode_point_hex(&json.sk_li_x, "sk_li_x")?,
+        })
+    }
+}
+
+/// Deserializes compressed bytes with full validation and no trailing data.
+///
+/// `Validate::Yes` runs `Valid::check` on every group element, rejecting
+/// points that are off the curve or outside the prime-order subgroup.
+pub(crate) fn deserialize_validated<T: CanonicalDeserialize>(
+    bytes: &[u8],
+    what: &str,
+) -> Result<T, SteError> {
+    deserialize_exact(bytes, what, Validate::Yes)
+}
+
+/// Deserializes compressed bytes with no trailing data, validating group
+/// elements only if `validate` is `Validate::Yes`.
+fn deserialize_exact<T: CanonicalDeserialize>(
+    bytes: &[u8],
+    what: &str,
+    validate: Validate,
+) -> Result<T, SteError> {
+    let mut reader = bytes;
+    let value = T::deserialize_with_mode(&mut reader, Compress::Yes, validate)
+        .map_err(|e| SteError::SerializationError(format!("Invalid {}: {}", what, e)))?;
+    if !reader.is_empty() {
+        return Err(SteError::SerializationError(format!(
+            "Invalid {}: {} trailing bytes",
+            what,
+            reader.len()
+        )));
+    }
+    Ok(value)
+}
+
+#[cfg(feature = "serde")]
+pub(crate) fn encode_point_hex<G: CanonicalSerialize>(point: &G) -> Result<String, SteError> {
+    let mut bytes = Vec::new();
+    point
+        .serialize_compressed(&mut bytes)
+        .map_err(|e| SteError::SerializationError(format!("Failed to serialize point: {}", e)))?;
+    Ok(hex::encode(bytes))
+}
+
+#[cfg(feature = "serde")]
+fn decode_point_hex<G: CanonicalDeserialize>(encoded: &str, field: &str) -> Result<G, SteError> {
+    let bytes = hex::decode(encoded).map_err(|e| {
+        SteError::SerializationError(format!("Invalid hex in field {}: {}", field, e))
+    })?;
+    G::deserialize_compressed(&bytes[..]).map_err(|e| {
+        SteError::SerializationError(format!("Invalid point in field {}: {}", field, e))
+    })
 }
 
 impl<E: Pairing> Zeroize for SecretKey<E> {
@@ -178,17 +810,60 @@ impl<E: Pairing> Zeroize for SecretKey<E> {
     }
 }
 
+/// Draws before key generation gives up on an RNG that only yields zero.
+const MAX_KEYGEN_ATTEMPTS: usize = 16;
+
 impl<E: Pairing> SecretKey<E> {
-    /// Creates a new secret key with a random scalar.
+    /// Creates a new secret key with a random non-zero scalar.
+    ///
+    /// A zero scalar is resampled, since a zero key silently breaks the scheme.
     ///
     /// # Arguments
     /// * `rng` - A random number generator
+    ///
+    /// # Panics
+    /// Panics if the RNG yields zero `MAX_KEYGEN_ATTEMPTS` times in a row, which
+    /// only a broken RNG does. Use [`Self::try_new`] to handle this as an error.
     pub fn new<R: RngCore>(rng: &mut R) -> Self {
+        let sk = Self::sample_nonzero(rng)
+            .expect("RNG produced only zero scalars; refusing to create a zero secret key");
         SecretKey {
-            sk: SensitiveScalar::new(E::ScalarField::rand(rng)),
+            sk: SensitiveScalar::new(sk),
         }
     }
 
+    /// Like [`Self::new`], but reports a broken RNG instead of panicking.
+    ///
+    /// Also performs a health check: a second scalar is drawn and key
+    /// generation fails if it equals the first.
+    ///
+    /// # Errors
+    /// Returns `SteError::RandomnessError` if the RNG only yields zero or
+    /// repeats itself
+    pub fn try_new<R: RngCore>(rng: &mut R) -> Result<Self, SteError> {
+        let sk = Self::sample_nonzero(rng).ok_or_else(|| {
+            SteError::RandomnessError(format!(
+                "RNG produced zero {} times in a row",
+                MAX_KEYGEN_ATTEMPTS
+            ))
+        })?;
+        let check = SensitiveScalar::new(E::ScalarField::rand(rng));
+        if *check.expose_secret() == sk {
+            return Err(SteError::RandomnessError(
+                "RNG returned the same scalar twice in a row".to_string(),
+            ));
+        }
+        Ok(SecretKey {
+            sk: SensitiveScalar::new(sk),
+        })
+    }
+
+    fn sample_nonzero<R: RngCore>(rng: &mut R) -> Option<E::ScalarField> {
+        (0..MAX_KEYGEN_ATTEMPTS)
+            .map(|_| E::ScalarField::rand(rng))
+            .find(|sk| !sk.is_zero())
+    }
+
     /// Nullifies the secret key by setting it to one.
     /// This is used for the dummy party (party 0) which always participates.
     pub fn nullify(&mut self) {
@@ -215,25 +890,15 @@ impl<E: Pairing> SecretKey<E> {
         n: usize,
     ) -> Result<PublicKey<E>, SteError> {
         // Validate inputs
+        validate_committee_size(n)?;
         if id >= n {
             return Err(SteError::ValidationError(format!(
                 "id ({}) must be < n ({})",
                 id, n
             )));
         }
-        if !n.is_power_of_two() {
-            return Err(SteError::InvalidParameter(format!(
-                "n must be a power of 2, got {}",
-                n
-            )));
-        }
 
-        let domain = Radix2EvaluationDomain::<E::ScalarField>::new(n).ok_or_else(|| {
-            SteError::DomainError(format!(
-                "Failed to create domain for n = {} (must be a power of 2)",
-                n
-            ))
-        })?;
+        let domain = make_domain::<E::ScalarField>(n)?;
 
         let li = lagrange_poly(n, id);
 
@@ -333,47 +998,150 @@ impl<E: Pairing> SecretKey<E> {
         })
     }
 
-    /// Computes a partial decryption of the ciphertext.
-    ///
-    /// This is essentially a BLS signature on `gamma_g2`.
-    ///
-    /// # Arguments
-    /// * `ct` - The ciphertext to partially decrypt
-    pub fn partial_decryption(&self, ct: &Ciphertext<E>) -> E::G2 {
-        ct.gamma_g2 * self.scalar()
-    }
-
-    /// Batch computes public keys for multiple secret keys in O(n) time per key.
+    /// Computes the public keys of this secret key for several party IDs.
     ///
-    /// This is more efficient than calling `lagrange_get_pk` n times because it leverages
-    /// parallel processing and reduces redundant computations.
+    /// Useful when one machine provisions a shard of IDs: the BLS public key is
+    /// computed once and the per-ID hints are computed in parallel. Each output
+    /// is identical to the corresponding `lagrange_get_pk` call.
     ///
     /// # Arguments
-    /// * `secret_keys` - Slice of secret keys to generate public keys for
+    /// * `ids` - The party IDs (each must be < n)
     /// * `params` - The preprocessed Lagrange powers
     /// * `n` - The number of parties
     ///
     /// # Errors
-    /// Returns an error if any party ID is invalid
-    ///
-    /// # Performance
-    /// - Time complexity: O(n²) total for n parties (same as calling `lagrange_get_pk` n times)
-    /// - However, this method uses parallel processing to speed up computation
-    /// - Memory efficient: processes in batches
-    pub fn batch_lagrange_get_pk(
-        secret_keys: &[SecretKey<E>],
+    /// Returns an error if any id >= n
+    pub fn lagrange_get_pk_range(
+        &self,
+        ids: &[usize],
         params: &LagrangePowers<E>,
         n: usize,
     ) -> Result<Vec<PublicKey<E>>, SteError> {
-        if secret_keys.len() != n {
+        if let Some(&id) = ids.iter().find(|&&id| id >= n) {
             return Err(SteError::ValidationError(format!(
-                "Number of secret keys ({}) must equal n ({})",
-                secret_keys.len(),
-                n
+                "id ({}) must be < n ({})",
+                id, n
             )));
         }
 
-        // Use parallel iterator to compute public keys for all parties
+        let sk_scalar = self.scalar();
+        let bls_pk = E::G1::generator() * sk_scalar;
+
+        Ok(ids
+            .par_iter()
+            .map(|&id| PublicKey {
+                id,
+                bls_pk,
+                sk_li: params.li[id] * sk_scalar,
+                sk_li_minus0: params.li_minus0[id] * sk_scalar,
+                sk_li_lj_z: params.li_lj_z[id][..n]
+                    .iter()
+                    .map(|li_lj_z| *li_lj_z * sk_scalar)
+                    .collect(),
+                sk_li_x: params.li_x[id] * sk_scalar,
+            })
+            .collect())
+    }
+
+    /// Refreshes the key in place for proactive security: samples a random
+    /// non-zero `delta`, replaces `sk` with `sk + delta`, and returns the
+    /// public key of `delta` for slot `id`.
+    ///
+    /// Every hint is linear in the secret key, so the committee updates the
+    /// party's public key by adding the returned delta key to it (see
+    /// [`AggregateKey::apply_refresh`]); `n` and `t` are unchanged. The
+    /// delta key is published like any other public key, and
+    /// `apply_refresh` checks its hints with [`PublicKey::verify_hints`]
+    /// before summing it in.
+    ///
+    /// Refresh is forward-secure: ciphertexts encrypted under the old
+    /// aggregate key cannot be decrypted with the new keys, so decrypt or
+    /// re-encrypt them before the old key is erased. The dummy party must
+    /// never refresh.
+    ///
+    /// # Errors
+    /// Returns an error if `id >= n`, in which case the key is unchanged
+    pub fn refresh<R: RngCore>(
+        &mut self,
+        id: usize,
+        params: &LagrangePowers<E>,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<PublicKey<E>, SteError> {
+        let delta = Self::new(rng);
+        let delta_pk = delta.lagrange_get_pk(id, params, n)?;
+        self.sk = SensitiveScalar::new(self.scalar() + delta.scalar());
+        Ok(delta_pk)
+    }
+
+    /// Computes a partial decryption of the ciphertext.
+    ///
+    /// This is essentially a BLS signature on `gamma_g2`.
+    ///
+    /// # Arguments
+    /// * `ct` - The ciphertext to partially decrypt
+    pub fn partial_decryption(&self, ct: &Ciphertext<E>) -> E::G2 {
+        self.partial_decryption_from_gamma(&ct.gamma_g2)
+    }
+
+    /// Computes a partial decryption from `gamma_g2` alone, the only part of
+    /// the ciphertext it depends on, so a coordinator can ship just that
+    /// point to responders that cannot hold the full ciphertext.
+    pub fn partial_decryption_from_gamma(&self, gamma_g2: &E::G2) -> E::G2 {
+        self.sign(gamma_g2)
+    }
+
+    /// Commits to this key's partial decryption of `ct` for a commit-reveal
+    /// round; reveal later with [`Self::partial_decryption`].
+    pub fn partial_decryption_commitment(&self, ct: &Ciphertext<E>) -> Commitment {
+        Commitment::new(ct, &self.partial_decryption(ct))
+    }
+
+    /// Computes a BLS signature `sk * message` on a G2 message.
+    ///
+    /// See [`aggregate_signature`](crate::decryption::aggregate_signature) for
+    /// combining signatures of several parties.
+    pub fn sign(&self, message: &E::G2) -> E::G2 {
+        *message * self.scalar()
+    }
+
+    /// Computes a partial decryption of an [`Encapsulation`], equal to
+    /// [`Self::partial_decryption`] of the ciphertext it was split from.
+    pub fn partial_decryption_encapsulated(&self, enc: &Encapsulation<E>) -> E::G2 {
+        enc.gamma_g2 * self.scalar()
+    }
+
+    /// Batch computes public keys for multiple secret keys in O(n) time per key.
+    ///
+    /// This is more efficient than calling `lagrange_get_pk` n times because it leverages
+    /// parallel processing and reduces redundant computations.
+    ///
+    /// # Arguments
+    /// * `secret_keys` - Slice of secret keys to generate public keys for
+    /// * `params` - The preprocessed Lagrange powers
+    /// * `n` - The number of parties
+    ///
+    /// # Errors
+    /// Returns an error if any party ID is invalid
+    ///
+    /// # Performance
+    /// - Time complexity: O(n²) total for n parties (same as calling `lagrange_get_pk` n times)
+    /// - However, this method uses parallel processing to speed up computation
+    /// - Memory efficient: processes in batches
+    pub fn batch_lagrange_get_pk(
+        secret_keys: &[SecretKey<E>],
+        params: &LagrangePowers<E>,
+        n: usize,
+    ) -> Result<Vec<PublicKey<E>>, SteError> {
+        if secret_keys.len() != n {
+            return Err(SteError::ValidationError(format!(
+                "Number of secret keys ({}) must equal n ({})",
+                secret_keys.len(),
+                n
+            )));
+        }
+
+        // Use parallel iterator to compute public keys for all parties
         secret_keys
             .par_iter()
             .enumerate()
@@ -381,8 +1149,23 @@ impl<E: Pairing> SecretKey<E> {
             .collect()
     }
 
+    /// Computes the partial decryptions of `ct` for several keys in parallel,
+    /// e.g. for an operator running many parties.
+    ///
+    /// The output is in the order of `secret_keys` and matches calling
+    /// [`Self::partial_decryption`] on each key.
+    pub fn batch_partial_decryption(
+        secret_keys: &[SecretKey<E>],
+        ct: &Ciphertext<E>,
+    ) -> Vec<E::G2> {
+        secret_keys
+            .par_iter()
+            .map(|sk| sk.partial_decryption(ct))
+            .collect()
+    }
+
     fn scalar(&self) -> E::ScalarField {
-        self.sk.expose_secret().clone()
+        *self.sk.expose_secret()
     }
 }
 
@@ -394,28 +1177,17 @@ impl<E: Pairing> AggregateKey<E> {
     /// * `params` - The KZG parameters (powers of tau)
     ///
     /// # Errors
-    /// Returns an error if pk is empty or if n > params length
+    /// Returns an error if fewer than `MIN_COMMITTEE_SIZE` keys are given, if
+    /// the params' degree is below [`required_degree`], or if their G1 and G2
+    /// powers differ in length
     pub fn new(pk: Vec<PublicKey<E>>, params: &PowersOfTau<E>) -> Result<Self, SteError> {
         let n = pk.len();
-        if n == 0 {
-            return Err(SteError::ValidationError("pk cannot be empty".to_string()));
-        }
-        if n >= params.powers_of_h.len() {
-            return Err(SteError::ValidationError(format!(
-                "n ({}) requires at least n + 1 = {} powers of h, but params only have {}",
-                n,
-                n + 1,
-                params.powers_of_h.len()
-            )));
-        }
-        if params.powers_of_g.is_empty() {
-            return Err(SteError::ValidationError(
-                "KZG parameters must contain at least one G power".to_string(),
-            ));
-        }
+        validate_committee_size(n)?;
+        Self::validate_party_ids(&pk)?;
+        check_params_degree(params, n)?;
 
         let h_minus1 = params.powers_of_h[0] * (-E::ScalarField::one());
-        let z_g2 = params.powers_of_h[n] + h_minus1;
+        let z_g2 = compute_z_g2(params, n)?;
 
         // gather sk_li from all public keys
         let mut ask = E::G1::zero();
@@ -441,15 +1213,519 @@ impl<E: Pairing> AggregateKey<E> {
             e_gh: E::pairing(params.powers_of_g[0], params.powers_of_h[0]),
         })
     }
+
+    /// Checks that the key is what [`Self::new`] would build from its public
+    /// keys and `params`, e.g. before trusting a key received over the network.
+    ///
+    /// `z_g2`, `h_minus1` and `e_gh` must match `params`, and `ask` and
+    /// `agg_sk_li_lj_z` must be the sums of the parties' hints. The public keys
+    /// themselves are not checked against the Lagrange powers.
+    ///
+    /// # Errors
+    /// Returns a `ValidationError` naming the first field that does not match
+    pub fn verify(&self, params: &PowersOfTau<E>) -> Result<(), SteError> {
+        let n = self.pk.len();
+        validate_committee_size(n)?;
+        Self::validate_party_ids(&self.pk)?;
+        check_params_degree(params, n)?;
+
+        let h_minus1 = params.powers_of_h[0] * (-E::ScalarField::one());
+        if self.h_minus1 != h_minus1 {
+            return Err(SteError::ValidationError(
+                "h_minus1 does not match the parameters".to_string(),
+            ));
+        }
+        if self.z_g2 != compute_z_g2(params, n)? {
+            return Err(SteError::ValidationError(
+                "z_g2 does not match the parameters".to_string(),
+            ));
+        }
+        if self.e_gh != E::pairing(params.powers_of_g[0], params.powers_of_h[0]) {
+            return Err(SteError::ValidationError(
+                "e_gh does not match the parameters".to_string(),
+            ));
+        }
+
+        let ask: E::G1 = self.pk.iter().map(|pki| pki.sk_li).sum();
+        if self.ask != ask {
+            return Err(SteError::ValidationError(
+                "ask is not the sum of the parties' sk_li".to_string(),
+            ));
+        }
+
+        if self.agg_sk_li_lj_z.len() != n {
+            return Err(SteError::ValidationError(format!(
+                "agg_sk_li_lj_z has {} entries, expected {}",
+                self.agg_sk_li_lj_z.len(),
+                n
+            )));
+        }
+        for (i, agg) in self.agg_sk_li_lj_z.iter().enumerate() {
+            let mut expected = E::G1::zero();
+            for pkj in self.pk.iter() {
+                let hint = pkj.sk_li_lj_z.get(i).ok_or_else(|| {
+                    SteError::ValidationError(format!(
+                        "party {} has {} hints, expected {}",
+                        pkj.id,
+                        pkj.sk_li_lj_z.len(),
+                        n
+                    ))
+                })?;
+                expected += hint;
+            }
+            if *agg != expected {
+                return Err(SteError::ValidationError(format!(
+                    "agg_sk_li_lj_z[{}] is not the sum of the parties' hints",
+                    i
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that `pk[i].id == i` for every position.
+    ///
+    /// # Errors
+    /// Returns a `ValidationError` listing duplicate, out-of-range and misplaced IDs
+    fn validate_party_ids(pk: &[PublicKey<E>]) -> Result<(), SteError> {
+        let n = pk.len();
+        let mut seen = vec![false; n];
+        let mut duplicates = Vec::new();
+        let mut out_of_range = Vec::new();
+        let mut misplaced = Vec::new();
+
+        for (position, pki) in pk.iter().enumerate() {
+            if pki.id >= n {
+                out_of_range.push(pki.id);
+                continue;
+            }
+            if seen[pki.id] {
+                if !duplicates.contains(&pki.id) {
+                    duplicates.push(pki.id);
+                }
+            } else {
+                seen[pki.id] = true;
+            }
+            if pki.id != position {
+                misplaced.push((position, pki.id));
+            }
+        }
+
+        if duplicates.is_empty() && out_of_range.is_empty() && misplaced.is_empty() {
+            return Ok(());
+        }
+
+        let mut problems = Vec::new();
+        if !duplicates.is_empty() {
+            problems.push(format!("duplicate ids {:?}", duplicates));
+        }
+        if !out_of_range.is_empty() {
+            problems.push(format!(
+                "ids out of range for n = {}: {:?}",
+                n, out_of_range
+            ));
+        }
+        if !misplaced.is_empty() {
+            problems.push(format!("(position, id) mismatches {:?}", misplaced));
+        }
+        Err(SteError::ValidationError(format!(
+            "invalid party ids: {}",
+            problems.join("; ")
+        )))
+    }
+
+    /// Swaps one party's public key for a new one, e.g. after a key rotation.
+    ///
+    /// Only the replaced party's contribution to `ask` and `agg_sk_li_lj_z` is
+    /// subtracted and the new one added, which takes O(n) group operations
+    /// instead of the O(n²) of rebuilding with [`Self::new`]. The result is
+    /// identical to a fresh build.
+    ///
+    /// # Errors
+    /// Returns a `ValidationError` if `old` is not the key currently held for
+    /// its party, or if `new` is for a different party or has the wrong number
+    /// of hints
+    pub fn replace_public_key(
+        &mut self,
+        old: &PublicKey<E>,
+        new: PublicKey<E>,
+    ) -> Result<(), SteError> {
+        let n = self.pk.len();
+        let id = old.id;
+        let current = self.pk.get(id).ok_or_else(|| {
+            SteError::ValidationError(format!("party id {} out of range for n = {}", id, n))
+        })?;
+        if current.bls_pk != old.bls_pk
+            || current.sk_li != old.sk_li
+            || current.sk_li_lj_z != old.sk_li_lj_z
+        {
+            return Err(SteError::ValidationError(format!(
+                "public key does not match the one held for party {}",
+                id
+            )));
+        }
+        if new.id != id {
+            return Err(SteError::ValidationError(format!(
+                "replacement key is for party {}, expected {}",
+                new.id, id
+            )));
+        }
+        if new.sk_li_lj_z.len() != n {
+            return Err(SteError::ValidationError(format!(
+                "replacement key has {} hints, expected {}",
+                new.sk_li_lj_z.len(),
+                n
+            )));
+        }
+
+        self.ask += new.sk_li - old.sk_li;
+        for ((agg, old_hint), new_hint) in self
+            .agg_sk_li_lj_z
+            .iter_mut()
+            .zip(&old.sk_li_lj_z)
+            .zip(&new.sk_li_lj_z)
+        {
+            *agg += *new_hint - old_hint;
+        }
+        self.pk[id] = new;
+        Ok(())
+    }
+
+    /// Applies a party's key refresh: adds the delta key returned by
+    /// [`SecretKey::refresh`] to the party's public key, in O(n) like
+    /// [`Self::replace_public_key`].
+    ///
+    /// The delta's hints are checked against its `bls_pk` with
+    /// [`PublicKey::verify_hints`] before anything is summed, so a party
+    /// cannot corrupt the aggregate key with inconsistent hints.
+    ///
+    /// # Errors
+    /// Returns a `ValidationError` if the delta is for an unknown party, has
+    /// the wrong number of hints, is the identity or fails
+    /// [`PublicKey::verify_hints`], or if the party is a dummy (its public key
+    /// is the generator)
+    pub fn apply_refresh(
+        &mut self,
+        delta: &PublicKey<E>,
+        params: &PowersOfTau<E>,
+    ) -> Result<(), SteError> {
+        let n = self.pk.len();
+        let old = self.pk.get(delta.id).cloned().ok_or_else(|| {
+            SteError::ValidationError(format!("party id {} out of range for n = {}", delta.id, n))
+        })?;
+        if old.bls_pk == E::G1::generator() {
+            return Err(SteError::ValidationError(format!(
+                "party {} is a dummy party and cannot refresh its key",
+                delta.id
+            )));
+        }
+        if delta.sk_li_lj_z.len() != n {
+            return Err(SteError::ValidationError(format!(
+                "refresh for party {} has {} hints, expected {}",
+                delta.id,
+                delta.sk_li_lj_z.len(),
+                n
+            )));
+        }
+        if delta.bls_pk.is_zero() {
+            return Err(SteError::ValidationError(format!(
+                "refresh for party {} has a zero delta",
+                delta.id
+            )));
+        }
+        delta.verify_hints(params, n)?;
+
+        let new = PublicKey {
+            id: old.id,
+            bls_pk: old.bls_pk + delta.bls_pk,
+            sk_li: old.sk_li + delta.sk_li,
+            sk_li_minus0: old.sk_li_minus0 + delta.sk_li_minus0,
+            sk_li_lj_z: old
+                .sk_li_lj_z
+                .iter()
+                .zip(&delta.sk_li_lj_z)
+                .map(|(a, b)| *a + b)
+                .collect(),
+            sk_li_x: old.sk_li_x + delta.sk_li_x,
+        };
+        self.replace_public_key(&old, new)
+    }
+
+    /// The committee's BLS public key for the participant set `selector`.
+    ///
+    /// This is the `apk` that [`agg_dec`](crate::decryption::agg_dec) derives
+    /// internally. The selected parties' partial signatures, combined by
+    /// [`aggregate_signature`](crate::decryption::aggregate_signature), verify
+    /// under it with any standard BLS verifier.
+    ///
+    /// # Errors
+    /// Returns an error if `selector.len() != n` or party 0 is not selected
+    pub fn committee_bls_key(&self, selector: &[bool]) -> Result<E::G1, SteError> {
+        crate::decryption::compute_apk(self, selector)
+    }
+
+    /// Precomputes the decryption work that depends only on `selector`, for
+    /// deployments that always decrypt with the same parties.
+    ///
+    /// Use the result with [`agg_dec_with`](crate::decryption::agg_dec_with)
+    /// for any ciphertext under this key whose threshold the selection meets.
+    ///
+    /// # Errors
+    /// Returns an error if `selector.len() != n`, party 0 is not selected, a
+    /// padding slot is selected, or a commitment fails
+    pub fn fix_selector(
+        &self,
+        selector: &[bool],
+        params: &PowersOfTau<E>,
+    ) -> Result<crate::decryption::FixedSelectorKey<E>, SteError> {
+        crate::decryption::FixedSelectorKey::new(self, selector, params)
+    }
+
+    /// Serializes the key as chunks of `chunk_size` bytes, plus the manifest
+    /// a receiver needs to verify them; see [`crate::transfer`].
+    ///
+    /// # Errors
+    /// Returns any error from [`crate::transfer::split`]
+    pub fn serialize_chunked(
+        &self,
+        chunk_size: usize,
+    ) -> Result<(crate::transfer::ChunkManifest, Vec<Vec<u8>>), SteError> {
+        crate::transfer::split(self, chunk_size)
+    }
+
+    /// Rebuilds a key from the chunks received so far, in any order, checking
+    /// every chunk against `manifest`.
+    ///
+    /// # Errors
+    /// Returns any error from [`crate::transfer::reassemble`], including when
+    /// chunks are still missing
+    pub fn deserialize_resume(
+        manifest: &crate::transfer::ChunkManifest,
+        chunks: impl IntoIterator<Item = (usize, Vec<u8>)>,
+    ) -> Result<Self, SteError> {
+        crate::transfer::reassemble(manifest, chunks, "aggregate key")
+    }
+
+    /// Lists up to `max` distinct minimal quorums for threshold `t`: sets of
+    /// `t + 1` party ids that include the dummy party 0, in lexicographic order.
+    ///
+    /// There are `C(n - 1, t)` such quorums, so `max` caps the work. Returns
+    /// nothing if `t` is not in `[1, n - 1]`.
+    pub fn enumerate_minimal_quorums(&self, t: usize, max: usize) -> Vec<Vec<usize>> {
+        let n = self.pk.len();
+        let mut quorums = Vec::new();
+        if t == 0 || t >= n {
+            return quorums;
+        }
+
+        // Choose t of the real parties 1..n, advancing like an odometer
+        let mut chosen: Vec<usize> = (1..=t).collect();
+        while quorums.len() < max {
+            let mut quorum = Vec::with_capacity(t + 1);
+            quorum.push(0);
+            quorum.extend_from_slice(&chosen);
+            quorums.push(quorum);
+
+            let Some(i) = (0..t).rev().find(|&i| chosen[i] < n - t + i) else {
+                break;
+            };
+            chosen[i] += 1;
+            for j in i + 1..t {
+                chosen[j] = chosen[j - 1] + 1;
+            }
+        }
+        quorums
+    }
+
+    /// The part of the aggregate key needed to encrypt, without the per-party
+    /// public keys and hints.
+    pub fn encrypt_only(&self) -> EncryptOnlyKey<E> {
+        EncryptOnlyKey {
+            n: self.pk.len(),
+            ask: self.ask,
+            z_g2: self.z_g2,
+            h_minus1: self.h_minus1,
+            e_gh: self.e_gh,
+        }
+    }
+
+    /// The part of the aggregate key that
+    /// [`agg_dec`](crate::decryption::agg_dec) reads, without the per-party
+    /// `sk_li_lj_z` hints.
+    pub fn decrypt_only(&self) -> DecryptOnlyKey<E> {
+        DecryptOnlyKey {
+            bls_pk: self.pk.iter().map(|pk| pk.bls_pk).collect(),
+            sk_li_x: self.pk.iter().map(|pk| pk.sk_li_x).collect(),
+            sk_li_minus0: self.pk.iter().map(|pk| pk.sk_li_minus0).collect(),
+            agg_sk_li_lj_z: self.agg_sk_li_lj_z.clone(),
+        }
+    }
+
+    /// Serializes [`Self::encrypt_only`] in compressed form.
+    ///
+    /// # Errors
+    /// Returns an error if serialization fails
+    pub fn serialize_encrypt_only(&self) -> Result<Vec<u8>, SteError> {
+        let mut bytes = Vec::new();
+        self.encrypt_only()
+            .serialize_compressed(&mut bytes)
+            .map_err(|e| {
+                SteError::SerializationError(format!("Failed to serialize encrypt-only key: {}", e))
+            })?;
+        Ok(bytes)
+    }
+}
+
+/// The constant-size part of an [`AggregateKey`] that [`encrypt`] uses.
+///
+/// A publisher that only encrypts can hold this instead of the full aggregate
+/// key, whose per-party public keys grow quadratically in `n`. Ciphertexts are
+/// decrypted with the full key as usual.
+///
+/// [`encrypt`]: crate::encryption::encrypt
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EncryptOnlyKey<E: Pairing> {
+    /// Committee size of the aggregate key this was taken from
+    pub n: usize,
+    /// Sum of the parties' `sk_li` hints
+    pub ask: E::G1,
+    /// Cached `h^{tau^n} - h`
+    pub z_g2: E::G2,
+    /// Cached `-h`
+    pub h_minus1: E::G2,
+    /// Cached `e(g, h)`, so encryption needs no pairing
+    pub e_gh: PairingOutput<E>,
+}
+
+impl<E: Pairing> EncryptOnlyKey<E> {
+    /// Loads a key written by [`AggregateKey::serialize_encrypt_only`].
+    ///
+    /// # Errors
+    /// Returns an error if the bytes are malformed or the committee size is invalid
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, SteError> {
+        let key = Self::deserialize_compressed(bytes).map_err(|e| {
+            SteError::SerializationError(format!("Failed to deserialize encrypt-only key: {}", e))
+        })?;
+        validate_committee_size(key.n)?;
+        Ok(key)
+    }
+}
+
+/// Keys that [`encrypt`](crate::encryption::encrypt) accepts.
+pub trait EncryptionKey<E: Pairing> {
+    fn encryption_key(&self) -> EncryptOnlyKey<E>;
+}
+
+impl<E: Pairing> EncryptionKey<E> for AggregateKey<E> {
+    fn encryption_key(&self) -> EncryptOnlyKey<E> {
+        self.encrypt_only()
+    }
+}
+
+impl<E: Pairing> EncryptionKey<E> for EncryptOnlyKey<E> {
+    fn encryption_key(&self) -> EncryptOnlyKey<E> {
+        *self
+    }
+}
+
+/// The linear-size part of an [`AggregateKey`] that
+/// [`agg_dec`](crate::decryption::agg_dec) reads.
+///
+/// The full key holds every party's `sk_li_lj_z` hints, which grow
+/// quadratically in `n`. A decryption coordinator only needs the four vectors
+/// below and can hold this instead, decrypting with
+/// [`agg_dec_with_decrypt_key`](crate::decryption::agg_dec_with_decrypt_key).
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DecryptOnlyKey<E: Pairing> {
+    /// Each party's `bls_pk`
+    pub bls_pk: Vec<E::G1>,
+    /// Each party's `sk_li_x` hint
+    pub sk_li_x: Vec<E::G1>,
+    /// Each party's `sk_li_minus0` hint
+    pub sk_li_minus0: Vec<E::G1>,
+    /// Same as [`AggregateKey::agg_sk_li_lj_z`]
+    pub agg_sk_li_lj_z: Vec<E::G1>,
+}
+
+impl<E: Pairing> DecryptOnlyKey<E> {
+    /// Checks that every vector has one entry per party of a valid committee.
+    ///
+    /// # Errors
+    /// Returns an error if the committee size is invalid or the lengths differ
+    pub fn validate(&self) -> Result<(), SteError> {
+        let n = self.bls_pk.len();
+        validate_committee_size(n)?;
+        for (name, len) in [
+            ("sk_li_x", self.sk_li_x.len()),
+            ("sk_li_minus0", self.sk_li_minus0.len()),
+            ("agg_sk_li_lj_z", self.agg_sk_li_lj_z.len()),
+        ] {
+            if len != n {
+                return Err(SteError::ValidationError(format!(
+                    "{} has {} entries, expected {}",
+                    name, len, n
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Read access to the hints that decryption uses, shared by [`AggregateKey`]
+/// and [`DecryptOnlyKey`].
+pub(crate) trait DecryptionHints<E: Pairing> {
+    fn n(&self) -> usize;
+    fn bls_pk(&self, i: usize) -> E::G1;
+    fn sk_li_x(&self, i: usize) -> E::G1;
+    fn sk_li_minus0(&self, i: usize) -> E::G1;
+    fn agg_sk_li_lj_z(&self, i: usize) -> E::G1;
+}
+
+impl<E: Pairing> DecryptionHints<E> for AggregateKey<E> {
+    fn n(&self) -> usize {
+        self.pk.len()
+    }
+    fn bls_pk(&self, i: usize) -> E::G1 {
+        self.pk[i].bls_pk
+    }
+    fn sk_li_x(&self, i: usize) -> E::G1 {
+        self.pk[i].sk_li_x
+    }
+    fn sk_li_minus0(&self, i: usize) -> E::G1 {
+        self.pk[i].sk_li_minus0
+    }
+    fn agg_sk_li_lj_z(&self, i: usize) -> E::G1 {
+        self.agg_sk_li_lj_z[i]
+    }
+}
+
+impl<E: Pairing> DecryptionHints<E> for DecryptOnlyKey<E> {
+    fn n(&self) -> usize {
+        self.bls_pk.len()
+    }
+    fn bls_pk(&self, i: usize) -> E::G1 {
+        self.bls_pk[i]
+    }
+    fn sk_li_x(&self, i: usize) -> E::G1 {
+        self.sk_li_x[i]
+    }
+    fn sk_li_minus0(&self, i: usize) -> E::G1 {
+        self.sk_li_minus0[i]
+    }
+    fn agg_sk_li_lj_z(&self, i: usize) -> E::G1 {
+        self.agg_sk_li_lj_z[i]
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::SteError;
+    use ark_poly::Polynomial;
 
     type E = ark_bls12_381::Bls12_381;
     type Fr = <E as Pairing>::ScalarField;
+    type G1 = <E as Pairing>::G1;
     type UniPoly381 = DensePolynomial<<E as Pairing>::ScalarField>;
 
     #[test]
@@ -483,16 +1759,169 @@ mod tests {
     }
 
     #[test]
-    fn test_aggregate_key_rejects_insufficient_params() {
+    fn test_selfcheck() {
         let mut rng = ark_std::test_rng();
-        let n = 4;
+        let n = 8;
         let tau = Fr::rand(&mut rng);
         let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
         let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+        selfcheck(&params, &lagrange_params, n).unwrap();
 
-        let sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::<E>::new(&mut rng)).collect();
-        let pk: Vec<PublicKey<E>> = sk
-            .iter()
+        let other = LagrangePowers::<E>::new(Fr::rand(&mut rng), n).unwrap();
+        let err = selfcheck(&params, &other, n).unwrap_err();
+        assert!(
+            matches!(err, SteError::ValidationError(ref msg) if msg.contains("same tau")),
+            "{err:?}"
+        );
+
+        let small = LagrangePowers::<E>::new(tau, n / 2).unwrap();
+        assert!(selfcheck(&params, &small, n).is_err());
+        assert!(selfcheck(&params, &lagrange_params, 2 * n).is_err());
+    }
+
+    #[test]
+    fn test_lagrange_powers_match_per_element_multiplication() {
+        let mut rng = ark_std::test_rng();
+        let n = 16;
+        let tau = Fr::rand(&mut rng);
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+        let g = G1::generator();
+        let li_evals: Vec<Fr> = (0..n).map(|i| lagrange_poly(n, i).evaluate(&tau)).collect();
+        let z_eval_inv = (tau.pow([n as u64]) - Fr::one()).inverse().unwrap();
+        for i in 0..n {
+            let li_minus0 = li_evals[i] - lagrange_poly::<Fr>(n, i).coeffs[0];
+            assert_eq!(lagrange_params.li[i], g * li_evals[i]);
+            assert_eq!(lagrange_params.li_minus0[i], g * li_minus0);
+            assert_eq!(
+                lagrange_params.li_x[i],
+                g * (li_minus0 * tau.inverse().unwrap())
+            );
+            for j in 0..n {
+                let expected = if i == j {
+                    g * ((li_evals[i] * li_evals[i] - li_evals[i]) * z_eval_inv)
+                } else {
+                    g * (li_evals[i] * li_evals[j] * z_eval_inv)
+                };
+                assert_eq!(lagrange_params.li_lj_z[i][j], expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_lagrange_powers_li_lj_z_divides_by_z() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+        // e(g^(L_i L_j / Z), h^Z) = e(g^L_i, h^L_j) for i != j
+        let h = params.powers_of_h[0];
+        let z_g2 = params.powers_of_h[n] - h;
+        for i in 0..n {
+            for j in (0..n).filter(|&j| j != i) {
+                let lj_h = h * lagrange_poly::<Fr>(n, j).evaluate(&tau);
+                assert_eq!(
+                    E::pairing(lagrange_params.li_lj_z[i][j], z_g2),
+                    E::pairing(lagrange_params.li[i], lj_h)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_lagrange_powers_from_powers_of_tau() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+
+        let expected = LagrangePowers::<E>::new(tau, n).unwrap();
+        let derived = LagrangePowers::<E>::from_powers_of_tau(&params, n).unwrap();
+        assert_eq!(derived.li, expected.li);
+        assert_eq!(derived.li_minus0, expected.li_minus0);
+        assert_eq!(derived.li_x, expected.li_x);
+        assert_eq!(derived.li_lj_z, expected.li_lj_z);
+    }
+
+    /// Yields `zeros` zero bytes, then a counter.
+    struct StubRng {
+        zeros: usize,
+        counter: u8,
+    }
+
+    impl RngCore for StubRng {
+        fn next_u32(&mut self) -> u32 {
+            let mut bytes = [0u8; 4];
+            self.fill_bytes(&mut bytes);
+            u32::from_le_bytes(bytes)
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            let mut bytes = [0u8; 8];
+            self.fill_bytes(&mut bytes);
+            u64::from_le_bytes(bytes)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            for byte in dest {
+                if self.zeros > 0 {
+                    self.zeros -= 1;
+                    *byte = 0;
+                } else {
+                    self.counter = self.counter.wrapping_add(1);
+                    *byte = self.counter;
+                }
+            }
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), ark_std::rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_secret_key_rejects_zero_rng() {
+        let mut zero_rng = StubRng {
+            zeros: usize::MAX,
+            counter: 0,
+        };
+        let err = SecretKey::<E>::try_new(&mut zero_rng).unwrap_err();
+        assert!(matches!(err, SteError::RandomnessError(_)), "{err:?}");
+
+        // A single zero draw is resampled
+        let mut rng = StubRng {
+            zeros: 32,
+            counter: 0,
+        };
+        let sk = SecretKey::<E>::new(&mut rng);
+        assert!(!sk.scalar().is_zero());
+
+        let mut rng = StubRng {
+            zeros: 32,
+            counter: 0,
+        };
+        assert!(SecretKey::<E>::try_new(&mut rng).is_ok());
+
+        // nullify still sets the key to one
+        let mut sk = SecretKey::<E>::new(&mut ark_std::test_rng());
+        sk.nullify();
+        assert_eq!(sk.scalar(), Fr::one());
+    }
+
+    #[test]
+    fn test_aggregate_key_rejects_insufficient_params() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+        let sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::<E>::new(&mut rng)).collect();
+        let pk: Vec<PublicKey<E>> = sk
+            .iter()
             .enumerate()
             .map(|(i, sk)| sk.lagrange_get_pk(i, &lagrange_params, n).unwrap())
             .collect();
@@ -502,7 +1931,529 @@ mod tests {
         let err = AggregateKey::<E>::new(pk, &short_params)
             .expect_err("expected aggregate key creation to fail with short params");
         assert!(
-            matches!(err, SteError::ValidationError(ref msg) if msg.contains("n + 1")),
+            matches!(err, SteError::ValidationError(ref msg) if msg.contains("< required n")),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn test_aggregate_key_rejects_mismatched_params() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(2 * n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+        let pk: Vec<PublicKey<E>> = (0..n)
+            .map(|i| {
+                SecretKey::<E>::new(&mut rng)
+                    .lagrange_get_pk(i, &lagrange_params, n)
+                    .unwrap()
+            })
+            .collect();
+
+        // Still long enough for n, but G1 and G2 no longer line up
+        let mut bad_params = params.clone();
+        bad_params.powers_of_g.truncate(n + 2);
+        let err = AggregateKey::<E>::new(pk, &bad_params)
+            .expect_err("expected aggregation to reject mismatched params");
+        assert!(
+            matches!(err, SteError::ValidationError(ref msg) if msg.contains("G1 powers")),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn test_aggregate_key_rejects_duplicate_ids() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+        let mut pk: Vec<PublicKey<E>> = (0..n)
+            .map(|i| {
+                SecretKey::<E>::new(&mut rng)
+                    .lagrange_get_pk(i, &lagrange_params, n)
+                    .unwrap()
+            })
+            .collect();
+        pk[3] = SecretKey::<E>::new(&mut rng)
+            .lagrange_get_pk(2, &lagrange_params, n)
+            .unwrap();
+
+        let err = AggregateKey::<E>::new(pk.clone(), &params)
+            .expect_err("expected aggregation to reject two keys with id 2");
+        assert!(
+            matches!(err, SteError::ValidationError(ref msg)
+                if msg.contains("duplicate ids [2]") && msg.contains("(3, 2)")),
+            "unexpected error: {err:?}"
+        );
+
+        pk[3].id = n + 1;
+        let err = AggregateKey::<E>::new(pk, &params)
+            .expect_err("expected aggregation to reject an out-of-range id");
+        assert!(
+            matches!(err, SteError::ValidationError(ref msg) if msg.contains("out of range")),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn test_rejects_oversized_committees_before_allocating() {
+        let tau = Fr::rand(&mut ark_std::test_rng());
+        let huge = 1 << 20;
+
+        let err = LagrangePowers::<E>::new(tau, huge)
+            .expect_err("expected Lagrange setup to reject an oversized committee");
+        assert!(
+            matches!(err, SteError::InvalidParameter(ref msg) if msg.contains("exceeds the maximum")),
+            "unexpected error: {err:?}"
+        );
+
+        // A lower limit rejects sizes the default accepts
+        let limit = CommitteeSizeLimit::default().with_max(4);
+        assert!(LagrangePowers::<E>::new_with_limit(tau, 8, limit).is_err());
+        assert!(LagrangePowers::<E>::new_with_limit(tau, 4, limit).is_ok());
+    }
+
+    #[test]
+    fn test_rejects_committees_smaller_than_two() {
+        let mut rng = ark_std::test_rng();
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(4, tau).unwrap();
+
+        for n in [0, 1] {
+            let err = LagrangePowers::<E>::new(tau, n)
+                .expect_err("expected Lagrange setup to reject a degenerate committee");
+            assert!(
+                matches!(err, SteError::InvalidParameter(ref msg) if msg.contains("at least 2")),
+                "unexpected error for n = {n}: {err:?}"
+            );
+
+            let err = SecretKey::<E>::new(&mut rng)
+                .get_pk(0, &params, n)
+                .expect_err("expected get_pk to reject a degenerate committee");
+            assert!(
+                matches!(err, SteError::InvalidParameter(ref msg) if msg.contains("at least 2")),
+                "unexpected error for n = {n}: {err:?}"
+            );
+        }
+
+        let err = AggregateKey::<E>::new(vec![], &params)
+            .expect_err("expected aggregation to reject an empty committee");
+        assert!(
+            matches!(err, SteError::InvalidParameter(ref msg) if msg.contains("got 0")),
+            "unexpected error: {err:?}"
+        );
+
+        let lagrange_params = LagrangePowers::<E>::new(tau, 2).unwrap();
+        let pk = SecretKey::<E>::new(&mut rng)
+            .lagrange_get_pk(0, &lagrange_params, 2)
+            .unwrap();
+        let err = AggregateKey::<E>::new(vec![pk], &params)
+            .expect_err("expected aggregation to reject a single-party committee");
+        assert!(
+            matches!(err, SteError::InvalidParameter(ref msg) if msg.contains("got 1")),
+            "unexpected error: {err:?}"
+        );
+    }
+
+    #[test]
+    fn test_replace_public_key() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+        let mut pk: Vec<PublicKey<E>> = (0..n)
+            .map(|i| {
+                SecretKey::<E>::new(&mut rng)
+                    .lagrange_get_pk(i, &lagrange_params, n)
+                    .unwrap()
+            })
+            .collect();
+        let mut agg_key = AggregateKey::<E>::new(pk.clone(), &params).unwrap();
+
+        let old = pk[3].clone();
+        let new = SecretKey::<E>::new(&mut rng)
+            .lagrange_get_pk(3, &lagrange_params, n)
+            .unwrap();
+        agg_key.replace_public_key(&old, new.clone()).unwrap();
+
+        pk[3] = new.clone();
+        let expected = AggregateKey::<E>::new(pk, &params).unwrap();
+        assert_eq!(agg_key.ask, expected.ask);
+        assert_eq!(agg_key.agg_sk_li_lj_z, expected.agg_sk_li_lj_z);
+        assert_eq!(agg_key.pk[3].bls_pk, expected.pk[3].bls_pk);
+
+        // The old key is no longer held, and ids must match
+        assert!(agg_key.replace_public_key(&old, new.clone()).is_err());
+        let mut wrong_id = new.clone();
+        wrong_id.id = 4;
+        assert!(agg_key.replace_public_key(&new, wrong_id).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_key_rejects_params_one_degree_short() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let tau = Fr::rand(&mut rng);
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+        let pk: Vec<PublicKey<E>> = (0..n)
+            .map(|i| {
+                SecretKey::<E>::new(&mut rng)
+                    .lagrange_get_pk(i, &lagrange_params, n)
+                    .unwrap()
+            })
+            .collect();
+
+        let short = KZG10::<E, UniPoly381>::setup(required_degree(n) - 1, tau).unwrap();
+        match AggregateKey::<E>::new(pk.clone(), &short) {
+            Err(SteError::ValidationError(msg)) => {
+                assert_eq!(msg, "params degree 3 < required n = 4")
+            }
+            other => panic!("expected a degree error, got {:?}", other.map(|_| ())),
+        }
+
+        let exact = KZG10::<E, UniPoly381>::setup(required_degree(n), tau).unwrap();
+        assert!(AggregateKey::<E>::new(pk, &exact).is_ok());
+    }
+
+    #[test]
+    fn test_compute_z_g2_matches_aggregate_key() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let pk = (0..n)
+            .map(|i| SecretKey::<E>::new(&mut rng).get_pk(i, &params, n).unwrap())
+            .collect();
+        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+
+        assert_eq!(compute_z_g2(&params, n).unwrap(), agg_key.z_g2);
+        assert_eq!(
+            compute_z_g2(&params, 3).unwrap(),
+            <E as Pairing>::G2::generator() * (tau.pow([3]) - Fr::one())
+        );
+        assert!(compute_z_g2(&params, n + 1).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_key_verify() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+        let pk: Vec<PublicKey<E>> = (0..n)
+            .map(|i| {
+                SecretKey::<E>::new(&mut rng)
+                    .lagrange_get_pk(i, &lagrange_params, n)
+                    .unwrap()
+            })
+            .collect();
+        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+        agg_key.verify(&params).unwrap();
+
+        let mut tampered = agg_key.clone();
+        tampered.ask += G1::generator();
+        assert!(matches!(
+            tampered.verify(&params),
+            Err(SteError::ValidationError(msg)) if msg.contains("ask")
+        ));
+
+        // A key built over different parameters is rejected too
+        let other_params = KZG10::<E, UniPoly381>::setup(n, Fr::rand(&mut rng)).unwrap();
+        assert!(agg_key.verify(&other_params).is_err());
+    }
+
+    #[test]
+    fn test_public_key_verify_hints() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+        let mut dummy = SecretKey::<E>::new(&mut rng);
+        dummy.nullify();
+        dummy
+            .get_pk(0, &params, n)
+            .unwrap()
+            .verify_hints(&params, n)
+            .unwrap();
+        for i in 1..n {
+            let sk = SecretKey::<E>::new(&mut rng);
+            sk.get_pk(i, &params, n)
+                .unwrap()
+                .verify_hints(&params, n)
+                .unwrap();
+            sk.lagrange_get_pk(i, &lagrange_params, n)
+                .unwrap()
+                .verify_hints(&params, n)
+                .unwrap();
+        }
+
+        let pk = SecretKey::<E>::new(&mut rng)
+            .lagrange_get_pk(3, &lagrange_params, n)
+            .unwrap();
+        let mut bad = [pk.clone(), pk.clone(), pk.clone(), pk.clone()];
+        bad[0].sk_li += G1::generator();
+        bad[1].sk_li_minus0 += G1::generator();
+        bad[2].sk_li_x += G1::generator();
+        bad[3].sk_li_lj_z[3] += G1::generator();
+        for (bad, hint) in bad
+            .iter()
+            .zip(["sk_li", "sk_li_minus0", "sk_li_x", "sk_li_lj_z"])
+        {
+            assert!(
+                matches!(bad.verify_hints(&params, n), Err(SteError::ValidationError(ref msg)) if msg.contains(hint)),
+                "{hint}"
+            );
+        }
+
+        // Honest hints for another slot, or another tau, don't pass either
+        let mut moved = pk.clone();
+        moved.id = 4;
+        assert!(moved.verify_hints(&params, n).is_err());
+        let other_params = KZG10::<E, UniPoly381>::setup(n, Fr::rand(&mut rng)).unwrap();
+        assert!(pk.verify_hints(&other_params, n).is_err());
+    }
+
+    #[test]
+    fn test_public_key_deserialize_validated() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let tau = Fr::rand(&mut rng);
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+        let pk = SecretKey::<E>::new(&mut rng)
+            .lagrange_get_pk(1, &lagrange_params, n)
+            .unwrap();
+        let mut bytes = Vec::new();
+        pk.serialize_compressed(&mut bytes).unwrap();
+
+        let decoded = PublicKey::<E>::deserialize_validated(&bytes).unwrap();
+        assert_eq!(decoded.bls_pk, pk.bls_pk);
+        assert_eq!(decoded.sk_li_lj_z, pk.sk_li_lj_z);
+
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(PublicKey::<E>::deserialize_validated(&trailing).is_err());
+
+        // Swap bls_pk (after the 8-byte id) for a point outside the subgroup
+        let mut x = ark_bls12_381::Fq::from(1u64);
+        let outside = loop {
+            if let Some(p) = ark_bls12_381::G1Affine::get_point_from_x_unchecked(x, true) {
+                if !p.is_in_correct_subgroup_assuming_on_curve() {
+                    break p;
+                }
+            }
+            x += ark_bls12_381::Fq::from(1u64);
+        };
+        let mut point_bytes = Vec::new();
+        outside.serialize_compressed(&mut point_bytes).unwrap();
+        bytes[8..8 + point_bytes.len()].copy_from_slice(&point_bytes);
+
+        let err = PublicKey::<E>::deserialize_validated(&bytes).unwrap_err();
+        assert!(matches!(err, SteError::SerializationError(_)), "{err:?}");
+    }
+
+    #[test]
+    fn test_public_key_deserialize_trusted() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let tau = Fr::rand(&mut rng);
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+        let pk = SecretKey::<E>::new(&mut rng)
+            .lagrange_get_pk(2, &lagrange_params, n)
+            .unwrap();
+        let mut bytes = Vec::new();
+        pk.serialize_compressed(&mut bytes).unwrap();
+
+        let trusted = PublicKey::<E>::deserialize_trusted(&bytes).unwrap();
+        let validated = PublicKey::<E>::deserialize_validated(&bytes).unwrap();
+        let (mut trusted_bytes, mut validated_bytes) = (Vec::new(), Vec::new());
+        trusted.serialize_compressed(&mut trusted_bytes).unwrap();
+        validated
+            .serialize_compressed(&mut validated_bytes)
+            .unwrap();
+        assert_eq!(trusted_bytes, bytes);
+        assert_eq!(validated_bytes, bytes);
+
+        // A bls_pk outside the subgroup only gets through the trusted path
+        let mut x = ark_bls12_381::Fq::from(1u64);
+        let outside = loop {
+            if let Some(p) = ark_bls12_381::G1Affine::get_point_from_x_unchecked(x, true) {
+                if !p.is_in_correct_subgroup_assuming_on_curve() {
+                    break p;
+                }
+            }
+            x += ark_bls12_381::Fq::from(1u64);
+        };
+        let mut point_bytes = Vec::new();
+        outside.serialize_compressed(&mut point_bytes).unwrap();
+        bytes[8..8 + point_bytes.len()].copy_from_slice(&point_bytes);
+
+        assert!(PublicKey::<E>::deserialize_validated(&bytes).is_err());
+        let accepted = PublicKey::<E>::deserialize_trusted(&bytes).unwrap();
+        assert_eq!(ark_ec::CurveGroup::into_affine(accepted.bls_pk), outside);
+    }
+
+    #[test]
+    fn test_lagrange_get_pk_range() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let tau = Fr::rand(&mut rng);
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+        let sk = SecretKey::<E>::new(&mut rng);
+        let ids = [3, 4, 5];
+        let range_pk = sk.lagrange_get_pk_range(&ids, &lagrange_params, n).unwrap();
+
+        assert_eq!(range_pk.len(), ids.len());
+        for (pk, &id) in range_pk.iter().zip(ids.iter()) {
+            let expected = sk.lagrange_get_pk(id, &lagrange_params, n).unwrap();
+            assert_eq!(pk.id, expected.id);
+            assert_eq!(pk.bls_pk, expected.bls_pk);
+            assert_eq!(pk.sk_li, expected.sk_li);
+            assert_eq!(pk.sk_li_minus0, expected.sk_li_minus0);
+            assert_eq!(pk.sk_li_x, expected.sk_li_x);
+            assert_eq!(pk.sk_li_lj_z, expected.sk_li_lj_z);
+        }
+
+        assert!(sk
+            .lagrange_get_pk_range(&[1, n], &lagrange_params, n)
+            .is_err());
+    }
+
+    #[test]
+    fn test_batch_partial_decryption() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+        let sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::new(&mut rng)).collect();
+        let pk = sk
+            .iter()
+            .enumerate()
+            .map(|(i, sk)| sk.lagrange_get_pk(i, &lagrange_params, n).unwrap())
+            .collect();
+        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+        let ct = crate::encryption::encrypt::<E, _>(&agg_key, 3, &params, &mut rng).unwrap();
+
+        let batch = SecretKey::batch_partial_decryption(&sk, &ct);
+        let expected: Vec<_> = sk.iter().map(|sk| sk.partial_decryption(&ct)).collect();
+        assert_eq!(batch, expected);
+    }
+
+    #[test]
+    fn test_enumerate_minimal_quorums() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 2;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+        let mut sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::new(&mut rng)).collect();
+        sk[0].nullify();
+        let pk = sk
+            .iter()
+            .enumerate()
+            .map(|(i, sk)| sk.lagrange_get_pk(i, &lagrange_params, n).unwrap())
+            .collect();
+        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+        let ct = crate::encryption::encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+
+        // C(7, 2) = 21 quorums exist; asking for more returns all of them
+        let quorums = agg_key.enumerate_minimal_quorums(t, 100);
+        assert_eq!(quorums.len(), 21);
+        assert_eq!(quorums[0], vec![0, 1, 2]);
+        assert_eq!(quorums[20], vec![0, 6, 7]);
+        for quorum in &quorums {
+            let mut selector = vec![false; n];
+            let mut partial_decryptions = vec![<E as Pairing>::G2::zero(); n];
+            for &i in quorum {
+                selector[i] = true;
+                partial_decryptions[i] = sk[i].partial_decryption(&ct);
+            }
+            let dec_key =
+                crate::decryption::agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params)
+                    .unwrap();
+            assert_eq!(dec_key, ct.enc_key);
+        }
+
+        assert_eq!(agg_key.enumerate_minimal_quorums(t, 5), quorums[..5]);
+        assert_eq!(agg_key.enumerate_minimal_quorums(n - 1, 10).len(), 1);
+        assert!(agg_key.enumerate_minimal_quorums(0, 10).is_empty());
+        assert!(agg_key.enumerate_minimal_quorums(n, 10).is_empty());
+    }
+
+    #[test]
+    fn test_partial_decryption_from_gamma() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::new(&mut rng)).collect();
+        let pk = sk
+            .iter()
+            .enumerate()
+            .map(|(i, sk)| sk.get_pk(i, &params, n).unwrap())
+            .collect();
+        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+        let ct = crate::encryption::encrypt::<E, _>(&agg_key, 2, &params, &mut rng).unwrap();
+
+        for sk in &sk {
+            assert_eq!(
+                sk.partial_decryption_from_gamma(&ct.gamma_g2),
+                sk.partial_decryption(&ct)
+            );
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_public_key_json_round_trip() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let tau = Fr::rand(&mut rng);
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+        let sk = SecretKey::<E>::new(&mut rng);
+        let pk = sk.lagrange_get_pk(2, &lagrange_params, n).unwrap();
+
+        let json = pk.to_json().unwrap();
+        let decoded = PublicKey::<E>::from_json(&json).unwrap();
+
+        assert_eq!(decoded.id, pk.id);
+        assert_eq!(decoded.bls_pk, pk.bls_pk);
+        assert_eq!(decoded.sk_li, pk.sk_li);
+        assert_eq!(decoded.sk_li_minus0, pk.sk_li_minus0);
+        assert_eq!(decoded.sk_li_x, pk.sk_li_x);
+        assert_eq!(decoded.sk_li_lj_z, pk.sk_li_lj_z);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_public_key_json_rejects_wrong_hint_count() {
+        let mut rng = ark_std::test_rng();
+        let n = 4;
+        let tau = Fr::rand(&mut rng);
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+        let sk = SecretKey::<E>::new(&mut rng);
+        let pk = sk.lagrange_get_pk(1, &lagrange_params, n).unwrap();
+
+        let mut json: serde_json::Value = serde_json::from_str(&pk.to_json().unwrap()).unwrap();
+        json["sk_li_lj_z"].as_array_mut().unwrap().pop();
+
+        let err = PublicKey::<E>::from_json(&json.to_string())
+            .expect_err("expected import to fail with a missing sk_li_lj_z entry");
+        assert!(
+            matches!(err, SteError::ValidationError(ref msg) if msg.contains("sk_li_lj_z")),
             "unexpected error: {err:?}"
         );
     }
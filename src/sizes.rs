@@ -0,0 +1,118 @@
+//! Serialized sizes of the scheme's artifacts over BLS12-381.
+//!
+//! The sizes are computed from the compressed point sizes and the element
+//! counts of each type, so storage and bandwidth can be planned for a
+//! committee size without running setup.
+
+/// Compressed BLS12-381 G1 point
+pub const G1_COMPRESSED_SIZE: usize = 48;
+/// Compressed BLS12-381 G2 point
+pub const G2_COMPRESSED_SIZE: usize = 96;
+/// Compressed BLS12-381 GT element (an Fq12 element)
+pub const GT_COMPRESSED_SIZE: usize = 576;
+/// A serialized `usize` or `Vec` length prefix
+const LENGTH_SIZE: usize = 8;
+
+/// Compressed sizes in bytes of each artifact for one committee size.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SizeReport {
+    pub n: usize,
+    /// `PowersOfTau` with the `n + 1` powers that setup produces
+    pub powers_of_tau: usize,
+    /// `LagrangePowers`, dominated by the n² `li_lj_z` points
+    pub lagrange_powers: usize,
+    /// A single party's `PublicKey`
+    pub public_key: usize,
+    /// `AggregateKey`, holding all n public keys
+    pub aggregate_key: usize,
+    /// `EncryptOnlyKey`
+    pub encrypt_only_key: usize,
+    /// `Ciphertext`, independent of `n`
+    pub ciphertext: usize,
+    /// One partial decryption (a G2 point)
+    pub partial_decryption: usize,
+}
+
+/// Estimates the compressed serialized sizes of every artifact for committee
+/// size `n`.
+///
+/// The figures are exact for the arkworks compressed encoding used throughout
+/// the crate.
+pub fn serialized_size_estimate(n: usize) -> SizeReport {
+    let g1_vec = |len: usize| LENGTH_SIZE + len * G1_COMPRESSED_SIZE;
+
+    let powers_of_tau = g1_vec(n + 1) + LENGTH_SIZE + (n + 1) * G2_COMPRESSED_SIZE;
+    let lagrange_powers = 3 * g1_vec(n) + LENGTH_SIZE + n * g1_vec(n);
+
+    // id, then bls_pk, sk_li, sk_li_minus0, sk_li_lj_z and sk_li_x
+    let public_key = LENGTH_SIZE + 3 * G1_COMPRESSED_SIZE + g1_vec(n) + G1_COMPRESSED_SIZE;
+
+    // z_g2, h_minus1 and e_gh are shared by both key types
+    let shared = 2 * G2_COMPRESSED_SIZE + GT_COMPRESSED_SIZE;
+    let aggregate_key = LENGTH_SIZE + n * public_key + g1_vec(n) + G1_COMPRESSED_SIZE + shared;
+    let encrypt_only_key = LENGTH_SIZE + G1_COMPRESSED_SIZE + shared;
+
+    let ciphertext = G2_COMPRESSED_SIZE
+        + crate::encryption::SA1_SIZE * G1_COMPRESSED_SIZE
+        + crate::encryption::SA2_SIZE * G2_COMPRESSED_SIZE
+        + GT_COMPRESSED_SIZE
+        + LENGTH_SIZE;
+
+    SizeReport {
+        n,
+        powers_of_tau,
+        lagrange_powers,
+        public_key,
+        aggregate_key,
+        encrypt_only_key,
+        ciphertext,
+        partial_decryption: G2_COMPRESSED_SIZE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        encryption::encrypt,
+        kzg::KZG10,
+        setup::{AggregateKey, LagrangePowers, SecretKey},
+    };
+    use ark_ec::pairing::Pairing;
+    use ark_poly::univariate::DensePolynomial;
+    use ark_serialize::CanonicalSerialize;
+    use ark_std::UniformRand;
+
+    type E = ark_bls12_381::Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+    type UniPoly381 = DensePolynomial<Fr>;
+
+    #[test]
+    fn test_size_estimate_matches_serialization() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+        let pk: Vec<_> = (0..n)
+            .map(|i| {
+                SecretKey::<E>::new(&mut rng)
+                    .lagrange_get_pk(i, &lagrange_params, n)
+                    .unwrap()
+            })
+            .collect();
+        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+        let ct = encrypt::<E, _>(&agg_key, 3, &params, &mut rng).unwrap();
+
+        let estimate = serialized_size_estimate(n);
+        assert_eq!(estimate.powers_of_tau, params.compressed_size());
+        assert_eq!(estimate.lagrange_powers, lagrange_params.compressed_size());
+        assert_eq!(estimate.public_key, agg_key.pk[0].compressed_size());
+        assert_eq!(estimate.aggregate_key, agg_key.compressed_size());
+        assert_eq!(
+            estimate.encrypt_only_key,
+            agg_key.encrypt_only().compressed_size()
+        );
+        assert_eq!(estimate.ciphertext, ct.compressed_size());
+    }
+}
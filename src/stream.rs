@@ -0,0 +1,309 @@
+//! Streaming encryption of large payloads.
+//!
+//! A single threshold ciphertext encapsulates the key for the whole stream.
+//! It is published as an [`Encapsulation`], without the key, which the
+//! encryptor drops once the stream state is derived; afterwards only a quorum
+//! can recover it, for a [`StreamDecryptor`].
+//! The payload is then sealed chunk by chunk with ChaCha20-Poly1305 under a key
+//! derived from the encapsulated GT element. Each chunk's nonce is
+//!
+//! ```text
+//! counter (8 bytes, big-endian) || last-chunk flag (1 byte) || 0 (3 bytes)
+//! ```
+//!
+//! so chunks cannot be reordered or dropped, and the flag on the final chunk
+//! makes truncation of the stream detectable. Every chunk is also bound to the
+//! threshold ciphertext through its associated data.
+//!
+//! Chunk boundaries are not recorded in the sealed output: the caller must
+//! store or frame each sealed chunk so the decryptor sees the same boundaries.
+
+use ark_ec::pairing::Pairing;
+use ark_serialize::CanonicalSerialize;
+use ark_std::rand::RngCore;
+use blake2::{Blake2b512, Digest};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
+use zeroize::Zeroize;
+
+use crate::{
+    encryption::{encapsulate, Encapsulation},
+    error::SteError,
+    kzg::PowersOfTau,
+    security::{DefaultKdf, RecoveredKey},
+    setup::EncryptionKey,
+};
+
+/// Domain separation tag for deriving the stream key from the symmetric key.
+const STREAM_KEY_DST: &[u8] = b"silent-threshold-encryption/stream-key/v1";
+
+/// Domain separation tag for the per-chunk associated data.
+const STREAM_AAD_DST: &[u8] = b"silent-threshold-encryption/stream-aad/v1";
+
+/// Bytes added to every chunk by the Poly1305 tag.
+pub const STREAM_TAG_SIZE: usize = 16;
+
+/// Encrypts a payload incrementally to a committee.
+pub struct StreamEncryptor<E: Pairing> {
+    ct: Encapsulation<E>,
+    state: StreamState,
+}
+
+impl<E: Pairing> StreamEncryptor<E> {
+    /// Encapsulates a fresh key to the committee and prepares to seal chunks.
+    ///
+    /// # Errors
+    /// Returns any error from [`encapsulate`] or from serializing the
+    /// encapsulation
+    pub fn new<R: RngCore>(
+        agg_key: &impl EncryptionKey<E>,
+        t: usize,
+        params: &PowersOfTau<E>,
+        rng: &mut R,
+    ) -> Result<Self, SteError> {
+        let (ct, key) = encapsulate(agg_key, t, params, rng)?;
+        let state = StreamState::new(&key, &ct)?;
+        Ok(Self { ct, state })
+    }
+
+    /// The encapsulation the decrypting parties must process.
+    pub fn ciphertext(&self) -> &Encapsulation<E> {
+        &self.ct
+    }
+
+    /// Seals the next chunk. The output is `chunk.len() + STREAM_TAG_SIZE` bytes.
+    pub fn update(&mut self, chunk: &[u8]) -> Vec<u8> {
+        self.state
+            .seal(chunk, false)
+            .expect("sealing a chunk with ChaCha20-Poly1305 cannot fail")
+    }
+
+    /// Seals the empty final chunk that marks the end of the stream.
+    pub fn finalize(mut self) -> Vec<u8> {
+        self.state
+            .seal(&[], true)
+            .expect("sealing a chunk with ChaCha20-Poly1305 cannot fail")
+    }
+}
+
+/// Decrypts a stream produced by [`StreamEncryptor`].
+pub struct StreamDecryptor {
+    state: StreamState,
+}
+
+impl StreamDecryptor {
+    /// Prepares to open chunks given the key recovered from `ct` by
+    /// [`agg_dec_encapsulated`](crate::decryption::agg_dec_encapsulated).
+    ///
+    /// # Errors
+    /// Returns `SteError::SerializationError` if the encapsulation cannot be serialized
+    pub fn new<E: Pairing>(key: &RecoveredKey<E>, ct: &Encapsulation<E>) -> Result<Self, SteError> {
+        Ok(Self {
+            state: StreamState::new(key, ct)?,
+        })
+    }
+
+    /// Opens the next chunk.
+    ///
+    /// # Errors
+    /// Returns `SteError::DecryptionFailure` if the chunk was tampered with,
+    /// reordered, or sealed under a different key or ciphertext
+    pub fn update(&mut self, sealed: &[u8]) -> Result<Vec<u8>, SteError> {
+        self.state.open(sealed, false)
+    }
+
+    /// Checks the final chunk, confirming the stream was not truncated.
+    ///
+    /// # Errors
+    /// Returns `SteError::DecryptionFailure` if `sealed` is not the final chunk
+    /// that follows the chunks opened so far
+    pub fn finalize(mut self, sealed: &[u8]) -> Result<(), SteError> {
+        let trailer = self.state.open(sealed, true)?;
+        if !trailer.is_empty() {
+            return Err(SteError::DecryptionFailure(
+                "final stream chunk must be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+struct StreamState {
+    cipher: ChaCha20Poly1305,
+    aad: [u8; 32],
+    counter: u64,
+}
+
+impl StreamState {
+    fn new<E: Pairing>(key: &RecoveredKey<E>, ct: &Encapsulation<E>) -> Result<Self, SteError> {
+        let mut ct_bytes = Vec::new();
+        ct.serialize_compressed(&mut ct_bytes).map_err(|e| {
+            SteError::SerializationError(format!("Failed to serialize ciphertext: {}", e))
+        })?;
+        let mut aad = [0u8; 32];
+        aad.copy_from_slice(
+            &Blake2b512::new()
+                .chain_update(STREAM_AAD_DST)
+                .chain_update(ct_bytes)
+                .finalize()[..32],
+        );
+
+        let symmetric_key = key.derive_symmetric_key::<DefaultKdf>();
+        let mut digest = Blake2b512::new()
+            .chain_update(STREAM_KEY_DST)
+            .chain_update(symmetric_key.as_slice())
+            .finalize();
+        let cipher = ChaCha20Poly1305::new(Key::from_slice(&digest[..32]));
+        digest.as_mut_slice().zeroize();
+
+        Ok(Self {
+            cipher,
+            aad,
+            counter: 0,
+        })
+    }
+
+    fn next_nonce(&mut self, last: bool) -> Result<Nonce, SteError> {
+        let mut nonce = [0u8; 12];
+        nonce[..8].copy_from_slice(&self.counter.to_be_bytes());
+        nonce[8] = last as u8;
+        self.counter = self
+            .counter
+            .checked_add(1)
+            .ok_or_else(|| SteError::CryptoError("stream chunk counter exhausted".to_string()))?;
+        Ok(*Nonce::from_slice(&nonce))
+    }
+
+    fn seal(&mut self, chunk: &[u8], last: bool) -> Result<Vec<u8>, SteError> {
+        let nonce = self.next_nonce(last)?;
+        self.cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: chunk,
+                    aad: &self.aad,
+                },
+            )
+            .map_err(|_| SteError::CryptoError("Failed to seal stream chunk".to_string()))
+    }
+
+    fn open(&mut self, sealed: &[u8], last: bool) -> Result<Vec<u8>, SteError> {
+        let nonce = self.next_nonce(last)?;
+        self.cipher
+            .decrypt(
+                &nonce,
+                Payload {
+                    msg: sealed,
+                    aad: &self.aad,
+                },
+            )
+            .map_err(|_| {
+                SteError::DecryptionFailure(format!(
+                    "stream chunk {} failed authentication",
+                    self.counter - 1
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        decryption::agg_dec_encapsulated,
+        kzg::KZG10,
+        setup::{AggregateKey, LagrangePowers, SecretKey},
+    };
+    use ark_poly::univariate::DensePolynomial;
+    use ark_std::UniformRand;
+
+    type E = ark_bls12_381::Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+    type G2 = <E as Pairing>::G2;
+    type UniPoly381 = DensePolynomial<Fr>;
+
+    const CHUNK_SIZE: usize = 64 * 1024;
+
+    #[test]
+    fn test_stream_round_trip() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let t = 3;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+        let mut sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::new(&mut rng)).collect();
+        sk[0].nullify();
+        let pk = sk
+            .iter()
+            .enumerate()
+            .map(|(i, sk)| sk.lagrange_get_pk(i, &lagrange_params, n).unwrap())
+            .collect();
+        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+
+        let mut payload = vec![0u8; 10 * 1024 * 1024];
+        rng.fill_bytes(&mut payload);
+
+        let mut encryptor = StreamEncryptor::new(&agg_key, t, &params, &mut rng).unwrap();
+        let ct = encryptor.ciphertext().clone();
+        let sealed: Vec<Vec<u8>> = payload
+            .chunks(CHUNK_SIZE)
+            .map(|chunk| encryptor.update(chunk))
+            .collect();
+        let trailer = encryptor.finalize();
+        assert!(sealed
+            .iter()
+            .all(|chunk| chunk.len() == CHUNK_SIZE + STREAM_TAG_SIZE));
+
+        let mut selector = vec![false; n];
+        let mut partial_decryptions = vec![G2::default(); n];
+        for i in 0..=t {
+            selector[i] = true;
+            partial_decryptions[i] = sk[i].partial_decryption_encapsulated(&ct);
+        }
+
+        // The published encapsulation does not carry the key, and without a
+        // quorum it cannot be recovered
+        let mut ct_bytes = Vec::new();
+        ct.serialize_compressed(&mut ct_bytes).unwrap();
+        let ct = Encapsulation::<E>::deserialize_validated(&ct_bytes).unwrap();
+        let mut short = selector.clone();
+        short[t] = false;
+        assert!(
+            agg_dec_encapsulated(&partial_decryptions, &ct, &short, &agg_key, &params).is_err()
+        );
+
+        let key =
+            agg_dec_encapsulated(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+        let mut key_bytes = Vec::new();
+        key.expose().serialize_compressed(&mut key_bytes).unwrap();
+        assert!(!ct_bytes.windows(key_bytes.len()).any(|w| w == key_bytes));
+
+        let mut decryptor = StreamDecryptor::new(&key, &ct).unwrap();
+        let mut recovered = Vec::with_capacity(payload.len());
+        for chunk in &sealed {
+            recovered.extend(decryptor.update(chunk).unwrap());
+        }
+        decryptor.finalize(&trailer).unwrap();
+        assert_eq!(recovered, payload);
+
+        // Reordered chunks fail authentication
+        let mut decryptor = StreamDecryptor::new(&key, &ct).unwrap();
+        assert!(decryptor.update(&sealed[1]).is_err());
+
+        // A truncated stream is detected when finalizing
+        let mut decryptor = StreamDecryptor::new(&key, &ct).unwrap();
+        decryptor.update(&sealed[0]).unwrap();
+        assert!(decryptor.finalize(&sealed[1]).is_err());
+
+        // The trailer cannot be passed off as a data chunk
+        let mut decryptor = StreamDecryptor::new(&key, &ct).unwrap();
+        for chunk in &sealed {
+            decryptor.update(chunk).unwrap();
+        }
+        assert!(decryptor.update(&trailer).is_err());
+    }
+}
@@ -0,0 +1,71 @@
+//! Helpers for tests of this crate and of code built on it.
+//!
+//! Compiled for the crate's own tests and with the `testing` feature.
+
+use ark_ec::pairing::Pairing;
+use ark_poly::univariate::DensePolynomial;
+use ark_std::{rand::RngCore, UniformRand, Zero};
+
+use crate::{
+    decryption::agg_dec,
+    encryption::encrypt,
+    error::SteError,
+    kzg::{PowersOfTau, KZG10},
+    setup::{AggregateKey, LagrangePowers, SecretKey},
+    utils::parties_to_selector,
+};
+
+/// Generates keys for a committee of size `n`, with party 0 as the dummy
+/// party, and aggregates them under `params`.
+///
+/// `params` may be larger than `n`, so committees of different sizes can
+/// share them as long as they were made with the same `tau`.
+///
+/// # Errors
+/// Returns an error if `n` is invalid or `params` are too small for it
+pub fn setup_committee<E: Pairing, R: RngCore>(
+    n: usize,
+    tau: E::ScalarField,
+    params: &PowersOfTau<E>,
+    rng: &mut R,
+) -> Result<(Vec<SecretKey<E>>, AggregateKey<E>), SteError> {
+    let lagrange_params = LagrangePowers::<E>::new(tau, n)?;
+    let mut sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::new(rng)).collect();
+    sk[0].nullify();
+    let pk = sk
+        .iter()
+        .enumerate()
+        .map(|(i, sk)| sk.lagrange_get_pk(i, &lagrange_params, n))
+        .collect::<Result<_, _>>()?;
+    let agg_key = AggregateKey::<E>::new(pk, params)?;
+    Ok((sk, agg_key))
+}
+
+/// Runs setup, key generation, encryption, partial decryption by the first
+/// `t + 1` parties and aggregation for a committee of size `n`.
+///
+/// Returns whether the recovered key equals the encrypted one.
+///
+/// # Errors
+/// Returns the first error from any step, e.g. for an invalid `n` or `t`
+pub fn run_full_cycle<E: Pairing, R: RngCore>(
+    n: usize,
+    t: usize,
+    rng: &mut R,
+) -> Result<bool, SteError> {
+    let tau = E::ScalarField::rand(rng);
+    let params = KZG10::<E, DensePolynomial<E::ScalarField>>::setup(n, tau)?;
+    let (sk, agg_key) = setup_committee(n, tau, &params, rng)?;
+
+    let ct = encrypt(&agg_key, t, &params, rng)?;
+
+    let quorum: Vec<usize> = (0..(t + 1).min(n)).collect();
+    let selector = parties_to_selector(&quorum, n)?;
+    let mut partial_decryptions = vec![E::G2::zero(); n];
+    for &i in &quorum {
+        partial_decryptions[i] = sk[i].partial_decryption(&ct);
+    }
+
+    let dec_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params)?;
+    Ok(dec_key == ct.enc_key)
+}
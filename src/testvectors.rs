@@ -0,0 +1,177 @@
+//! Deterministic test vectors for cross-implementation checks.
+//!
+//! A vector records every artifact of one setup → keygen → encrypt →
+//! partial decryption → `agg_dec` run over BLS12-381, driven by a seeded
+//! `StdRng`. All group elements are hex-encoded in arkworks compressed form.
+//!
+//! The RNG is consumed in a fixed order: `tau`, then one secret key per party
+//! (party 0 is nullified afterwards), then `encrypt`. Parties `0..=t` decrypt.
+
+use ark_bls12_381::Bls12_381;
+use ark_ec::pairing::Pairing;
+use ark_poly::univariate::DensePolynomial;
+use ark_std::{
+    rand::{rngs::StdRng, SeedableRng},
+    UniformRand,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    decryption::agg_dec,
+    encryption::encrypt,
+    error::SteError,
+    kzg::KZG10,
+    setup::{encode_point_hex, AggregateKey, LagrangePowers, SecretKey},
+};
+
+type E = Bls12_381;
+type Fr = <E as Pairing>::ScalarField;
+type G2 = <E as Pairing>::G2;
+type UniPoly381 = DensePolynomial<Fr>;
+
+pub const TEST_VECTOR_VERSION: u32 = 1;
+
+/// Transcript of one full protocol run.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct TestVector {
+    pub version: u32,
+    pub seed: u64,
+    pub n: usize,
+    pub t: usize,
+    /// `PowersOfTau` for degree `n`
+    pub params: String,
+    /// One `PublicKey` per party
+    pub public_keys: Vec<String>,
+    pub ciphertext: String,
+    /// Partial decryptions of parties `0..=t`, in order
+    pub partial_decryptions: Vec<String>,
+    /// Output of `agg_dec`, equal to the ciphertext's `enc_key`
+    pub recovered_key: String,
+}
+
+impl TestVector {
+    pub fn to_json(&self) -> Result<String, SteError> {
+        serde_json::to_string_pretty(self).map_err(|e| {
+            SteError::SerializationError(format!("Failed to encode test vector: {}", e))
+        })
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, SteError> {
+        serde_json::from_str(json).map_err(|e| {
+            SteError::SerializationError(format!("Failed to parse test vector: {}", e))
+        })
+    }
+}
+
+/// Runs the full protocol from `seed` and records its transcript.
+///
+/// # Errors
+/// Returns an error if `n` or `t` are rejected by setup or encryption
+pub fn generate(seed: u64, n: usize, t: usize) -> Result<TestVector, SteError> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let tau = Fr::rand(&mut rng);
+    let params = KZG10::<E, UniPoly381>::setup(n, tau)?;
+    let lagrange_params = LagrangePowers::<E>::new(tau, n)?;
+
+    let mut sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::new(&mut rng)).collect();
+    sk[0].nullify();
+    let pk = sk
+        .iter()
+        .enumerate()
+        .map(|(i, sk)| sk.lagrange_get_pk(i, &lagrange_params, n))
+        .collect::<Result<Vec<_>, _>>()?;
+    let agg_key = AggregateKey::<E>::new(pk.clone(), &params)?;
+
+    let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng)?;
+
+    let mut selector = vec![false; n];
+    let mut partial_decryptions = vec![G2::default(); n];
+    for i in 0..=t {
+        selector[i] = true;
+        partial_decryptions[i] = sk[i].partial_decryption(&ct);
+    }
+    let recovered_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params)?;
+
+    Ok(TestVector {
+        version: TEST_VECTOR_VERSION,
+        seed,
+        n,
+        t,
+        params: encode_point_hex(&params)?,
+        public_keys: pk.iter().map(encode_point_hex).collect::<Result<_, _>>()?,
+        ciphertext: encode_point_hex(&ct)?,
+        partial_decryptions: partial_decryptions[..=t]
+            .iter()
+            .map(encode_point_hex)
+            .collect::<Result<_, _>>()?,
+        recovered_key: encode_point_hex(&recovered_key)?,
+    })
+}
+
+/// Re-runs the flow described by a JSON test vector and checks that every
+/// artifact matches byte for byte.
+///
+/// # Errors
+/// Returns `SteError::ValidationError` naming the first artifact that differs
+pub fn check_vector(json: &str) -> Result<(), SteError> {
+    let expected = TestVector::from_json(json)?;
+    if expected.version != TEST_VECTOR_VERSION {
+        return Err(SteError::ValidationError(format!(
+            "unsupported test vector version {} (expected {})",
+            expected.version, TEST_VECTOR_VERSION
+        )));
+    }
+    let actual = generate(expected.seed, expected.n, expected.t)?;
+
+    let mismatch = |field: &str| {
+        Err(SteError::ValidationError(format!(
+            "test vector mismatch in {}",
+            field
+        )))
+    };
+    if actual.params != expected.params {
+        return mismatch("params");
+    }
+    if actual.public_keys != expected.public_keys {
+        return mismatch("public_keys");
+    }
+    if actual.ciphertext != expected.ciphertext {
+        return mismatch("ciphertext");
+    }
+    if actual.partial_decryptions != expected.partial_decryptions {
+        return mismatch("partial_decryptions");
+    }
+    if actual.recovered_key != expected.recovered_key {
+        return mismatch("recovered_key");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VECTOR_N8_T3: &str = include_str!("../test_vectors/n8_t3.json");
+
+    #[test]
+    fn test_vector_is_reproducible() {
+        let first = generate(2024, 8, 3).unwrap();
+        let second = generate(2024, 8, 3).unwrap();
+        assert_eq!(first, second);
+        check_vector(&first.to_json().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_committed_vector() {
+        check_vector(VECTOR_N8_T3).unwrap();
+
+        let mut tampered = TestVector::from_json(VECTOR_N8_T3).unwrap();
+        tampered.ciphertext.replace_range(0..2, "00");
+        let err = check_vector(&tampered.to_json().unwrap()).unwrap_err();
+        assert!(
+            matches!(err, SteError::ValidationError(ref msg) if msg.contains("ciphertext")),
+            "unexpected error: {err:?}"
+        );
+    }
+}
@@ -0,0 +1,242 @@
+//! Chunked transfer of large serialized artifacts.
+//!
+//! The aggregate key of a big committee runs to megabytes, and a transfer that
+//! dies halfway would otherwise start over. [`split`] serializes a value once
+//! and cuts the compressed bytes into chunks, described by a [`ChunkManifest`]
+//! holding each chunk's offset, length and SHA-256 hash.
+//!
+//! The receiver gets the manifest first, from a source it trusts, then fetches
+//! chunks in any order and from any peer, checking each one with
+//! [`ChunkManifest::verify_chunk`] as it arrives. Verified chunks can be kept
+//! across an interrupted transfer, and [`reassemble`] rebuilds the value once
+//! all of them are there.
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sha2::{Digest, Sha256};
+
+use crate::{error::SteError, setup::deserialize_validated};
+
+/// Where one chunk sits in the serialized bytes, and what it hashes to.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ChunkInfo {
+    pub offset: u64,
+    pub len: u32,
+    /// SHA-256 of the chunk's bytes
+    pub hash: [u8; 32],
+}
+
+/// Describes how a serialized value was cut into chunks.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ChunkManifest {
+    pub total_len: u64,
+    pub chunks: Vec<ChunkInfo>,
+}
+
+impl ChunkManifest {
+    pub fn num_chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Checks that chunk `index` has the length and hash the manifest records.
+    ///
+    /// # Errors
+    /// Returns `SteError::ValidationError` if `index` is out of range or the
+    /// bytes don't match
+    pub fn verify_chunk(&self, index: usize, bytes: &[u8]) -> Result<(), SteError> {
+        let info = self.chunks.get(index).ok_or_else(|| {
+            SteError::ValidationError(format!(
+                "chunk {} out of range for {} chunks",
+                index,
+                self.chunks.len()
+            ))
+        })?;
+        if bytes.len() != info.len as usize {
+            return Err(SteError::ValidationError(format!(
+                "chunk {} has {} bytes, expected {}",
+                index,
+                bytes.len(),
+                info.len
+            )));
+        }
+        if Sha256::digest(bytes).as_slice() != info.hash {
+            return Err(SteError::ValidationError(format!(
+                "chunk {} does not match its hash",
+                index
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks that the chunks tile `[0, total_len)` in order, so a manifest
+    /// from the wire cannot make [`reassemble`] skip or overlap bytes.
+    fn validate_layout(&self) -> Result<(), SteError> {
+        let mut end = 0u64;
+        for (i, info) in self.chunks.iter().enumerate() {
+            if info.offset != end || info.len == 0 {
+                return Err(SteError::ValidationError(format!(
+                    "manifest chunk {} at offset {} with {} bytes does not follow offset {}",
+                    i, info.offset, info.len, end
+                )));
+            }
+            end += u64::from(info.len);
+        }
+        if end != self.total_len {
+            return Err(SteError::ValidationError(format!(
+                "manifest chunks cover {} bytes, expected {}",
+                end, self.total_len
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Serializes `value` (compressed) and cuts it into chunks of `chunk_size`
+/// bytes; the last chunk may be shorter.
+///
+/// # Errors
+/// Returns `SteError::InvalidParameter` if `chunk_size` is zero or above
+/// `u32::MAX`, or `SteError::SerializationError` if serialization fails
+pub fn split<T: CanonicalSerialize>(
+    value: &T,
+    chunk_size: usize,
+) -> Result<(ChunkManifest, Vec<Vec<u8>>), SteError> {
+    if chunk_size == 0 || chunk_size > u32::MAX as usize {
+        return Err(SteError::InvalidParameter(format!(
+            "chunk size must be in [1, {}], got {}",
+            u32::MAX,
+            chunk_size
+        )));
+    }
+
+    let mut bytes = Vec::new();
+    value
+        .serialize_compressed(&mut bytes)
+        .map_err(|e| SteError::SerializationError(format!("Failed to serialize: {}", e)))?;
+
+    let chunks: Vec<Vec<u8>> = bytes.chunks(chunk_size).map(<[u8]>::to_vec).collect();
+    let infos = chunks
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| ChunkInfo {
+            offset: (i * chunk_size) as u64,
+            len: chunk.len() as u32,
+            hash: Sha256::digest(chunk).into(),
+        })
+        .collect();
+
+    let manifest = ChunkManifest {
+        total_len: bytes.len() as u64,
+        chunks: infos,
+    };
+    Ok((manifest, chunks))
+}
+
+/// Rebuilds a value from chunks received so far, in any order.
+///
+/// Each `(index, bytes)` pair is verified against the manifest; a repeated
+/// index is accepted as long as it verifies. The result is deserialized with
+/// full validation, as it came from the network.
+///
+/// # Errors
+/// Returns `SteError::ValidationError` if the manifest is inconsistent, a
+/// chunk fails verification or some chunks are missing (listing them), or
+/// `SteError::SerializationError` if the reassembled bytes don't decode
+pub fn reassemble<T: CanonicalDeserialize>(
+    manifest: &ChunkManifest,
+    chunks: impl IntoIterator<Item = (usize, Vec<u8>)>,
+    what: &str,
+) -> Result<T, SteError> {
+    manifest.validate_layout()?;
+
+    let mut received: Vec<Option<Vec<u8>>> = vec![None; manifest.num_chunks()];
+    for (index, bytes) in chunks {
+        manifest.verify_chunk(index, &bytes)?;
+        received[index] = Some(bytes);
+    }
+
+    let missing: Vec<usize> = (0..received.len())
+        .filter(|&i| received[i].is_none())
+        .collect();
+    if !missing.is_empty() {
+        return Err(SteError::ValidationError(format!(
+            "missing chunks {:?} of {}",
+            missing,
+            manifest.num_chunks()
+        )));
+    }
+
+    let bytes: Vec<u8> = received.into_iter().flatten().flatten().collect();
+    deserialize_validated(&bytes, what)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        kzg::KZG10,
+        setup::{AggregateKey, LagrangePowers, SecretKey},
+    };
+    use ark_ec::pairing::Pairing;
+    use ark_poly::univariate::DensePolynomial;
+    use ark_std::UniformRand;
+
+    type E = ark_bls12_381::Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+    type UniPoly381 = DensePolynomial<Fr>;
+
+    #[test]
+    fn test_aggregate_key_reassembles_from_shuffled_chunks() {
+        let mut rng = ark_std::test_rng();
+        let n = 8;
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+        let pk = (0..n)
+            .map(|i| {
+                SecretKey::<E>::new(&mut rng)
+                    .lagrange_get_pk(i, &lagrange_params, n)
+                    .unwrap()
+            })
+            .collect();
+        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+
+        let (manifest, chunks) = agg_key.serialize_chunked(1000).unwrap();
+        assert!(manifest.num_chunks() > 2);
+
+        // The first delivery is cut short by an interruption; the rest arrive
+        // later, out of order, with one resent
+        let mut order: Vec<usize> = (0..chunks.len()).rev().collect();
+        order.push(0);
+        for &i in &order {
+            manifest.verify_chunk(i, &chunks[i]).unwrap();
+        }
+        let received: Vec<(usize, Vec<u8>)> =
+            order.iter().map(|&i| (i, chunks[i].clone())).collect();
+
+        let err =
+            AggregateKey::<E>::deserialize_resume(&manifest, received[..2].to_vec()).unwrap_err();
+        assert!(
+            matches!(err, SteError::ValidationError(ref msg) if msg.contains("missing")),
+            "{err:?}"
+        );
+
+        let decoded = AggregateKey::<E>::deserialize_resume(&manifest, received).unwrap();
+        let mut expected = Vec::new();
+        agg_key.serialize_compressed(&mut expected).unwrap();
+        let mut actual = Vec::new();
+        decoded.serialize_compressed(&mut actual).unwrap();
+        assert_eq!(actual, expected);
+
+        // A corrupted chunk is caught on its own
+        let mut bad = chunks[1].clone();
+        bad[0] ^= 1;
+        assert!(manifest.verify_chunk(1, &bad).is_err());
+        assert!(manifest.verify_chunk(chunks.len(), &chunks[0]).is_err());
+
+        // So is a manifest whose chunks don't tile the bytes
+        let mut gap = manifest.clone();
+        gap.chunks[1].offset += 1;
+        let all = (0..chunks.len()).map(|i| (i, chunks[i].clone()));
+        assert!(AggregateKey::<E>::deserialize_resume(&gap, all).is_err());
+    }
+}
@@ -30,7 +30,9 @@ use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::rand::RngCore;
 use ark_std::vec::Vec;
 
+use crate::error::SteError;
 use crate::kzg::{Error as KzgError, PowersOfTau};
+use crate::setup::{check_params_degree, LagrangePowers};
 
 /// A contribution to the powers-of-tau ceremony
 #[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
@@ -83,8 +85,8 @@ impl<E: Pairing> Ceremony<E> {
         let powers_of_h = h.batch_mul(&powers_of_tau);
 
         // Store proof elements (τ^1 G and τ^1 H for verification)
-        let proof_g = powers_of_g[1].clone();
-        let proof_h = powers_of_h[1].clone();
+        let proof_g = powers_of_g[1];
+        let proof_h = powers_of_h[1];
 
         let initial_contribution = Contribution {
             powers_of_g,
@@ -117,10 +119,7 @@ impl<E: Pairing> Ceremony<E> {
     /// - After this function returns, caller MUST zeroize all RNG state and secrets
     /// - The secret τ must never be stored or transmitted
     pub fn contribute<R: RngCore>(&mut self, rng: &mut R) -> Result<(), KzgError> {
-        let previous = self
-            .contributions
-            .last()
-            .ok_or_else(|| KzgError::DegreeIsZero)?;
+        let previous = self.contributions.last().ok_or(KzgError::DegreeIsZero)?;
 
         // Generate random tau for this participant
         let tau = E::ScalarField::rand(rng);
@@ -157,8 +156,8 @@ impl<E: Pairing> Ceremony<E> {
             .collect();
 
         // Create proof elements for verification
-        let proof_g = new_powers_of_g[1].clone();
-        let proof_h = new_powers_of_h[1].clone();
+        let proof_g = new_powers_of_g[1];
+        let proof_h = new_powers_of_h[1];
 
         let new_contribution = Contribution {
             powers_of_g: new_powers_of_g,
@@ -240,8 +239,8 @@ impl<E: Pairing> Ceremony<E> {
 
         // Check that each successive G power was multiplied by the same scalar encoded in proof_h
         for i in 0..curr.powers_of_g.len() - 1 {
-            let lhs = E::pairing(curr.powers_of_g[i].clone(), proof_h);
-            let rhs = E::pairing(curr.powers_of_g[i + 1].clone(), prev_h0);
+            let lhs = E::pairing(curr.powers_of_g[i], proof_h);
+            let rhs = E::pairing(curr.powers_of_g[i + 1], prev_h0);
             if lhs != rhs {
                 return false;
             }
@@ -249,8 +248,8 @@ impl<E: Pairing> Ceremony<E> {
 
         // Check that each successive H power matches the scalar encoded in proof_g
         for i in 0..curr.powers_of_h.len() - 1 {
-            let lhs = E::pairing(proof_g, curr.powers_of_h[i].clone());
-            let rhs = E::pairing(prev_g0, curr.powers_of_h[i + 1].clone());
+            let lhs = E::pairing(proof_g, curr.powers_of_h[i]);
+            let rhs = E::pairing(prev_g0, curr.powers_of_h[i + 1]);
             if lhs != rhs {
                 return false;
             }
@@ -259,6 +258,31 @@ impl<E: Pairing> Ceremony<E> {
         true
     }
 
+    /// Verifies contributions `start..`, skipping any before it.
+    ///
+    /// Each contribution is checked only against its predecessor, so after
+    /// `verify_from(1)` has accepted the first `k` contributions, appending
+    /// more and calling `verify_from(k)` gives the same answer as verifying
+    /// everything again, as long as the first `k` are unchanged. Contribution
+    /// 0 has no predecessor and is never checked.
+    pub fn verify_from(&self, start: usize) -> bool {
+        (start.max(1)..self.contributions.len()).all(|i| self.verify_contribution(i))
+    }
+
+    /// Verifies every contribution after the initial one.
+    pub fn verify_all(&self) -> bool {
+        self.verify_from(1)
+    }
+
+    /// Verifies every contribution after the initial one and reports each
+    /// verdict as `(index, valid)`, so a failure can be traced to the
+    /// participant who submitted it.
+    pub fn verify_all_detailed(&self) -> Vec<(usize, bool)> {
+        (1..self.contributions.len())
+            .map(|i| (i, self.verify_contribution(i)))
+            .collect()
+    }
+
     /// Finalize the ceremony and extract the powers of tau parameters
     ///
     /// This should only be called after all participants have contributed
@@ -271,13 +295,29 @@ impl<E: Pairing> Ceremony<E> {
             .contributions
             .into_iter()
             .last()
-            .ok_or_else(|| KzgError::DegreeIsZero)?;
+            .ok_or(KzgError::DegreeIsZero)?;
         Ok(PowersOfTau {
             powers_of_g: final_contribution.powers_of_g,
             powers_of_h: final_contribution.powers_of_h,
         })
     }
 
+    /// Finalize the ceremony into everything a coordinator needs for a
+    /// committee of size `n`: the powers of tau and the Lagrange powers.
+    ///
+    /// The Lagrange powers are derived from the final powers without tau (see
+    /// [`LagrangePowers::from_powers_of_tau`]), which costs O(n^2) commitments.
+    ///
+    /// # Errors
+    /// Returns an error if no contributions have been made, `n` is not a
+    /// valid committee size, or the ceremony's degree is too small for `n`
+    pub fn finalize_full(self, n: usize) -> Result<(PowersOfTau<E>, LagrangePowers<E>), SteError> {
+        let params = self.finalize()?;
+        check_params_degree(&params, n)?;
+        let lagrange_powers = LagrangePowers::from_powers_of_tau(&params, n)?;
+        Ok((params, lagrange_powers))
+    }
+
     /// Get the number of participants so far
     pub fn num_participants(&self) -> usize {
         self.contributions.len()
@@ -360,4 +400,98 @@ mod tests {
         ceremony.contributions[1].powers_of_g[2] = <E as Pairing>::G1::generator().into();
         assert!(!ceremony.verify_contribution(1));
     }
+
+    #[test]
+    fn test_verify_all_detailed_attributes_failure() {
+        let mut rng = test_rng();
+        let mut ceremony = Ceremony::<E>::new(4, &mut rng).unwrap();
+        for _ in 0..4 {
+            ceremony.contribute(&mut rng).unwrap();
+        }
+        assert!(ceremony.verify_all_detailed().iter().all(|&(_, ok)| ok));
+
+        // Contribution 2 claims a different tau in its G and H proofs
+        ceremony.contributions[2].proof_h = (ceremony.contributions[2].proof_h
+            * <E as Pairing>::ScalarField::from(2u64))
+        .into_affine();
+        let failed: Vec<usize> = ceremony
+            .verify_all_detailed()
+            .into_iter()
+            .filter(|&(_, ok)| !ok)
+            .map(|(i, _)| i)
+            .collect();
+        assert_eq!(failed, vec![2]);
+        assert_eq!(ceremony.verify_all_detailed().len(), 4);
+    }
+
+    #[test]
+    fn test_finalize_full_supports_encrypt_and_decrypt() {
+        use crate::{
+            decryption::agg_dec,
+            encryption::encrypt,
+            setup::{AggregateKey, SecretKey},
+        };
+        use ark_std::Zero;
+
+        let mut rng = test_rng();
+        let (n, t) = (8, 3);
+        let mut ceremony = Ceremony::<E>::new(n, &mut rng).unwrap();
+        for _ in 0..3 {
+            ceremony.contribute(&mut rng).unwrap();
+        }
+        assert!(ceremony.verify_all());
+        assert!(ceremony.clone().finalize_full(2 * n).is_err());
+        let (params, lagrange_powers) = ceremony.finalize_full(n).unwrap();
+
+        let mut sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::new(&mut rng)).collect();
+        sk[0].nullify();
+        let pk = sk
+            .iter()
+            .enumerate()
+            .map(|(i, sk)| sk.lagrange_get_pk(i, &lagrange_powers, n).unwrap())
+            .collect();
+        let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+        let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+
+        let mut selector = vec![false; n];
+        let mut partial_decryptions = vec![<E as Pairing>::G2::zero(); n];
+        for i in 0..=t {
+            selector[i] = true;
+            partial_decryptions[i] = sk[i].partial_decryption(&ct);
+        }
+        let dec_key = agg_dec(&partial_decryptions, &ct, &selector, &agg_key, &params).unwrap();
+        assert_eq!(dec_key, ct.enc_key);
+    }
+
+    #[test]
+    fn test_verify_from_checks_only_the_tail() {
+        let mut rng = test_rng();
+        let mut ceremony = Ceremony::<E>::new(4, &mut rng).unwrap();
+        for _ in 0..4 {
+            ceremony.contribute(&mut rng).unwrap();
+        }
+        assert!(ceremony.verify_all());
+        for k in 0..=ceremony.num_participants() {
+            assert!(ceremony.verify_from(k));
+        }
+
+        // A bad proof in contribution 1 is outside the tail from 2 on
+        let mut tampered = ceremony.clone();
+        tampered.contributions[1].proof_g = (tampered.contributions[1].proof_g
+            * <E as Pairing>::ScalarField::from(2u64))
+        .into_affine();
+        assert!(!tampered.verify_all());
+        assert!(!tampered.verify_from(1));
+        assert!(tampered.verify_from(2));
+
+        // The tail result matches checking each contribution in it
+        tampered.contributions[3].proof_h = tampered.contributions[2].proof_h;
+        for k in 2..=tampered.num_participants() {
+            let expected =
+                (k..tampered.num_participants()).all(|i| tampered.verify_contribution(i));
+            assert_eq!(tampered.verify_from(k), expected, "k={k}");
+        }
+        assert!(!tampered.verify_from(3));
+        assert!(tampered.verify_from(4));
+    }
 }
@@ -4,6 +4,29 @@ use ark_poly::{
     Radix2EvaluationDomain,
 };
 
+use crate::error::SteError;
+
+/// Creates the evaluation domain of the `n`-th roots of unity for a committee
+/// of size `n`.
+///
+/// `Radix2EvaluationDomain::new` silently rounds `n` up to the next power of
+/// two, so this rejects other sizes explicitly.
+///
+/// # Errors
+/// Returns `SteError::DomainError` if `n` is not a power of 2 or exceeds the
+/// field's two-adicity
+pub fn make_domain<F: FftField>(n: usize) -> Result<Radix2EvaluationDomain<F>, SteError> {
+    n.is_power_of_two()
+        .then(|| Radix2EvaluationDomain::<F>::new(n))
+        .flatten()
+        .ok_or_else(|| {
+            SteError::DomainError(format!(
+                "Failed to create domain for n = {} (must be a power of 2)",
+                n
+            ))
+        })
+}
+
 /// Computes the Lagrange basis polynomial L_i(x) that is 1 at omega^i and 0 elsewhere
 /// on the domain {omega^i}_{i \in [n]}.
 ///
@@ -24,8 +47,7 @@ pub fn lagrange_poly<F: FftField>(n: usize, i: usize) -> DensePolynomial<F> {
     }
 
     //powers of nth root of unity
-    let domain = Radix2EvaluationDomain::<F>::new(n)
-        .expect("n must be a power of 2 for Radix2EvaluationDomain");
+    let domain = make_domain::<F>(n).expect("n must be a power of 2 for Radix2EvaluationDomain");
     let eval_form = Evaluations::from_vec_and_domain(evals, domain);
     //interpolated polynomial over the n points
     eval_form.interpolate()
@@ -60,3 +82,79 @@ pub fn interp_mostly_zero<F: Field>(eval: F, points: &[F]) -> DensePolynomial<F>
 
     interp
 }
+
+/// Indices of the selected parties, in ascending order.
+pub fn selector_to_parties(selector: &[bool]) -> Vec<usize> {
+    (0..selector.len()).filter(|&i| selector[i]).collect()
+}
+
+/// Builds the selector for a committee of size `n` from a list of party
+/// indices, in any order.
+///
+/// # Errors
+/// Returns `SteError::InvalidParameter` if an index is not below `n` or
+/// appears twice
+pub fn parties_to_selector(parties: &[usize], n: usize) -> Result<Vec<bool>, SteError> {
+    let mut selector = vec![false; n];
+    for &i in parties {
+        if i >= n {
+            return Err(SteError::InvalidParameter(format!(
+                "party index {} out of range for n = {}",
+                i, n
+            )));
+        }
+        if selector[i] {
+            return Err(SteError::InvalidParameter(format!(
+                "party {} is listed twice",
+                i
+            )));
+        }
+        selector[i] = true;
+    }
+    Ok(selector)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bls12_381::Fr;
+
+    #[test]
+    fn test_make_domain_rejects_non_powers_of_two() {
+        assert_eq!(make_domain::<Fr>(8).unwrap().size(), 8);
+
+        for n in [0, 3, 6, 12] {
+            let err = make_domain::<Fr>(n).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                SteError::DomainError(format!(
+                    "Failed to create domain for n = {} (must be a power of 2)",
+                    n
+                ))
+                .to_string()
+            );
+        }
+    }
+
+    #[test]
+    fn test_selector_and_parties_round_trip() {
+        let selector = [true, false, false, true, true, false, false, true];
+        let parties = selector_to_parties(&selector);
+        assert_eq!(parties, vec![0, 3, 4, 7]);
+        assert_eq!(parties_to_selector(&parties, 8).unwrap(), selector);
+
+        // Order doesn't matter, and empty selections are fine both ways
+        assert_eq!(parties_to_selector(&[7, 0, 4, 3], 8).unwrap(), selector);
+        assert!(selector_to_parties(&[false; 4]).is_empty());
+        assert_eq!(parties_to_selector(&[], 4).unwrap(), vec![false; 4]);
+
+        assert!(matches!(
+            parties_to_selector(&[0, 8], 8),
+            Err(SteError::InvalidParameter(ref msg)) if msg.contains("out of range")
+        ));
+        assert!(matches!(
+            parties_to_selector(&[0, 3, 3], 8),
+            Err(SteError::InvalidParameter(ref msg)) if msg.contains("twice")
+        ));
+    }
+}
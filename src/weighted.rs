@@ -0,0 +1,318 @@
+//! Stake-weighted thresholds.
+//!
+//! A party with weight `w` owns `w` consecutive slots of an ordinary committee,
+//! all keyed with the same secret key. Slot 0 is the dummy party and slots past
+//! the last party pad the committee up to the next power of two. Padding keys
+//! are generated and immediately dropped when the aggregate key is built, so
+//! those slots can never take part in decryption.
+//!
+//! Encrypting with threshold `t` then requires participating parties whose
+//! weights sum to at least `t`: the dummy slot supplies the extra `+1` that the
+//! unweighted scheme asks for.
+
+use ark_ec::pairing::{Pairing, PairingOutput};
+use ark_std::{rand::RngCore, Zero};
+use std::ops::Range;
+
+use crate::{
+    decryption::agg_dec,
+    encryption::{encrypt, Ciphertext},
+    error::SteError,
+    kzg::PowersOfTau,
+    setup::{AggregateKey, PublicKey, SecretKey, MIN_COMMITTEE_SIZE},
+    utils::parties_to_selector,
+};
+
+/// Assignment of weighted parties to slots of the underlying committee.
+#[derive(Clone, Debug)]
+pub struct WeightedCommittee {
+    weights: Vec<usize>,
+    /// First slot of each party; slot 0 is the dummy party
+    offsets: Vec<usize>,
+    n: usize,
+}
+
+impl WeightedCommittee {
+    /// Lays out parties with the given weights.
+    ///
+    /// # Errors
+    /// Returns an error if `weights` is empty or contains a zero weight
+    pub fn new(weights: &[usize]) -> Result<Self, SteError> {
+        if weights.is_empty() {
+            return Err(SteError::InvalidParameter(
+                "weighted committee needs at least one party".to_string(),
+            ));
+        }
+        if let Some(party) = weights.iter().position(|&w| w == 0) {
+            return Err(SteError::InvalidParameter(format!(
+                "party {} has weight 0",
+                party
+            )));
+        }
+
+        let mut offsets = Vec::with_capacity(weights.len());
+        let mut next: usize = 1;
+        for &w in weights {
+            offsets.push(next);
+            next = next.checked_add(w).ok_or_else(|| {
+                SteError::InvalidParameter("total weight overflows usize".to_string())
+            })?;
+        }
+        let n = next
+            .checked_next_power_of_two()
+            .ok_or_else(|| SteError::InvalidParameter("total weight too large".to_string()))?
+            .max(MIN_COMMITTEE_SIZE);
+
+        Ok(Self {
+            weights: weights.to_vec(),
+            offsets,
+            n,
+        })
+    }
+
+    /// Size of the underlying committee (dummy, party and padding slots)
+    pub fn n(&self) -> usize {
+        self.n
+    }
+
+    /// Number of weighted parties
+    pub fn num_parties(&self) -> usize {
+        self.weights.len()
+    }
+
+    pub fn weights(&self) -> &[usize] {
+        &self.weights
+    }
+
+    /// Sum of all party weights; the largest threshold that can be used
+    pub fn total_weight(&self) -> usize {
+        self.weights.iter().sum()
+    }
+
+    /// The slot IDs owned by `party`. Pass these to
+    /// [`SecretKey::lagrange_get_pk_range`] to generate the party's keys.
+    ///
+    /// # Panics
+    /// Panics if `party >= self.num_parties()`
+    pub fn slots(&self, party: usize) -> Range<usize> {
+        self.offsets[party]..self.offsets[party] + self.weights[party]
+    }
+}
+
+/// An aggregate key over a [`WeightedCommittee`].
+#[derive(Clone, Debug)]
+pub struct WeightedAggregateKey<E: Pairing> {
+    pub committee: WeightedCommittee,
+    pub agg_key: AggregateKey<E>,
+}
+
+impl<E: Pairing> WeightedAggregateKey<E> {
+    /// Builds the aggregate key from each party's per-slot public keys.
+    ///
+    /// The dummy and padding slot keys are generated here.
+    ///
+    /// # Arguments
+    /// * `committee` - The slot layout
+    /// * `party_pks` - For each party, its public keys for `committee.slots(party)` in order
+    /// * `params` - The KZG parameters
+    /// * `rng` - Randomness for the padding keys
+    ///
+    /// # Errors
+    /// Returns an error if a party's keys don't cover exactly its slots or were
+    /// made from more than one secret key, or if aggregation fails
+    pub fn new<R: RngCore>(
+        committee: WeightedCommittee,
+        party_pks: Vec<Vec<PublicKey<E>>>,
+        params: &PowersOfTau<E>,
+        rng: &mut R,
+    ) -> Result<Self, SteError> {
+        if party_pks.len() != committee.num_parties() {
+            return Err(SteError::ValidationError(format!(
+                "expected public keys for {} parties, got {}",
+                committee.num_parties(),
+                party_pks.len()
+            )));
+        }
+
+        let n = committee.n();
+        let mut dummy = SecretKey::<E>::new(rng);
+        dummy.nullify();
+        let mut pk = vec![dummy.get_pk(0, params, n)?];
+
+        for (party, pks) in party_pks.into_iter().enumerate() {
+            let slots = committee.slots(party);
+            if !pks.iter().map(|pk| pk.id).eq(slots.clone()) {
+                return Err(SteError::ValidationError(format!(
+                    "party {} must provide keys for slots {:?}",
+                    party, slots
+                )));
+            }
+            if pks.iter().any(|pk| pk.bls_pk != pks[0].bls_pk) {
+                return Err(SteError::ValidationError(format!(
+                    "party {} used different secret keys across its slots",
+                    party
+                )));
+            }
+            pk.extend(pks);
+        }
+
+        for id in pk.len()..n {
+            pk.push(SecretKey::<E>::new(rng).get_pk(id, params, n)?);
+        }
+
+        Ok(Self {
+            agg_key: AggregateKey::new(pk, params)?,
+            committee,
+        })
+    }
+}
+
+/// Encrypts to a weighted committee so that parties of total weight at least
+/// `t` can decrypt.
+///
+/// # Errors
+/// Returns an error if `t` is 0 or exceeds the committee's total weight
+pub fn encrypt_weighted<E: Pairing, R: RngCore>(
+    wak: &WeightedAggregateKey<E>,
+    t: usize,
+    params: &PowersOfTau<E>,
+    rng: &mut R,
+) -> Result<Ciphertext<E>, SteError> {
+    let total = wak.committee.total_weight();
+    if t == 0 || t > total {
+        return Err(SteError::InvalidThreshold(format!(
+            "weighted threshold must be in [1, {}], got {}",
+            total, t
+        )));
+    }
+    encrypt(&wak.agg_key, t, params, rng)
+}
+
+/// Aggregates the partial decryptions of weighted parties.
+///
+/// # Arguments
+/// * `partial_decryptions` - One entry per party; `None` if the party did not respond
+/// * `ct` - The ciphertext to decrypt
+/// * `wak` - The weighted aggregate key
+/// * `params` - The KZG parameters
+///
+/// # Errors
+/// Returns `SteError::InvalidThreshold` if the responding parties' weights sum
+/// to less than `ct.t`, or any error from [`agg_dec`]
+pub fn agg_dec_weighted<E: Pairing>(
+    partial_decryptions: &[Option<E::G2>],
+    ct: &Ciphertext<E>,
+    wak: &WeightedAggregateKey<E>,
+    params: &PowersOfTau<E>,
+) -> Result<PairingOutput<E>, SteError> {
+    let committee = &wak.committee;
+    if partial_decryptions.len() != committee.num_parties() {
+        return Err(SteError::ValidationError(format!(
+            "partial_decryptions length ({}) must equal the number of parties ({})",
+            partial_decryptions.len(),
+            committee.num_parties()
+        )));
+    }
+
+    let weight: usize = partial_decryptions
+        .iter()
+        .zip(committee.weights())
+        .filter(|(pd, _)| pd.is_some())
+        .map(|(_, &w)| w)
+        .sum();
+    if weight < ct.t {
+        return Err(SteError::InvalidThreshold(format!(
+            "participating weight {} is below the threshold {}",
+            weight, ct.t
+        )));
+    }
+
+    let n = committee.n();
+    let mut pds = vec![E::G2::zero(); n];
+
+    // The dummy party's secret key is 1
+    let mut slots = vec![0];
+    pds[0] = ct.gamma_g2;
+
+    for (party, pd) in partial_decryptions.iter().enumerate() {
+        if let Some(pd) = pd {
+            for slot in committee.slots(party) {
+                slots.push(slot);
+                pds[slot] = *pd;
+            }
+        }
+    }
+    let selector = parties_to_selector(&slots, n)?;
+
+    agg_dec(&pds, ct, &selector, &wak.agg_key, params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{kzg::KZG10, setup::LagrangePowers};
+    use ark_poly::univariate::DensePolynomial;
+    use ark_std::UniformRand;
+
+    type E = ark_bls12_381::Bls12_381;
+    type Fr = <E as Pairing>::ScalarField;
+    type UniPoly381 = DensePolynomial<Fr>;
+
+    #[test]
+    fn test_weighted_committee_layout() {
+        let committee = WeightedCommittee::new(&[2, 2, 1]).unwrap();
+        assert_eq!(committee.n(), 8);
+        assert_eq!(committee.total_weight(), 5);
+        assert_eq!(committee.slots(0), 1..3);
+        assert_eq!(committee.slots(1), 3..5);
+        assert_eq!(committee.slots(2), 5..6);
+
+        assert!(WeightedCommittee::new(&[]).is_err());
+        assert!(WeightedCommittee::new(&[1, 0]).is_err());
+    }
+
+    #[test]
+    fn test_weighted_decryption() {
+        let mut rng = ark_std::test_rng();
+        let committee = WeightedCommittee::new(&[2, 2, 1]).unwrap();
+        let n = committee.n();
+        let tau = Fr::rand(&mut rng);
+        let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+        let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+        let sk: Vec<SecretKey<E>> = (0..committee.num_parties())
+            .map(|_| SecretKey::new(&mut rng))
+            .collect();
+        let party_pks = sk
+            .iter()
+            .enumerate()
+            .map(|(party, sk)| {
+                let slots: Vec<usize> = committee.slots(party).collect();
+                sk.lagrange_get_pk_range(&slots, &lagrange_params, n)
+                    .unwrap()
+            })
+            .collect();
+        let wak = WeightedAggregateKey::new(committee, party_pks, &params, &mut rng).unwrap();
+
+        let ct = encrypt_weighted(&wak, 4, &params, &mut rng).unwrap();
+        let partials = |parties: &[usize]| -> Vec<Option<<E as Pairing>::G2>> {
+            (0..sk.len())
+                .map(|p| parties.contains(&p).then(|| sk[p].partial_decryption(&ct)))
+                .collect()
+        };
+
+        // Weight 2 + 2 meets the threshold
+        let dec_key = agg_dec_weighted(&partials(&[0, 1]), &ct, &wak, &params).unwrap();
+        assert_eq!(dec_key, ct.enc_key);
+        let dec_key = agg_dec_weighted(&partials(&[0, 1, 2]), &ct, &wak, &params).unwrap();
+        assert_eq!(dec_key, ct.enc_key);
+
+        // Weight 2 + 1 does not
+        for parties in [[0, 2], [1, 2]] {
+            let err = agg_dec_weighted(&partials(&parties), &ct, &wak, &params).unwrap_err();
+            assert!(matches!(err, SteError::InvalidThreshold(_)), "{err:?}");
+        }
+
+        assert!(encrypt_weighted(&wak, 6, &params, &mut rng).is_err());
+    }
+}
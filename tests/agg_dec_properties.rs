@@ -0,0 +1,145 @@
+//! Property tests for the quorum rules of `agg_dec`.
+//!
+//! Committees of size 4, 8 and 16 are set up from a seed with every
+//! threshold `1 <= t < n`, and selectors are drawn around the `t + 1`
+//! boundary with and without the dummy party 0.
+
+use ark_ec::pairing::Pairing;
+use ark_poly::univariate::DensePolynomial;
+use ark_std::{
+    rand::{rngs::StdRng, SeedableRng},
+    UniformRand, Zero,
+};
+use proptest::prelude::*;
+use proptest::sample::subsequence;
+use silent_threshold_encryption::{
+    decryption::{agg_dec, agg_dec_with},
+    encryption::{encrypt, Ciphertext},
+    kzg::{PowersOfTau, KZG10},
+    setup::{AggregateKey, LagrangePowers, SecretKey},
+};
+
+type E = ark_bls12_381::Bls12_381;
+type Fr = <E as Pairing>::ScalarField;
+type G2 = <E as Pairing>::G2;
+type UniPoly381 = DensePolynomial<Fr>;
+
+struct Committee {
+    params: PowersOfTau<E>,
+    sk: Vec<SecretKey<E>>,
+    agg_key: AggregateKey<E>,
+    ct: Ciphertext<E>,
+}
+
+/// Sets up a committee of `n` and encrypts to it with threshold `t`.
+fn committee(n: usize, t: usize, seed: u64) -> Committee {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let tau = Fr::rand(&mut rng);
+    let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+    let lagrange_params = LagrangePowers::<E>::new(tau, n).unwrap();
+
+    let mut sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::new(&mut rng)).collect();
+    sk[0].nullify();
+    let pk = sk
+        .iter()
+        .enumerate()
+        .map(|(i, sk)| sk.lagrange_get_pk(i, &lagrange_params, n).unwrap())
+        .collect();
+    let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+    let ct = encrypt::<E, _>(&agg_key, t, &params, &mut rng).unwrap();
+
+    Committee {
+        params,
+        sk,
+        agg_key,
+        ct,
+    }
+}
+
+/// Honest partial decryptions from `parties`, with the matching selector.
+fn partials(c: &Committee, parties: &[usize]) -> (Vec<G2>, Vec<bool>) {
+    let n = c.sk.len();
+    let mut partial_decryptions = vec![G2::zero(); n];
+    let mut selector = vec![false; n];
+    for &i in parties {
+        selector[i] = true;
+        partial_decryptions[i] = c.sk[i].partial_decryption(&c.ct);
+    }
+    (partial_decryptions, selector)
+}
+
+/// `(n, t)` with `n` a power of two and `1 <= t < n`.
+fn n_and_t() -> impl Strategy<Value = (usize, usize)> {
+    prop_oneof![Just(4usize), Just(8), Just(16)].prop_flat_map(|n| (Just(n), 1..n))
+}
+
+/// Party 0 plus between `t` and `n - 1` of the other parties.
+fn quorum() -> impl Strategy<Value = (usize, usize, Vec<usize>)> {
+    n_and_t().prop_flat_map(|(n, t)| {
+        subsequence((1..n).collect::<Vec<_>>(), t..n).prop_map(move |mut others| {
+            others.insert(0, 0);
+            (n, t, others)
+        })
+    })
+}
+
+/// Party 0 plus at most `t - 1` of the other parties.
+fn short_quorum() -> impl Strategy<Value = (usize, usize, Vec<usize>)> {
+    n_and_t().prop_flat_map(|(n, t)| {
+        subsequence((1..n).collect::<Vec<_>>(), 0..t).prop_map(move |mut others| {
+            others.insert(0, 0);
+            (n, t, others)
+        })
+    })
+}
+
+/// Between `t + 1` and `n - 1` parties, none of them party 0.
+fn quorum_without_dummy() -> impl Strategy<Value = (usize, usize, Vec<usize>)> {
+    n_and_t()
+        .prop_filter("needs t + 1 non-dummy parties", |&(n, t)| t + 1 < n)
+        .prop_flat_map(|(n, t)| {
+            subsequence((1..n).collect::<Vec<_>>(), t + 1..n).prop_map(move |p| (n, t, p))
+        })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn honest_quorum_recovers_key((n, t, parties) in quorum(), seed in any::<u64>()) {
+        let c = committee(n, t, seed);
+        let (partial_decryptions, selector) = partials(&c, &parties);
+        let dec_key = agg_dec(&partial_decryptions, &c.ct, &selector, &c.agg_key, &c.params);
+        prop_assert_eq!(dec_key.unwrap(), c.ct.enc_key);
+    }
+
+    #[test]
+    fn fixed_selector_agrees_with_agg_dec((n, t, parties) in quorum(), seed in any::<u64>()) {
+        let c = committee(n, t, seed);
+        let (partial_decryptions, selector) = partials(&c, &parties);
+        let key = c.agg_key.fix_selector(&selector, &c.params).unwrap();
+        let dec_key = agg_dec_with(&partial_decryptions, &c.ct, &key, &c.params);
+        prop_assert_eq!(dec_key.unwrap(), c.ct.enc_key);
+    }
+
+    #[test]
+    fn quorum_below_threshold_fails((n, t, parties) in short_quorum(), seed in any::<u64>()) {
+        let c = committee(n, t, seed);
+        let (partial_decryptions, selector) = partials(&c, &parties);
+        prop_assert!(
+            agg_dec(&partial_decryptions, &c.ct, &selector, &c.agg_key, &c.params).is_err()
+        );
+    }
+
+    #[test]
+    fn quorum_without_dummy_fails(
+        (n, t, parties) in quorum_without_dummy(),
+        seed in any::<u64>(),
+    ) {
+        let c = committee(n, t, seed);
+        let (partial_decryptions, selector) = partials(&c, &parties);
+        prop_assert!(
+            agg_dec(&partial_decryptions, &c.ct, &selector, &c.agg_key, &c.params).is_err()
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use ark_ec::pairing::Pairing;
+use ark_poly::univariate::DensePolynomial;
+use ark_std::UniformRand;
+use silent_threshold_encryption::{container, kzg::KZG10};
+use std::process::Command;
+
+type E = ark_bls12_381::Bls12_381;
+type Fr = <E as Pairing>::ScalarField;
+type UniPoly381 = DensePolynomial<Fr>;
+
+/// Runs `bench_decrypt` and returns the `n` column of its table.
+fn run(args: &[&str]) -> Vec<usize> {
+    let output = Command::new(env!("CARGO_BIN_EXE_bench_decrypt"))
+        .args(args)
+        .output()
+        .unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(
+        output.status.success(),
+        "bench_decrypt failed:\n{}\n{}",
+        stdout,
+        String::from_utf8_lossy(&output.stderr)
+    );
+    stdout
+        .lines()
+        .filter_map(|line| line.split_whitespace().next()?.parse().ok())
+        .collect()
+}
+
+#[test]
+fn test_bench_decrypt_prints_a_row_per_size() {
+    assert_eq!(run(&["--sizes", "8,16"]), vec![8, 16]);
+}
+
+#[test]
+fn test_bench_decrypt_reuses_params_file() {
+    let mut rng = ark_std::test_rng();
+    let params = KZG10::<E, UniPoly381>::setup(8, Fr::rand(&mut rng)).unwrap();
+    let path =
+        std::env::temp_dir().join(format!("bench_decrypt_params_{}.bin", std::process::id()));
+    container::save_file(&params, &path).unwrap();
+
+    let rows = run(&["--sizes", "8", "--params-file", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(rows, vec![8]);
+}
@@ -4,16 +4,25 @@ use web_sys::{MessageEvent, WebSocket, ErrorEvent, CloseEvent};
 use ark_bls12_381::Bls12_381 as E;
 use ark_ec::pairing::Pairing;
 use ark_serialize::{CanonicalSerialize, CanonicalDeserialize};
+use ark_std::rand::RngCore;
 use blake2::{Blake2b512, Digest};
 use serde::{Serialize, Deserialize};
+use chacha20poly1305::{
+    aead::{Aead, KeyInit, Payload},
+    ChaCha20Poly1305, Key, Nonce,
+};
 use silent_threshold_encryption::{
     setup::{SecretKey, LagrangePowers},
-    encryption::Ciphertext,
+    encryption::{Ciphertext, Encapsulation},
+    security::DefaultKdf,
 };
-use crate::WasmRng;
+use silent_threshold_encryption::rng::SecureRng;
 
 type G2 = <E as Pairing>::G2;
 
+/// Size in bytes of the ChaCha20-Poly1305 nonce used by `encryptData`
+const AEAD_NONCE_SIZE: usize = 12;
+
 /// Messages sent from coordinator to parties (matches the Rust protocol)
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum CoordinatorMessage {
@@ -226,7 +235,7 @@ impl DistributedParty {
         self.log_status("Generating secret key...");
 
         // Generate secret key
-        let mut rng = WasmRng;
+        let mut rng = SecureRng::new();
         let mut sk = SecretKey::<E>::new(&mut rng);
 
         // Nullify party 0 (dummy party)
@@ -308,7 +317,7 @@ impl DistributedParty {
         self.log_status("Computing partial decryption...");
 
         // Deserialize ciphertext
-        let ct = Ciphertext::<E>::deserialize_compressed(ct_bytes_js)
+        let ct = Ciphertext::<E>::deserialize_validated(ct_bytes_js)
             .map_err(|e| JsValue::from_str(&format!("Failed to deserialize ciphertext: {:?}", e)))?;
 
         // Get secret key
@@ -391,7 +400,7 @@ pub fn encrypt_message(
     let kzg_params = PowersOfTau::<E>::deserialize_compressed(kzg_params_bytes)
         .map_err(|e| JsValue::from_str(&format!("Failed to deserialize KZG params: {:?}", e)))?;
 
-    let mut rng = WasmRng;
+    let mut rng = SecureRng::new();
     let ct = encrypt::<E, _>(&agg_key, threshold, &kzg_params, &mut rng)
         .map_err(|e| JsValue::from_str(&format!("Failed to encrypt: {:?}", e)))?;
 
@@ -421,7 +430,7 @@ pub fn decrypt_message(
 
     web_sys::console::log_1(&"Decrypting message...".into());
 
-    let ct = Ciphertext::<E>::deserialize_compressed(ciphertext_bytes)
+    let ct = Ciphertext::<E>::deserialize_validated(ciphertext_bytes)
         .map_err(|e| JsValue::from_str(&format!("Failed to deserialize ciphertext: {:?}", e)))?;
 
     let agg_key = AggregateKey::<E>::deserialize_compressed(agg_key_bytes)
@@ -430,24 +439,8 @@ pub fn decrypt_message(
     let kzg_params = PowersOfTau::<E>::deserialize_compressed(kzg_params_bytes)
         .map_err(|e| JsValue::from_str(&format!("Failed to deserialize KZG params: {:?}", e)))?;
 
-    let _n = selector.length() as usize;
-    let mut partial_decs = Vec::new();
-    for i in 0..partial_decryptions_bytes.length() {
-        let pd_js = partial_decryptions_bytes.get(i);
-        let pd_bytes: Vec<u8> = serde_wasm_bindgen::from_value(pd_js)
-            .map_err(|e| JsValue::from_str(&format!("Failed to convert partial decryption {}: {:?}", i, e)))?;
-        let pd = G2::deserialize_compressed(&*pd_bytes)
-            .map_err(|e| JsValue::from_str(&format!("Failed to deserialize partial decryption {}: {:?}", i, e)))?;
-        partial_decs.push(pd);
-    }
-
-    // Convert selector from JS array to Vec<bool>
-    let mut selector_vec = Vec::new();
-    for i in 0..selector.length() {
-        let val = selector.get(i);
-        let bool_val: bool = val.as_bool().unwrap_or(false);
-        selector_vec.push(bool_val);
-    }
+    let partial_decs = parse_partial_decryptions(partial_decryptions_bytes)?;
+    let selector_vec = parse_selector(selector);
 
     let dec_key = agg_dec(&partial_decs, &ct, &selector_vec, &agg_key, &kzg_params)
         .map_err(|e| JsValue::from_str(&format!("Failed to aggregate decrypt: {:?}", e)))?;
@@ -465,3 +458,161 @@ pub fn decrypt_message(
 
     Ok(result)
 }
+
+/// Result of `encryptData`: the threshold encapsulation plus the AEAD box it protects
+#[wasm_bindgen]
+pub struct EncryptedData {
+    ciphertext: Vec<u8>,
+    aead_ciphertext: Vec<u8>,
+    nonce: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl EncryptedData {
+    /// Serialized threshold encapsulation, needed by parties for partial
+    /// decryption. It does not contain the key, which only a quorum recovers
+    #[wasm_bindgen(getter)]
+    pub fn ciphertext(&self) -> Vec<u8> {
+        self.ciphertext.clone()
+    }
+
+    /// ChaCha20-Poly1305 encryption of the payload
+    #[wasm_bindgen(getter, js_name = aeadCiphertext)]
+    pub fn aead_ciphertext(&self) -> Vec<u8> {
+        self.aead_ciphertext.clone()
+    }
+
+    /// Nonce used for the AEAD encryption
+    #[wasm_bindgen(getter)]
+    pub fn nonce(&self) -> Vec<u8> {
+        self.nonce.clone()
+    }
+}
+
+/// Encrypt arbitrary data to the committee
+///
+/// A fresh key is encapsulated to the committee and used to seal `message` with
+/// ChaCha20-Poly1305. Only the encapsulation, without the key, is returned; it
+/// is bound to the AEAD box as associated data.
+#[wasm_bindgen(js_name = encryptData)]
+pub fn encrypt_data(
+    message: &[u8],
+    agg_key_bytes: &[u8],
+    threshold: usize,
+    kzg_params_bytes: &[u8],
+) -> Result<EncryptedData, JsValue> {
+    use silent_threshold_encryption::{
+        setup::AggregateKey,
+        encryption::encapsulate,
+        kzg::PowersOfTau,
+    };
+
+    let agg_key = AggregateKey::<E>::deserialize_compressed(agg_key_bytes)
+        .map_err(|e| JsValue::from_str(&format!("Failed to deserialize aggregate key: {:?}", e)))?;
+
+    let kzg_params = PowersOfTau::<E>::deserialize_compressed(kzg_params_bytes)
+        .map_err(|e| JsValue::from_str(&format!("Failed to deserialize KZG params: {:?}", e)))?;
+
+    let mut rng = SecureRng::new();
+    let (enc, recovered) = encapsulate::<E, _>(&agg_key, threshold, &kzg_params, &mut rng)
+        .map_err(|e| JsValue::from_str(&format!("Failed to encrypt: {:?}", e)))?;
+
+    let mut ct_bytes = Vec::new();
+    enc.serialize_compressed(&mut ct_bytes)
+        .map_err(|e| JsValue::from_str(&format!("Failed to serialize encapsulation: {:?}", e)))?;
+
+    let key = recovered.derive_symmetric_key::<DefaultKdf>();
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&*key));
+
+    let mut nonce = vec![0u8; AEAD_NONCE_SIZE];
+    rng.fill_bytes(&mut nonce);
+
+    let aead_ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: message, aad: &ct_bytes })
+        .map_err(|_| JsValue::from_str("Failed to encrypt data"))?;
+
+    web_sys::console::log_1(&format!("Encrypted {} bytes of data", message.len()).into());
+
+    Ok(EncryptedData {
+        ciphertext: ct_bytes,
+        aead_ciphertext,
+        nonce,
+    })
+}
+
+/// Decrypt data produced by `encryptData`
+///
+/// Recovers the encapsulated key via `agg_dec_encapsulated`, derives the symmetric key and
+/// opens the AEAD box. Fails if the partial decryptions are insufficient or the
+/// box was tampered with.
+#[wasm_bindgen(js_name = decryptData)]
+pub fn decrypt_data(
+    ciphertext_bytes: &[u8],
+    aead_ciphertext: &[u8],
+    nonce: &[u8],
+    partial_decryptions_bytes: &js_sys::Array,
+    selector: &js_sys::Array,
+    agg_key_bytes: &[u8],
+    kzg_params_bytes: &[u8],
+) -> Result<Vec<u8>, JsValue> {
+    use silent_threshold_encryption::{
+        setup::AggregateKey,
+        decryption::agg_dec_encapsulated,
+        kzg::PowersOfTau,
+    };
+
+    if nonce.len() != AEAD_NONCE_SIZE {
+        return Err(JsValue::from_str(&format!(
+            "Nonce must be {} bytes, got {}",
+            AEAD_NONCE_SIZE,
+            nonce.len()
+        )));
+    }
+
+    let enc = Encapsulation::<E>::deserialize_validated(ciphertext_bytes)
+        .map_err(|e| JsValue::from_str(&format!("Failed to deserialize encapsulation: {:?}", e)))?;
+
+    let agg_key = AggregateKey::<E>::deserialize_compressed(agg_key_bytes)
+        .map_err(|e| JsValue::from_str(&format!("Failed to deserialize aggregate key: {:?}", e)))?;
+
+    let kzg_params = PowersOfTau::<E>::deserialize_compressed(kzg_params_bytes)
+        .map_err(|e| JsValue::from_str(&format!("Failed to deserialize KZG params: {:?}", e)))?;
+
+    let partial_decs = parse_partial_decryptions(partial_decryptions_bytes)?;
+    let selector_vec = parse_selector(selector);
+
+    let recovered = agg_dec_encapsulated(&partial_decs, &enc, &selector_vec, &agg_key, &kzg_params)
+        .map_err(|e| JsValue::from_str(&format!("Failed to aggregate decrypt: {:?}", e)))?;
+
+    let key = recovered.derive_symmetric_key::<DefaultKdf>();
+    let cipher = ChaCha20Poly1305::new(Key::from_slice(&*key));
+
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: aead_ciphertext, aad: ciphertext_bytes })
+        .map_err(|_| JsValue::from_str("Failed to decrypt data: authentication failed"))?;
+
+    web_sys::console::log_1(&format!("Decrypted {} bytes of data", plaintext.len()).into());
+
+    Ok(plaintext)
+}
+
+/// Convert a JavaScript array of Uint8Array into G2 partial decryptions
+fn parse_partial_decryptions(partial_decryptions_bytes: &js_sys::Array) -> Result<Vec<G2>, JsValue> {
+    let mut partial_decs = Vec::new();
+    for i in 0..partial_decryptions_bytes.length() {
+        let pd_js = partial_decryptions_bytes.get(i);
+        let pd_bytes: Vec<u8> = serde_wasm_bindgen::from_value(pd_js)
+            .map_err(|e| JsValue::from_str(&format!("Failed to convert partial decryption {}: {:?}", i, e)))?;
+        let pd = G2::deserialize_compressed(&*pd_bytes)
+            .map_err(|e| JsValue::from_str(&format!("Failed to deserialize partial decryption {}: {:?}", i, e)))?;
+        partial_decs.push(pd);
+    }
+    Ok(partial_decs)
+}
+
+/// Convert a JavaScript array of booleans into a selector
+fn parse_selector(selector: &js_sys::Array) -> Vec<bool> {
+    (0..selector.length())
+        .map(|i| selector.get(i).as_bool().unwrap_or(false))
+        .collect()
+}
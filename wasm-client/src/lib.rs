@@ -11,12 +11,13 @@ use ark_bls12_381::Bls12_381 as E;
 use ark_ec::pairing::Pairing;
 use ark_poly::univariate::DensePolynomial;
 use ark_serialize::{CanonicalSerialize, CanonicalDeserialize};
-use ark_std::{rand::RngCore, UniformRand};
+use ark_std::UniformRand;
 use silent_threshold_encryption::{
-    setup::{SecretKey, PublicKey, LagrangePowers, AggregateKey},
-    encryption::{encrypt, Ciphertext},
+    setup::{SecretKey, PublicKey, LagrangePowers, AggregateKey, CommitteeSizeLimit},
+    encryption::{encrypt, Ciphertext, Encapsulation},
     decryption::agg_dec,
     kzg::{KZG10, PowersOfTau},
+    rng::SecureRng,
     trusted_setup::Ceremony,
 };
 use serde::{Serialize, Deserialize};
@@ -42,32 +43,6 @@ macro_rules! console_log {
     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 }
 
-/// WebAssembly-friendly RNG using browser's crypto.getRandomValues
-pub(crate) struct WasmRng;
-
-impl RngCore for WasmRng {
-    fn next_u32(&mut self) -> u32 {
-        getrandom::u32().expect("Failed to get random u32")
-    }
-
-    fn next_u64(&mut self) -> u64 {
-        getrandom::u64().expect("Failed to get random u64")
-    }
-
-    fn fill_bytes(&mut self, dest: &mut [u8]) {
-        getrandom::fill(dest).expect("Failed to get random bytes");
-    }
-
-    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), ark_std::rand::Error> {
-        match getrandom::fill(dest) {
-            Ok(()) => Ok(()),
-            Err(_) => Err(ark_std::rand::Error::from(
-                core::num::NonZero::new(1u32).unwrap()
-            )),
-        }
-    }
-}
-
 /// Serializable wrapper for PowersOfTau
 #[derive(Serialize, Deserialize)]
 pub struct SerializablePowersOfTau {
@@ -107,7 +82,7 @@ impl Party {
     /// Create a new party with a random secret key
     #[wasm_bindgen(constructor)]
     pub fn new(id: usize) -> Result<Party, JsValue> {
-        let mut rng = WasmRng;
+        let mut rng = SecureRng::new();
         let mut sk = SecretKey::<E>::new(&mut rng);
 
         // Nullify party 0 (dummy party)
@@ -152,16 +127,23 @@ impl Party {
         Ok(pk_bytes)
     }
 
-    /// Compute partial decryption for a ciphertext
+    /// Compute partial decryption for a ciphertext, or for the encapsulation
+    /// returned by `encryptData`
     #[wasm_bindgen(js_name = partialDecrypt)]
     pub fn partial_decrypt(&self, ciphertext_bytes: &[u8]) -> Result<Vec<u8>, JsValue> {
         let sk = SecretKey::<E>::deserialize_compressed(&*self.secret_key)
             .map_err(|e| JsValue::from_str(&format!("Failed to deserialize secret key: {:?}", e)))?;
 
-        let ct = Ciphertext::<E>::deserialize_compressed(ciphertext_bytes)
-            .map_err(|e| JsValue::from_str(&format!("Failed to deserialize ciphertext: {:?}", e)))?;
-
-        let partial_dec = sk.partial_decryption(&ct);
+        // Validated deserialization rejects trailing bytes, so the two forms
+        // cannot be confused
+        let partial_dec = match Ciphertext::<E>::deserialize_validated(ciphertext_bytes) {
+            Ok(ct) => sk.partial_decryption(&ct),
+            Err(_) => {
+                let enc = Encapsulation::<E>::deserialize_validated(ciphertext_bytes)
+                    .map_err(|e| JsValue::from_str(&format!("Failed to deserialize ciphertext: {:?}", e)))?;
+                sk.partial_decryption_encapsulated(&enc)
+            }
+        };
 
         let mut partial_dec_bytes = Vec::new();
         partial_dec.serialize_compressed(&mut partial_dec_bytes)
@@ -205,8 +187,11 @@ impl Coordinator {
         if !n.is_power_of_two() {
             return Err(JsValue::from_str("n must be a power of 2"));
         }
+        CommitteeSizeLimit::default()
+            .check(n)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-        let mut rng = WasmRng;
+        let mut rng = SecureRng::new();
 
         // Generate tau (WARNING: single-party setup - insecure for production)
         let tau = Fr::rand(&mut rng);
@@ -281,7 +266,7 @@ impl Coordinator {
             let pk_js = public_keys_bytes.get(i);
             let pk_bytes: Vec<u8> = serde_wasm_bindgen::from_value(pk_js)
                 .map_err(|e| JsValue::from_str(&format!("Failed to convert public key {}: {:?}", i, e)))?;
-            let pk = PublicKey::<E>::deserialize_compressed(&*pk_bytes)
+            let pk = PublicKey::<E>::deserialize_validated(&pk_bytes)
                 .map_err(|e| JsValue::from_str(&format!("Failed to deserialize public key {}: {:?}", i, e)))?;
             pks.push(pk);
         }
@@ -305,7 +290,7 @@ impl Coordinator {
         let kzg_params = PowersOfTau::<E>::deserialize_compressed(&*self.kzg_params)
             .map_err(|e| JsValue::from_str(&format!("Failed to deserialize KZG params: {:?}", e)))?;
 
-        let mut rng = WasmRng;
+        let mut rng = SecureRng::new();
         let ct = encrypt::<E, _>(&agg_key, threshold, &kzg_params, &mut rng)
             .map_err(|e| JsValue::from_str(&format!("Failed to encrypt: {:?}", e)))?;
 
@@ -344,7 +329,7 @@ impl Coordinator {
             )));
         }
 
-        let ct = Ciphertext::<E>::deserialize_compressed(ciphertext_bytes)
+        let ct = Ciphertext::<E>::deserialize_validated(ciphertext_bytes)
             .map_err(|e| JsValue::from_str(&format!("Failed to deserialize ciphertext: {:?}", e)))?;
 
         let agg_key = AggregateKey::<E>::deserialize_compressed(agg_key_bytes)
@@ -389,6 +374,10 @@ impl Coordinator {
 pub struct TrustedSetupCeremony {
     ceremony: Vec<u8>,
     max_degree: usize,
+    /// Contributions `0..verified_up_to` have already been verified
+    verified_up_to: usize,
+    /// Indices that failed the last `verifyAll`
+    failed: Vec<usize>,
 }
 
 #[wasm_bindgen]
@@ -401,7 +390,7 @@ impl TrustedSetupCeremony {
     pub fn new(max_degree: usize) -> Result<TrustedSetupCeremony, JsValue> {
         console_log!("Initializing trusted setup ceremony with max_degree={}", max_degree);
 
-        let mut rng = WasmRng;
+        let mut rng = SecureRng::new();
         let ceremony = Ceremony::<E>::new(max_degree, &mut rng)
             .map_err(|e| JsValue::from_str(&format!("Failed to initialize ceremony: {:?}", e)))?;
 
@@ -414,6 +403,8 @@ impl TrustedSetupCeremony {
         Ok(TrustedSetupCeremony {
             ceremony: ceremony_bytes,
             max_degree,
+            verified_up_to: 1,
+            failed: Vec::new(),
         })
     }
 
@@ -429,6 +420,27 @@ impl TrustedSetupCeremony {
         Ok(TrustedSetupCeremony {
             ceremony: ceremony_bytes.to_vec(),
             max_degree,
+            verified_up_to: 1,
+            failed: Vec::new(),
+        })
+    }
+
+    /// Load a ceremony state together with its persisted verification progress
+    ///
+    /// `verified_up_to` must come from `verifiedUpTo()` on this participant's
+    /// own copy of the ceremony, saved alongside `exportState()`. Contributions
+    /// below it are not checked again, so never pass a value received with
+    /// state from someone else; use `fromBytes` for that.
+    #[wasm_bindgen]
+    pub fn resume(ceremony_bytes: &[u8], max_degree: usize, verified_up_to: usize) -> Result<TrustedSetupCeremony, JsValue> {
+        let ceremony = Ceremony::<E>::deserialize_compressed(ceremony_bytes)
+            .map_err(|e| JsValue::from_str(&format!("Invalid ceremony data: {:?}", e)))?;
+
+        Ok(TrustedSetupCeremony {
+            ceremony: ceremony_bytes.to_vec(),
+            max_degree,
+            verified_up_to: verified_up_to.min(ceremony.num_participants()).max(1),
+            failed: Vec::new(),
         })
     }
 
@@ -446,7 +458,7 @@ impl TrustedSetupCeremony {
         let participant_id = ceremony.num_participants();
         console_log!("Contributing as participant #{}", participant_id);
 
-        let mut rng = WasmRng;
+        let mut rng = SecureRng::new();
         ceremony.contribute(&mut rng)
             .map_err(|e| JsValue::from_str(&format!("Failed to contribute: {:?}", e)))?;
 
@@ -465,29 +477,54 @@ impl TrustedSetupCeremony {
 
     /// Verify all contributions in the ceremony
     ///
-    /// Returns true if all contributions are valid
+    /// Contributions verified by an earlier call are skipped, so only ones
+    /// added since are checked. Returns true if all contributions are valid;
+    /// otherwise `failedContributions()` lists the participants responsible
     #[wasm_bindgen(js_name = verifyAll)]
-    pub fn verify_all(&self) -> Result<bool, JsValue> {
+    pub fn verify_all(&mut self) -> Result<bool, JsValue> {
         console_log!("Verifying all contributions...");
 
         let ceremony = Ceremony::<E>::deserialize_compressed(&*self.ceremony)
             .map_err(|e| JsValue::from_str(&format!("Failed to deserialize ceremony: {:?}", e)))?;
 
         let num_participants = ceremony.num_participants();
-        console_log!("Verifying {} contributions...", num_participants - 1);
-
-        for i in 1..num_participants {
-            if !ceremony.verify_contribution(i) {
+        console_log!("Verifying contributions {} to {}...", self.verified_up_to, num_participants - 1);
+
+        self.failed.clear();
+        for i in self.verified_up_to..num_participants {
+            if ceremony.verify_contribution(i) {
+                console_log!("✓ Contribution {} verified", i);
+                if self.failed.is_empty() {
+                    self.verified_up_to = i + 1;
+                }
+            } else {
                 console_log!("✗ Contribution {} failed verification", i);
-                return Ok(false);
+                self.failed.push(i);
             }
-            console_log!("✓ Contribution {} verified", i);
         }
 
+        if !self.failed.is_empty() {
+            console_log!("✗ Failed contributions: {:?}", self.failed);
+            return Ok(false);
+        }
         console_log!("✓ All contributions verified successfully");
         Ok(true)
     }
 
+    /// Indices of the contributions that failed the last `verifyAll`, i.e.
+    /// the participants who submitted them
+    #[wasm_bindgen(js_name = failedContributions)]
+    pub fn failed_contributions(&self) -> Vec<usize> {
+        self.failed.clone()
+    }
+
+    /// Number of leading contributions already verified; persist it with
+    /// `exportState()` and pass both to `resume`
+    #[wasm_bindgen(js_name = verifiedUpTo)]
+    pub fn verified_up_to(&self) -> usize {
+        self.verified_up_to
+    }
+
     /// Get the number of participants so far
     #[wasm_bindgen(js_name = numParticipants)]
     pub fn num_participants(&self) -> Result<usize, JsValue> {
@@ -536,6 +573,7 @@ impl TrustedSetupCeremony {
             "max_degree": self.max_degree,
             "num_participants": ceremony.num_participants(),
             "state_size_bytes": self.ceremony.len(),
+            "verified_up_to": self.verified_up_to,
         });
 
         Ok(stats.to_string())
@@ -569,6 +607,76 @@ pub fn coordinator_from_trusted_setup(
     ));
 }
 
+/// Milliseconds from the page's high-resolution clock, if there is one
+fn now_ms() -> Option<f64> {
+    web_sys::window().and_then(|w| w.performance()).map(|p| p.now())
+}
+
+/// Run the whole protocol in one call: setup, keygen, encrypt, partial
+/// decryptions from a quorum of `t + 1` parties, and `agg_dec`.
+///
+/// Returns JSON `{success, ciphertext_bytes, decrypt_ms}`, where
+/// `decrypt_ms` times `agg_dec` alone. Randomness comes from `SecureRng`,
+/// i.e. `crypto.getRandomValues()` in the browser.
+///
+/// **WARNING**: This samples tau locally and is for demos only.
+#[wasm_bindgen(js_name = runDemo)]
+pub fn run_demo(n: usize, t: usize) -> Result<String, JsValue> {
+    if !is_power_of_two(n) {
+        return Err(JsValue::from_str("n must be a power of 2"));
+    }
+    if t == 0 || t >= n {
+        return Err(JsValue::from_str(&format!("t must be in [1, {}), got {}", n, t)));
+    }
+    CommitteeSizeLimit::default()
+        .check(n)
+        .map_err(|e| JsValue::from_str(&e.to_string()))?;
+
+    let mut rng = SecureRng::new();
+    let tau = Fr::rand(&mut rng);
+    let kzg_params = KZG10::<E, UniPoly381>::setup(n, tau)
+        .map_err(|e| JsValue::from_str(&format!("Failed to setup KZG: {:?}", e)))?;
+    let lagrange_params = LagrangePowers::<E>::new(tau, n)
+        .map_err(|e| JsValue::from_str(&format!("Failed to create Lagrange powers: {:?}", e)))?;
+
+    let mut sk: Vec<SecretKey<E>> = (0..n).map(|_| SecretKey::new(&mut rng)).collect();
+    sk[0].nullify();
+    let pk = sk
+        .iter()
+        .enumerate()
+        .map(|(i, sk)| sk.lagrange_get_pk(i, &lagrange_params, n))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| JsValue::from_str(&format!("Failed to compute public keys: {:?}", e)))?;
+    let agg_key = AggregateKey::<E>::new(pk, &kzg_params)
+        .map_err(|e| JsValue::from_str(&format!("Failed to aggregate keys: {:?}", e)))?;
+
+    let ct = encrypt::<E, _>(&agg_key, t, &kzg_params, &mut rng)
+        .map_err(|e| JsValue::from_str(&format!("Failed to encrypt: {:?}", e)))?;
+
+    let mut selector = vec![false; n];
+    let mut partial_decs = vec![<E as Pairing>::G2::default(); n];
+    for i in 0..=t {
+        selector[i] = true;
+        partial_decs[i] = sk[i].partial_decryption(&ct);
+    }
+
+    let started = now_ms();
+    let dec_key = agg_dec(&partial_decs, &ct, &selector, &agg_key, &kzg_params)
+        .map_err(|e| JsValue::from_str(&format!("Failed to aggregate decrypt: {:?}", e)))?;
+    let decrypt_ms = match (started, now_ms()) {
+        (Some(start), Some(end)) => end - start,
+        _ => 0.0,
+    };
+
+    let result = serde_json::json!({
+        "success": dec_key == ct.enc_key,
+        "ciphertext_bytes": ct.compressed_size(),
+        "decrypt_ms": decrypt_ms,
+    });
+
+    Ok(result.to_string())
+}
+
 /// Utility functions for the WASM client
 #[wasm_bindgen]
 pub fn get_version() -> String {
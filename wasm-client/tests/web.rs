@@ -0,0 +1,97 @@
+//! Browser tests for the WASM bindings
+//!
+//! Run with `wasm-pack test --headless --firefox` (or `--chrome`).
+
+#![cfg(target_arch = "wasm32")]
+
+use ark_bls12_381::Bls12_381 as E;
+use ark_ec::pairing::Pairing;
+use ark_poly::univariate::DensePolynomial;
+use ark_serialize::CanonicalSerialize;
+use ark_std::{UniformRand, Zero};
+use silent_threshold_encryption::{
+    encryption::Encapsulation,
+    kzg::KZG10,
+    setup::{AggregateKey, PublicKey, SecretKey},
+};
+use silent_threshold_encryption_wasm::{decrypt_data, encrypt_data, run_demo};
+use wasm_bindgen_test::*;
+
+type Fr = <E as Pairing>::ScalarField;
+type G2 = <E as Pairing>::G2;
+type UniPoly381 = DensePolynomial<Fr>;
+
+fn to_bytes<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
+
+#[wasm_bindgen_test]
+fn encrypt_data_round_trips_utf8() {
+    let mut rng = ark_std::test_rng();
+    let n = 4;
+    let t = 2;
+
+    let tau = Fr::rand(&mut rng);
+    let params = KZG10::<E, UniPoly381>::setup(n, tau).unwrap();
+
+    let mut sk: Vec<SecretKey<E>> = Vec::new();
+    let mut pk: Vec<PublicKey<E>> = Vec::new();
+    for i in 0..n {
+        sk.push(SecretKey::<E>::new(&mut rng));
+        if i == 0 {
+            sk[0].nullify();
+        }
+        pk.push(sk[i].get_pk(i, &params, n).unwrap());
+    }
+    let agg_key = AggregateKey::<E>::new(pk, &params).unwrap();
+    let agg_key_bytes = to_bytes(&agg_key);
+    let params_bytes = to_bytes(&params);
+
+    let message = "threshold 🔐 encryption — héllo wörld";
+    let sealed = encrypt_data(message.as_bytes(), &agg_key_bytes, t, &params_bytes).unwrap();
+
+    let enc = Encapsulation::<E>::deserialize_validated(&sealed.ciphertext()).unwrap();
+    let quorum = |k: usize| {
+        let partial_decryptions = js_sys::Array::new();
+        let selector = js_sys::Array::new();
+        for (i, sk_i) in sk.iter().enumerate() {
+            let selected = i < k;
+            let pd = if selected {
+                sk_i.partial_decryption_encapsulated(&enc)
+            } else {
+                G2::zero()
+            };
+            partial_decryptions.push(&js_sys::Uint8Array::from(&to_bytes(&pd)[..]));
+            selector.push(&selected.into());
+        }
+        (partial_decryptions, selector)
+    };
+    let open = |k: usize| {
+        let (partial_decryptions, selector) = quorum(k);
+        decrypt_data(
+            &sealed.ciphertext(),
+            &sealed.aead_ciphertext(),
+            &sealed.nonce(),
+            &partial_decryptions,
+            &selector,
+            &agg_key_bytes,
+            &params_bytes,
+        )
+    };
+
+    // The returned encapsulation alone does not open the box
+    assert!(open(t).is_err());
+
+    let plaintext = open(t + 1).unwrap();
+    assert_eq!(String::from_utf8(plaintext).unwrap(), message);
+}
+
+#[wasm_bindgen_test]
+fn run_demo_decrypts_with_a_quorum() {
+    let result: serde_json::Value = serde_json::from_str(&run_demo(8, 3).unwrap()).unwrap();
+    assert_eq!(result["success"], true);
+    assert!(result["ciphertext_bytes"].as_u64().unwrap() > 0);
+    assert!(result["decrypt_ms"].as_f64().unwrap() >= 0.0);
+}